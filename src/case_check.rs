@@ -0,0 +1,142 @@
+//! Case-only path collisions for `relocal sync push --check-case`.
+//!
+//! On a case-insensitive filesystem (macOS by default) `Foo.txt` and
+//! `foo.txt` are the same file, while the Linux remote keeps them apart. A
+//! repo whose git index tracks both behaves surprisingly under rsync: only one
+//! exists locally, the other survives or vanishes on the remote depending on
+//! the sync direction. The check enumerates the same file set as
+//! [`checksum`](crate::checksum) and groups paths that differ only in case.
+//!
+//! Like `checksum.rs`, this module only builds commands and parses output.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::rsync::PathFilter;
+
+/// Shell command listing the files a push would consider, NUL-separated.
+///
+/// `dir` must already be shell-quoted (see
+/// [`quote_local_dir`](crate::checksum::quote_local_dir)).
+pub fn list_files_command(dir: &str) -> String {
+    format!("cd {dir} && git ls-files -z --cached --others --exclude-standard")
+}
+
+/// Parses [`list_files_command`] output, dropping paths `filter` excludes
+/// from the sync.
+pub fn parse_file_list(output: &str, filter: &PathFilter) -> Vec<String> {
+    output
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .filter(|path| !filter.is_excluded(path))
+        .map(String::from)
+        .collect()
+}
+
+/// Groups of paths (files or directories) that differ only in case.
+///
+/// Only the topmost collision is reported: `Docs/a` and `docs/a` yield the
+/// single group `["Docs", "docs"]`. Groups and their members are sorted.
+pub fn case_collisions(paths: &[String]) -> Vec<Vec<String>> {
+    // Lowercased prefix -> every spelling of it seen in `paths`.
+    let mut spellings: BTreeMap<String, BTreeSet<&str>> = BTreeMap::new();
+    for path in paths {
+        let ends = path
+            .match_indices('/')
+            .map(|(i, _)| i)
+            .chain(std::iter::once(path.len()));
+        for end in ends {
+            let prefix = &path[..end];
+            spellings
+                .entry(prefix.to_lowercase())
+                .or_default()
+                .insert(prefix);
+        }
+    }
+
+    let colliding: BTreeSet<&String> = spellings
+        .iter()
+        .filter(|(_, names)| names.len() > 1)
+        .map(|(key, _)| key)
+        .collect();
+    spellings
+        .iter()
+        .filter(|(key, names)| names.len() > 1 && !has_colliding_ancestor(key, &colliding))
+        .map(|(_, names)| names.iter().map(|n| n.to_string()).collect())
+        .collect()
+}
+
+/// Whether any parent directory of the lowercased `key` is itself colliding.
+fn has_colliding_ancestor(key: &str, colliding: &BTreeSet<&String>) -> bool {
+    key.match_indices('/')
+        .any(|(i, _)| colliding.contains(&key[..i].to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::rsync::{FilterSet, SyncOptions};
+    use std::path::Path;
+
+    fn paths(list: &[&str]) -> Vec<String> {
+        list.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn list_files_command_format() {
+        assert_eq!(
+            list_files_command("'/tmp/my repo'"),
+            "cd '/tmp/my repo' && git ls-files -z --cached --others --exclude-standard"
+        );
+    }
+
+    #[test]
+    fn parse_file_list_splits_on_nul_and_filters_excludes() {
+        let output = "src/main.rs\0relocal.toml\0.claude/settings.json\0.env\0README.md\0";
+        let mut config = Config::parse("remote = \"user@host\"").unwrap();
+        config.exclude = vec![".env".to_string()];
+        let filter =
+            FilterSet::new(&config, Path::new("/repo"), &SyncOptions::default()).path_filter();
+        let files = parse_file_list(output, &filter);
+        assert_eq!(files, paths(&["src/main.rs", "README.md"]));
+    }
+
+    #[test]
+    fn no_collisions() {
+        let files = paths(&["src/main.rs", "src/lib.rs", "README.md"]);
+        assert!(case_collisions(&files).is_empty());
+    }
+
+    #[test]
+    fn file_collision_detected() {
+        let files = paths(&["README.md", "src/lib.rs", "readme.md"]);
+        assert_eq!(
+            case_collisions(&files),
+            vec![paths(&["README.md", "readme.md"])]
+        );
+    }
+
+    #[test]
+    fn directory_collision_reported_once_at_top() {
+        let files = paths(&["Docs/a.md", "docs/a.md", "docs/b.md"]);
+        assert_eq!(case_collisions(&files), vec![paths(&["Docs", "docs"])]);
+    }
+
+    #[test]
+    fn nested_collisions_in_separate_directories() {
+        let files = paths(&["a/X.rs", "a/x.rs", "b/Y.rs", "b/y.rs", "b/z.rs"]);
+        assert_eq!(
+            case_collisions(&files),
+            vec![paths(&["a/X.rs", "a/x.rs"]), paths(&["b/Y.rs", "b/y.rs"])]
+        );
+    }
+
+    #[test]
+    fn three_way_collision_is_one_group() {
+        let files = paths(&["Makefile", "makefile", "MAKEFILE"]);
+        assert_eq!(
+            case_collisions(&files),
+            vec![paths(&["MAKEFILE", "Makefile", "makefile"])]
+        );
+    }
+}
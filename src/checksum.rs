@@ -0,0 +1,303 @@
+//! Checksum manifests for `relocal sync push --checksum-verify`.
+//!
+//! After a push, both sides produce a `sha256sum`-format manifest of the
+//! synced tree and the two are diffed. File enumeration uses
+//! `git ls-files --cached --others --exclude-standard`, which applies the same
+//! `.gitignore` rules rsync honours via `--filter=:- .gitignore`; the rest of
+//! the sync's rules (see [`PathFilter`]) are applied to the parsed manifests
+//! so both sides are compared over an identical file set.
+//!
+//! Like `rsync.rs`, this module only builds commands and parses output; the
+//! caller runs them through a [`CommandRunner`](crate::runner::CommandRunner).
+
+use std::collections::BTreeMap;
+
+use shell_quote::{Bash, QuoteRefExt};
+
+use crate::rsync::PathFilter;
+
+/// Paths relative to the repo root mapped to their hex SHA-256 digest.
+pub type Manifest = BTreeMap<String, String>;
+
+/// Differences between a local and a remote manifest.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ManifestDiff {
+    /// Present on both sides with different contents.
+    pub mismatched: Vec<String>,
+    /// Present locally but absent on the remote.
+    pub missing_remote: Vec<String>,
+    /// Present on the remote but absent locally.
+    pub extra_remote: Vec<String>,
+}
+
+impl ManifestDiff {
+    /// Returns `true` when both sides are identical.
+    pub fn is_empty(&self) -> bool {
+        self.mismatched.is_empty() && self.missing_remote.is_empty() && self.extra_remote.is_empty()
+    }
+
+    /// Total number of differing paths.
+    pub fn len(&self) -> usize {
+        self.mismatched.len() + self.missing_remote.len() + self.extra_remote.len()
+    }
+}
+
+/// Shell command that prints a `sha256sum` manifest of the tree at `dir`.
+///
+/// `dir` is inserted verbatim so remote paths like `~/relocal/<session>` keep
+/// their tilde expansion; use [`quote_local_dir`] for local paths. Symlinks
+/// are skipped (rsync transfers them as links, not contents) and entries
+/// listed in the index but deleted from the working tree are ignored.
+pub fn manifest_command(dir: &str) -> String {
+    format!(
+        "cd {dir} && git ls-files -z --cached --others --exclude-standard | xargs -0 -r sh -c 'for f; do if [ -f \"$f\" ] && [ ! -L \"$f\" ]; then sha256sum \"$f\" || exit 1; fi; done' sh"
+    )
+}
+
+/// Shell-quotes a local directory path for use with [`manifest_command`].
+pub fn quote_local_dir(dir: &std::path::Path) -> String {
+    dir.to_string_lossy().as_ref().quoted(Bash)
+}
+
+/// Parses `sha256sum` output, dropping paths `filter` excludes from the
+/// sync. Malformed lines are ignored.
+pub fn parse_manifest(output: &str, filter: &PathFilter) -> Manifest {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (hash, path) = line.split_once("  ")?;
+            let path = path.strip_prefix("./").unwrap_or(path);
+            Some((path.to_string(), hash.to_string()))
+        })
+        .filter(|(path, _)| !filter.is_excluded(path))
+        .collect()
+}
+
+/// Compares two manifests. Result lists are sorted by path.
+pub fn diff_manifests(local: &Manifest, remote: &Manifest) -> ManifestDiff {
+    let mut diff = ManifestDiff::default();
+    for (path, hash) in local {
+        match remote.get(path) {
+            Some(remote_hash) if remote_hash == hash => {}
+            Some(_) => diff.mismatched.push(path.clone()),
+            None => diff.missing_remote.push(path.clone()),
+        }
+    }
+    for path in remote.keys() {
+        if !local.contains_key(path) {
+            diff.extra_remote.push(path.clone());
+        }
+    }
+    diff
+}
+
+/// Whether `pattern` matches the first `end` of a file path's `components`,
+/// which name a directory unless they are all of them.
+///
+/// A trailing `/` restricts the match to directories; a leading `/` anchors
+/// at the root; patterns containing no other `/` match a single path
+/// component anywhere.
+pub fn matches_prefix(pattern: &str, components: &[&str], end: usize) -> bool {
+    let (pattern, dir_only) = match pattern.strip_suffix('/') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+    let (pattern, anchored) = match pattern.strip_prefix('/') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+    let pattern_depth = pattern.split('/').count();
+    let is_dir = end < components.len();
+    if dir_only && !is_dir {
+        return false;
+    }
+    let start = if anchored {
+        0
+    } else if pattern_depth > end {
+        return false;
+    } else {
+        end - pattern_depth
+    };
+    if anchored && pattern_depth != end {
+        return false;
+    }
+    glob_match(pattern, &components[start..end].join("/"))
+}
+
+/// Minimal glob: `*` and `?` do not cross `/`, `**` does.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    glob_match_bytes(pattern.as_bytes(), text.as_bytes())
+}
+
+fn glob_match_bytes(p: &[u8], t: &[u8]) -> bool {
+    match p.first() {
+        None => t.is_empty(),
+        Some(b'*') if p.get(1) == Some(&b'*') => {
+            (0..=t.len()).any(|i| glob_match_bytes(&p[2..], &t[i..]))
+        }
+        Some(b'*') => (0..=t.len())
+            .take_while(|&i| i == 0 || t[i - 1] != b'/')
+            .any(|i| glob_match_bytes(&p[1..], &t[i..])),
+        Some(b'?') => t.first().is_some_and(|&c| c != b'/') && glob_match_bytes(&p[1..], &t[1..]),
+        Some(&c) => t.first() == Some(&c) && glob_match_bytes(&p[1..], &t[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::rsync::{FilterSet, SyncOptions};
+
+    /// The filter of a push with the given `exclude` patterns.
+    fn filter(excludes: &[&str]) -> PathFilter {
+        let mut config = Config::parse("remote = \"user@host\"").unwrap();
+        config.exclude = excludes.iter().map(|p| p.to_string()).collect();
+        FilterSet::new(
+            &config,
+            std::path::Path::new("/repo"),
+            &SyncOptions::default(),
+        )
+        .path_filter()
+    }
+
+    const LOCAL: &str = "\
+aaaa  ./src/main.rs
+bbbb  ./README.md
+cccc  ./only-local.txt
+";
+
+    const REMOTE: &str = "\
+aaaa  ./src/main.rs
+ffff  ./README.md
+dddd  ./only-remote.txt
+";
+
+    #[test]
+    fn parse_strips_dot_slash_prefix() {
+        let manifest = parse_manifest("abcd  ./src/lib.rs\n", &filter(&[]));
+        assert_eq!(manifest.get("src/lib.rs").map(String::as_str), Some("abcd"));
+    }
+
+    #[test]
+    fn parse_accepts_paths_without_prefix() {
+        let manifest = parse_manifest("abcd  src/lib.rs\n", &filter(&[]));
+        assert!(manifest.contains_key("src/lib.rs"));
+    }
+
+    #[test]
+    fn parse_ignores_malformed_lines() {
+        let manifest = parse_manifest("garbage\n\nabcd  ok.txt\n", &filter(&[]));
+        assert_eq!(manifest.len(), 1);
+    }
+
+    #[test]
+    fn parse_keeps_spaces_in_paths() {
+        let manifest = parse_manifest("abcd  dir/a file.txt\n", &filter(&[]));
+        assert!(manifest.contains_key("dir/a file.txt"));
+    }
+
+    #[test]
+    fn parse_drops_relocal_internal_paths() {
+        let output =
+            "1  relocal.toml\n2  .claude/settings.json\n3  .relocal/origin.json\n4  keep.rs\n";
+        let manifest = parse_manifest(output, &filter(&[]));
+        assert_eq!(manifest.keys().collect::<Vec<_>>(), vec!["keep.rs"]);
+    }
+
+    #[test]
+    fn parse_only_drops_root_relocal_toml() {
+        let manifest = parse_manifest("1  sub/relocal.toml\n", &filter(&[]));
+        assert!(manifest.contains_key("sub/relocal.toml"));
+    }
+
+    #[test]
+    fn parse_drops_user_excludes() {
+        let excludes = filter(&[".env", "secrets/", "*.log"]);
+        let output = "1  .env\n2  secrets/key\n3  a/b/debug.log\n4  app/.env\n5  keep.rs\n";
+        let manifest = parse_manifest(output, &excludes);
+        assert_eq!(manifest.keys().collect::<Vec<_>>(), vec!["keep.rs"]);
+    }
+
+    #[test]
+    fn identical_manifests_have_empty_diff() {
+        let local = parse_manifest(LOCAL, &filter(&[]));
+        let diff = diff_manifests(&local, &local.clone());
+        assert!(diff.is_empty());
+        assert_eq!(diff.len(), 0);
+    }
+
+    #[test]
+    fn diff_reports_each_category() {
+        let diff = diff_manifests(
+            &parse_manifest(LOCAL, &filter(&[])),
+            &parse_manifest(REMOTE, &filter(&[])),
+        );
+        assert_eq!(
+            diff,
+            ManifestDiff {
+                mismatched: vec!["README.md".to_string()],
+                missing_remote: vec!["only-local.txt".to_string()],
+                extra_remote: vec!["only-remote.txt".to_string()],
+            }
+        );
+        assert_eq!(diff.len(), 3);
+    }
+
+    #[test]
+    fn diff_against_empty_remote_lists_everything_missing() {
+        let diff = diff_manifests(&parse_manifest(LOCAL, &filter(&[])), &Manifest::new());
+        assert_eq!(diff.missing_remote.len(), 3);
+        assert!(diff.mismatched.is_empty());
+        assert!(diff.extra_remote.is_empty());
+    }
+
+    #[test]
+    fn exclude_unanchored_name_matches_any_depth() {
+        assert!(filter(&["node_modules"]).is_excluded("a/node_modules/x.js"));
+        assert!(filter(&["node_modules"]).is_excluded("node_modules"));
+    }
+
+    #[test]
+    fn exclude_trailing_slash_matches_only_directories() {
+        assert!(filter(&["build/"]).is_excluded("build/out.o"));
+        assert!(!filter(&["build/"]).is_excluded("build"));
+    }
+
+    #[test]
+    fn exclude_anchored_matches_only_at_root() {
+        assert!(filter(&["/target"]).is_excluded("target/debug/app"));
+        assert!(!filter(&["/target"]).is_excluded("crates/x/target/debug/app"));
+    }
+
+    #[test]
+    fn exclude_multi_component_pattern() {
+        assert!(filter(&["docs/*.pdf"]).is_excluded("docs/a.pdf"));
+        assert!(filter(&["docs/*.pdf"]).is_excluded("x/docs/a.pdf"));
+        assert!(!filter(&["docs/*.pdf"]).is_excluded("docs/sub/a.pdf"));
+    }
+
+    #[test]
+    fn glob_single_star_does_not_cross_slash() {
+        assert!(glob_match("*.rs", "main.rs"));
+        assert!(!glob_match("*.rs", "src/main.rs"));
+        assert!(glob_match("**.rs", "src/main.rs"));
+        assert!(glob_match("?.txt", "a.txt"));
+        assert!(!glob_match("?.txt", "ab.txt"));
+    }
+
+    #[test]
+    fn manifest_command_lists_via_git_and_hashes() {
+        let cmd = manifest_command("~/relocal/s1");
+        assert!(cmd.starts_with("cd ~/relocal/s1 && "));
+        assert!(cmd.contains("git ls-files -z --cached --others --exclude-standard"));
+        assert!(cmd.contains("sha256sum"));
+    }
+
+    #[test]
+    fn quote_local_dir_escapes_spaces() {
+        let quoted = quote_local_dir(std::path::Path::new("/tmp/my repo"));
+        assert_ne!(quoted, "/tmp/my repo");
+        assert!(quoted.contains("my"));
+    }
+}
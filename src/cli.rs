@@ -3,7 +3,11 @@
 //! This module defines the full command tree. Every subcommand and flag is
 //! declared here; the rest of the codebase receives already-parsed structs.
 
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
+use relocal::commands::install::Step;
+use relocal::reconcile::Side;
 
 /// Run Claude Code remotely, work locally.
 #[derive(Debug, Parser)]
@@ -13,13 +17,33 @@ pub struct Cli {
     #[arg(short, long, action = clap::ArgAction::Count, global = true)]
     pub verbose: u8,
 
+    /// Only print warnings and errors.
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Use this remote instead of the configured one.
+    #[arg(long, global = true, value_name = "USER@HOST")]
+    pub remote: Option<String>,
+
+    /// Read the project config from this file instead of <repo>/relocal.toml.
+    /// The repo root defaults to the file's directory.
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Use this directory as the repo root instead of the current directory.
+    #[arg(long = "repo-root", global = true, value_name = "DIR")]
+    pub root: Option<PathBuf>,
+
     #[command(subcommand)]
     pub command: Command,
 }
 
 impl Cli {
-    /// Maps the verbosity count to a tracing filter level.
+    /// Maps `--quiet` and the verbosity count to a tracing filter level.
     pub fn log_level(&self) -> tracing::Level {
+        if self.quiet {
+            return tracing::Level::WARN;
+        }
         match self.verbose {
             0 => tracing::Level::INFO,
             1 => tracing::Level::DEBUG,
@@ -28,10 +52,26 @@ impl Cli {
     }
 }
 
+/// Whether terminal output should be colored: only on a terminal, and not
+/// when `NO_COLOR` is set to a non-empty value (<https://no-color.org>).
+pub fn color_enabled(no_color: Option<std::ffi::OsString>, is_terminal: bool) -> bool {
+    is_terminal && no_color.is_none_or(|v| v.is_empty())
+}
+
 #[derive(Debug, Subcommand)]
 pub enum Command {
     /// Create a relocal.toml in the current directory.
-    Init,
+    Init {
+        /// Copy settings from another repo's relocal.toml (repo dir or file path).
+        #[arg(long, value_name = "PATH")]
+        from_existing: Option<PathBuf>,
+    },
+
+    /// Inspect relocal.toml.
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
 
     /// Manage the remote environment.
     Remote {
@@ -44,6 +84,26 @@ pub enum Command {
         /// Session name (defaults to <dirname>-<hash>).
         session_name: Option<String>,
 
+        /// Launch claude without --dangerously-skip-permissions.
+        /// Overrides `skip_permissions = true` in config.
+        #[arg(long)]
+        no_skip_permissions: bool,
+
+        /// Delete the remote working copy when the session ends (after the
+        /// final pull). Only applies when this command starts the session.
+        #[arg(long)]
+        ephemeral: bool,
+
+        /// Skip the initial push if the remote working copy already exists.
+        /// Only applies when this command starts the session.
+        #[arg(long)]
+        no_initial_push: bool,
+
+        /// Run claude in a tmux session on the remote and return; the session
+        /// keeps syncing until claude exits. Reconnect with `relocal attach`.
+        #[arg(long)]
+        detach: bool,
+
         /// Extra arguments passed through to `claude` (after `--`).
         #[arg(last = true)]
         claude_args: Vec<String>,
@@ -54,27 +114,95 @@ pub enum Command {
         /// Session name (defaults to <dirname>-<hash>).
         session_name: Option<String>,
 
+        /// Launch codex without --yolo.
+        /// Overrides `skip_permissions = true` in config.
+        #[arg(long)]
+        no_skip_permissions: bool,
+
+        /// Delete the remote working copy when the session ends (after the
+        /// final pull). Only applies when this command starts the session.
+        #[arg(long)]
+        ephemeral: bool,
+
+        /// Skip the initial push if the remote working copy already exists.
+        /// Only applies when this command starts the session.
+        #[arg(long)]
+        no_initial_push: bool,
+
         /// Extra arguments passed through to `codex` (after `--`).
         #[arg(last = true)]
         codex_args: Vec<String>,
     },
 
+    /// Reattach to a session started with `relocal claude --detach`.
+    Attach {
+        /// Session name (defaults to <dirname>-<hash>).
+        session_name: Option<String>,
+    },
+
     /// Open an interactive SSH shell in the remote session directory.
     Ssh {
         /// Session name (defaults to <dirname>-<hash>).
         session_name: Option<String>,
     },
 
+    /// Run a command in the remote session directory.
+    Exec {
+        /// Session name (defaults to <dirname>-<hash>).
+        session_name: Option<String>,
+
+        /// Command and arguments to run (after `--`).
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
+
+    /// Push to the remote whenever local files change, until Ctrl-C.
+    Watch {
+        /// Session name (defaults to <dirname>-<hash>).
+        session_name: Option<String>,
+    },
+
     /// Manually sync files between local and remote.
+    ///
+    /// Without a subcommand, syncs each changed file from the side that
+    /// changed it since the last `relocal sync`, and reports files changed
+    /// on both sides instead of overwriting either.
+    #[command(args_conflicts_with_subcommands = true)]
     Sync {
         #[command(subcommand)]
-        command: SyncCommand,
+        command: Option<SyncCommand>,
+
+        /// Resolve conflicts by taking this side's version: local or remote.
+        #[arg(long, value_name = "SIDE", value_parser = relocal::reconcile::parse_side)]
+        prefer: Option<Side>,
+
+        /// Push then pull with rsync --update: never overwrite a newer file,
+        /// never delete. Not a merge.
+        #[arg(long, conflicts_with = "prefer")]
+        bidirectional_safe: bool,
+
+        /// Session name (defaults to <dirname>-<hash>).
+        session_name: Option<String>,
+    },
+
+    /// Time repeated dry-run pushes to measure sync round-trip latency.
+    Benchmark {
+        /// Session name (defaults to <dirname>-<hash>).
+        session_name: Option<String>,
+
+        /// Number of runs to time.
+        #[arg(short = 'n', long, default_value_t = 5, value_parser = clap::value_parser!(u32).range(1..))]
+        count: u32,
     },
 
     /// Show session status.
     Status {
         /// Session name (defaults to <dirname>-<hash>).
         session_name: Option<String>,
+
+        /// Print status as a JSON object on stdout.
+        #[arg(long)]
+        json: bool,
     },
 
     /// Tail the daemon log for a session.
@@ -83,13 +211,85 @@ pub enum Command {
         session_name: Option<String>,
     },
 
+    /// Print shell export lines for a session (`eval "$(relocal env)"`).
+    Env {
+        /// Session name (defaults to <dirname>-<hash>).
+        session_name: Option<String>,
+    },
+
+    /// Show which user and host the configured remote actually reaches.
+    Whoami,
+
     /// List all sessions on the remote.
-    List,
+    List {
+        /// Also show where each session was started from.
+        #[arg(long)]
+        detail: bool,
+
+        /// Print sessions as a JSON array on stdout.
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List the sessions this machine has started (local registry).
+    Sessions {
+        #[command(subcommand)]
+        command: Option<SessionsCommand>,
+    },
 
     /// Remove a session's remote working copy.
     Destroy {
         /// Session name (defaults to <dirname>-<hash>).
         session_name: Option<String>,
+
+        /// Run even if the remote is not in `allowed_remotes`.
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Adopt an existing remote directory as a session and pull it into the
+    /// current (empty) directory, without pushing.
+    ImportSession {
+        /// Directory on the remote; relative paths start at the remote home.
+        remote_path: String,
+        /// Session name (defaults to <dirname>-<hash>).
+        session_name: Option<String>,
+    },
+
+    /// Rename a session on the remote, keeping its working copy.
+    Rename {
+        /// Current session name.
+        old: String,
+        /// New session name.
+        new: String,
+    },
+
+    /// Remove sessions on the remote that have not been touched for a while.
+    Prune {
+        /// Minimum age to prune, e.g. `7d`, `12h` or `30m` (a bare number is days).
+        #[arg(long, value_name = "AGE", value_parser = relocal::commands::prune::parse_age)]
+        older_than: u64,
+
+        /// Also prune locked (possibly active) sessions.
+        #[arg(long)]
+        include_locked: bool,
+
+        /// Run even if the remote is not in `allowed_remotes`.
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Check the remote environment and suggest fixes.
+    Doctor {
+        /// Repair what can be repaired safely before checking.
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Print a shell completion script to stdout.
+    Completions {
+        /// Shell to generate completions for.
+        shell: clap_complete::Shell,
     },
 
     /// Internal daemon process (not user-facing).
@@ -99,28 +299,174 @@ pub enum Command {
         session_name: String,
         /// Absolute path to the repo root.
         repo_root: String,
+        /// Remove the remote working copy at shutdown.
+        #[arg(long)]
+        ephemeral: bool,
+        /// Skip the initial push if the remote working copy exists.
+        #[arg(long)]
+        no_initial_push: bool,
     },
 }
 
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Validate relocal.toml without connecting to the remote.
+    Check,
+}
+
 #[derive(Debug, Subcommand)]
 pub enum RemoteCommand {
     /// Install the full environment on the remote host.
-    Install,
+    Install {
+        /// Run only these steps (comma-separated, e.g. `claude,claude-auth`).
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_name = "STEPS",
+            value_parser = relocal::commands::install::parse_step
+        )]
+        only: Vec<Step>,
+
+        /// Skip these steps (comma-separated, e.g. `packages,rust`).
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_name = "STEPS",
+            value_parser = relocal::commands::install::parse_step
+        )]
+        skip: Vec<Step>,
+    },
     /// Delete everything under ~/relocal/ on the remote.
-    Nuke,
+    Nuke {
+        /// Run even if the remote is not in `allowed_remotes`.
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SessionsCommand {
+    /// Remove a session from the local registry (the remote is untouched).
+    Forget {
+        /// Session name.
+        name: String,
+    },
 }
 
 #[derive(Debug, Subcommand)]
 pub enum SyncCommand {
+    /// Copy one session's remote state into another session on the same remote.
+    Copy {
+        /// Session to copy from.
+        from: String,
+        /// Session to overwrite.
+        to: String,
+    },
     /// Push local files to the remote.
     Push {
         /// Session name (defaults to <dirname>-<hash>).
         session_name: Option<String>,
+
+        /// Show every change this push would make, without syncing.
+        #[arg(long, conflicts_with = "delete_dry_run")]
+        dry_run: bool,
+
+        /// List the remote files this push would delete, without syncing.
+        #[arg(long)]
+        delete_dry_run: bool,
+
+        /// After pushing, compare sha256 manifests of both sides (slow).
+        #[arg(long, conflicts_with_all = ["delete_dry_run", "dry_run"])]
+        checksum_verify: bool,
+
+        /// Keep partial files so an interrupted transfer can resume.
+        #[arg(long)]
+        resume: bool,
+
+        /// Keep remote files that do not exist locally (omit rsync --delete).
+        #[arg(long)]
+        no_delete: bool,
+
+        /// Warn about local paths that differ only in case before pushing.
+        #[arg(long)]
+        check_case: bool,
+
+        /// Don't cross filesystem boundaries (rsync -x).
+        #[arg(short = 'x', long)]
+        one_file_system: bool,
+
+        /// Compare files by content instead of mtime and size (rsync -c).
+        #[arg(short = 'c', long)]
+        checksum: bool,
+
+        /// Print transfer stats as JSON on stdout after syncing.
+        #[arg(long, conflicts_with_all = ["delete_dry_run", "dry_run"])]
+        stats_json: bool,
+
+        /// Also sync paths matching patterns in this file (rsync --include-from).
+        #[arg(long, value_name = "FILE")]
+        include_from: Option<PathBuf>,
+
+        /// Also skip paths matching patterns in this file (rsync --exclude-from).
+        #[arg(long, value_name = "FILE")]
+        exclude_from: Option<PathBuf>,
+
+        /// Print each include/exclude rule with its source, in precedence order, without syncing.
+        #[arg(long)]
+        show_filters: bool,
+
+        /// Skip files identical to this remote reference tree (rsync --compare-dest).
+        #[arg(long, value_name = "REMOTE_DIR", conflicts_with = "checksum_verify")]
+        compare_dest: Option<String>,
     },
     /// Pull remote files to local.
     Pull {
         /// Session name (defaults to <dirname>-<hash>).
         session_name: Option<String>,
+
+        /// Show every change this pull would make, without syncing.
+        #[arg(long, conflicts_with = "delete_dry_run")]
+        dry_run: bool,
+
+        /// List the local files this pull would delete, without syncing.
+        #[arg(long)]
+        delete_dry_run: bool,
+
+        /// Keep partial files so an interrupted transfer can resume.
+        #[arg(long)]
+        resume: bool,
+
+        /// Pull into this directory instead of the repo root.
+        #[arg(long, value_name = "DIR")]
+        local_path: Option<PathBuf>,
+
+        /// Allow a --local-path that is not a repo root (its contents are replaced).
+        #[arg(long, requires = "local_path")]
+        force: bool,
+
+        /// Don't cross filesystem boundaries (rsync -x).
+        #[arg(short = 'x', long)]
+        one_file_system: bool,
+
+        /// Compare files by content instead of mtime and size (rsync -c).
+        #[arg(short = 'c', long)]
+        checksum: bool,
+
+        /// Print transfer stats as JSON on stdout after syncing.
+        #[arg(long, conflicts_with_all = ["delete_dry_run", "dry_run"])]
+        stats_json: bool,
+
+        /// Also sync paths matching patterns in this file (rsync --include-from).
+        #[arg(long, value_name = "FILE")]
+        include_from: Option<PathBuf>,
+
+        /// Also skip paths matching patterns in this file (rsync --exclude-from).
+        #[arg(long, value_name = "FILE")]
+        exclude_from: Option<PathBuf>,
+
+        /// Print each include/exclude rule with its source, in precedence order, without syncing.
+        #[arg(long)]
+        show_filters: bool,
     },
 }
 
@@ -128,35 +474,101 @@ pub enum SyncCommand {
 mod tests {
     use super::*;
     use clap::Parser;
+    use std::path::Path;
 
     fn parse(args: &[&str]) -> Cli {
         Cli::parse_from(args)
     }
 
+    #[test]
+    fn remote_flag_is_global() {
+        let cli = parse(&["relocal", "status", "--remote", "me@vm"]);
+        assert_eq!(cli.remote.as_deref(), Some("me@vm"));
+        let cli = parse(&["relocal", "--remote", "me@vm", "sync", "push"]);
+        assert_eq!(cli.remote.as_deref(), Some("me@vm"));
+        assert!(parse(&["relocal", "status"]).remote.is_none());
+    }
+
+    #[test]
+    fn config_and_repo_root_flags_are_global() {
+        let cli = parse(&[
+            "relocal",
+            "sync",
+            "push",
+            "--config",
+            "/etc/api.toml",
+            "--repo-root",
+            "/work/api",
+        ]);
+        assert_eq!(cli.config, Some(PathBuf::from("/etc/api.toml")));
+        assert_eq!(cli.root, Some(PathBuf::from("/work/api")));
+        let cli = parse(&["relocal", "status"]);
+        assert!(cli.config.is_none() && cli.root.is_none());
+    }
+
     #[test]
     fn init() {
         let cli = parse(&["relocal", "init"]);
-        assert!(matches!(cli.command, Command::Init));
+        assert!(matches!(
+            cli.command,
+            Command::Init {
+                from_existing: None
+            }
+        ));
     }
 
     #[test]
     fn remote_install() {
         let cli = parse(&["relocal", "remote", "install"]);
+        match cli.command {
+            Command::Remote {
+                command: RemoteCommand::Install { only, skip },
+            } => assert!(only.is_empty() && skip.is_empty()),
+            other => panic!("expected Remote Install, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn remote_install_only_and_skip() {
+        let cli = parse(&[
+            "relocal",
+            "remote",
+            "install",
+            "--only",
+            "claude,claude-auth",
+            "--skip",
+            "rust",
+        ]);
+        match cli.command {
+            Command::Remote {
+                command: RemoteCommand::Install { only, skip },
+            } => {
+                assert_eq!(only, vec![Step::Claude, Step::ClaudeAuth]);
+                assert_eq!(skip, vec![Step::Rust]);
+            }
+            other => panic!("expected Remote Install, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(["relocal", "remote", "install", "--only", "hooks"]).is_err());
+    }
+
+    #[test]
+    fn remote_nuke() {
+        let cli = parse(&["relocal", "remote", "nuke"]);
         assert!(matches!(
             cli.command,
             Command::Remote {
-                command: RemoteCommand::Install
+                command: RemoteCommand::Nuke { force: false }
             }
         ));
     }
 
     #[test]
-    fn remote_nuke() {
-        let cli = parse(&["relocal", "remote", "nuke"]);
+    fn remote_nuke_force() {
+        let cli = parse(&["relocal", "remote", "nuke", "--force"]);
         assert!(matches!(
             cli.command,
             Command::Remote {
-                command: RemoteCommand::Nuke
+                command: RemoteCommand::Nuke { force: true }
             }
         ));
     }
@@ -168,6 +580,7 @@ mod tests {
             Command::Claude {
                 session_name,
                 claude_args,
+                ..
             } => {
                 assert!(session_name.is_none());
                 assert!(claude_args.is_empty());
@@ -183,6 +596,7 @@ mod tests {
             Command::Claude {
                 session_name,
                 claude_args,
+                ..
             } => {
                 assert_eq!(session_name.as_deref(), Some("my-session"));
                 assert!(claude_args.is_empty());
@@ -198,6 +612,7 @@ mod tests {
             Command::Claude {
                 session_name,
                 claude_args,
+                ..
             } => {
                 assert!(session_name.is_none());
                 assert_eq!(claude_args, &["--debug"]);
@@ -220,6 +635,7 @@ mod tests {
             Command::Claude {
                 session_name,
                 claude_args,
+                ..
             } => {
                 assert_eq!(session_name.as_deref(), Some("my-session"));
                 assert_eq!(claude_args, &["--debug", "--resume"]);
@@ -228,6 +644,50 @@ mod tests {
         }
     }
 
+    #[test]
+    fn claude_no_skip_permissions() {
+        let cli = parse(&["relocal", "claude", "--no-skip-permissions", "s1"]);
+        match &cli.command {
+            Command::Claude {
+                session_name,
+                no_skip_permissions,
+                ..
+            } => {
+                assert_eq!(session_name.as_deref(), Some("s1"));
+                assert!(no_skip_permissions);
+            }
+            _ => panic!("expected Claude"),
+        }
+    }
+
+    #[test]
+    fn claude_ephemeral() {
+        let cli = parse(&["relocal", "claude", "--ephemeral", "s1"]);
+        match &cli.command {
+            Command::Claude {
+                session_name,
+                ephemeral,
+                ..
+            } => {
+                assert_eq!(session_name.as_deref(), Some("s1"));
+                assert!(ephemeral);
+            }
+            _ => panic!("expected Claude"),
+        }
+    }
+
+    #[test]
+    fn claude_not_ephemeral_by_default() {
+        let cli = parse(&["relocal", "claude", "s1"]);
+        assert!(matches!(
+            cli.command,
+            Command::Claude {
+                ephemeral: false,
+                ..
+            }
+        ));
+    }
+
     #[test]
     fn codex_no_session() {
         let cli = parse(&["relocal", "codex"]);
@@ -235,6 +695,7 @@ mod tests {
             Command::Codex {
                 session_name,
                 codex_args,
+                ..
             } => {
                 assert!(session_name.is_none());
                 assert!(codex_args.is_empty());
@@ -250,6 +711,7 @@ mod tests {
             Command::Codex {
                 session_name,
                 codex_args,
+                ..
             } => {
                 assert_eq!(session_name.as_deref(), Some("my-session"));
                 assert!(codex_args.is_empty());
@@ -265,6 +727,7 @@ mod tests {
             Command::Codex {
                 session_name,
                 codex_args,
+                ..
             } => {
                 assert!(session_name.is_none());
                 assert_eq!(codex_args, &["--model", "o3"]);
@@ -280,6 +743,7 @@ mod tests {
             Command::Codex {
                 session_name,
                 codex_args,
+                ..
             } => {
                 assert_eq!(session_name.as_deref(), Some("my-session"));
                 assert_eq!(codex_args, &["--model", "o3"]);
@@ -288,6 +752,60 @@ mod tests {
         }
     }
 
+    #[test]
+    fn claude_no_initial_push() {
+        let cli = parse(&["relocal", "claude", "--no-initial-push"]);
+        assert!(matches!(
+            cli.command,
+            Command::Claude {
+                no_initial_push: true,
+                ephemeral: false,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn codex_no_initial_push() {
+        let cli = parse(&["relocal", "codex", "--no-initial-push", "s1"]);
+        assert!(matches!(
+            cli.command,
+            Command::Codex {
+                no_initial_push: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn codex_ephemeral() {
+        let cli = parse(&["relocal", "codex", "--ephemeral"]);
+        assert!(matches!(
+            cli.command,
+            Command::Codex {
+                ephemeral: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn claude_detach() {
+        let cli = parse(&["relocal", "claude", "--detach", "s1"]);
+        assert!(matches!(cli.command, Command::Claude { detach: true, .. }));
+        let cli = parse(&["relocal", "claude"]);
+        assert!(matches!(cli.command, Command::Claude { detach: false, .. }));
+    }
+
+    #[test]
+    fn attach_with_session() {
+        let cli = parse(&["relocal", "attach", "s1"]);
+        match &cli.command {
+            Command::Attach { session_name } => assert_eq!(session_name.as_deref(), Some("s1")),
+            _ => panic!("expected Attach"),
+        }
+    }
+
     #[test]
     fn ssh_no_session() {
         let cli = parse(&["relocal", "ssh"]);
@@ -305,23 +823,67 @@ mod tests {
         }
     }
 
+    #[test]
+    fn import_session_args() {
+        let cli = parse(&["relocal", "import-session", "~/work/app", "s1"]);
+        match cli.command {
+            Command::ImportSession {
+                remote_path,
+                session_name,
+            } => {
+                assert_eq!(remote_path, "~/work/app");
+                assert_eq!(session_name.as_deref(), Some("s1"));
+            }
+            other => panic!("expected ImportSession, got {other:?}"),
+        }
+    }
+
     #[test]
     fn sync_push_no_session() {
         let cli = parse(&["relocal", "sync", "push"]);
         assert!(matches!(
             cli.command,
             Command::Sync {
-                command: SyncCommand::Push { session_name: None }
+                command: Some(SyncCommand::Push {
+                    session_name: None,
+                    dry_run: false,
+                    delete_dry_run: false,
+                    checksum_verify: false,
+                    resume: false,
+                    no_delete: false,
+                    check_case: false,
+                    one_file_system: false,
+                    checksum: false,
+                    stats_json: false,
+                    include_from: None,
+                    exclude_from: None,
+                    compare_dest: None,
+                    show_filters: false,
+                }),
+                ..
             }
         ));
     }
 
+    #[test]
+    fn sync_push_compare_dest() {
+        let cli = parse(&["relocal", "sync", "push", "--compare-dest", "/srv/ref"]);
+        match &cli.command {
+            Command::Sync {
+                command: Some(SyncCommand::Push { compare_dest, .. }),
+                ..
+            } => assert_eq!(compare_dest.as_deref(), Some("/srv/ref")),
+            _ => panic!("expected Sync Push"),
+        }
+    }
+
     #[test]
     fn sync_push_with_session() {
         let cli = parse(&["relocal", "sync", "push", "s1"]);
         match &cli.command {
             Command::Sync {
-                command: SyncCommand::Push { session_name },
+                command: Some(SyncCommand::Push { session_name, .. }),
+                ..
             } => assert_eq!(session_name.as_deref(), Some("s1")),
             _ => panic!("expected Sync Push"),
         }
@@ -333,76 +895,405 @@ mod tests {
         assert!(matches!(
             cli.command,
             Command::Sync {
-                command: SyncCommand::Pull { session_name: None }
+                command: Some(SyncCommand::Pull {
+                    session_name: None,
+                    dry_run: false,
+                    delete_dry_run: false,
+                    resume: false,
+                    local_path: None,
+                    force: false,
+                    one_file_system: false,
+                    checksum: false,
+                    stats_json: false,
+                    include_from: None,
+                    exclude_from: None,
+                    show_filters: false,
+                }),
+                ..
             }
         ));
     }
 
     #[test]
-    fn status_no_session() {
-        let cli = parse(&["relocal", "status"]);
-        assert!(matches!(
-            cli.command,
-            Command::Status { session_name: None }
-        ));
-    }
-
-    #[test]
-    fn status_with_session() {
-        let cli = parse(&["relocal", "status", "s1"]);
+    fn sync_push_delete_dry_run() {
+        let cli = parse(&["relocal", "sync", "push", "--delete-dry-run", "s1"]);
         match &cli.command {
-            Command::Status { session_name } => {
+            Command::Sync {
+                command:
+                    Some(SyncCommand::Push {
+                        session_name,
+                        delete_dry_run,
+                        ..
+                    }),
+                ..
+            } => {
                 assert_eq!(session_name.as_deref(), Some("s1"));
+                assert!(delete_dry_run);
             }
-            _ => panic!("expected Status"),
+            _ => panic!("expected Sync Push"),
         }
     }
 
     #[test]
-    fn list() {
-        let cli = parse(&["relocal", "list"]);
-        assert!(matches!(cli.command, Command::List));
-    }
-
-    #[test]
-    fn destroy_no_session() {
-        let cli = parse(&["relocal", "destroy"]);
+    fn sync_push_checksum_verify() {
+        let cli = parse(&["relocal", "sync", "push", "--checksum-verify"]);
         assert!(matches!(
             cli.command,
-            Command::Destroy { session_name: None }
+            Command::Sync {
+                command: Some(SyncCommand::Push {
+                    checksum_verify: true,
+                    ..
+                }),
+                ..
+            }
         ));
     }
 
     #[test]
-    fn destroy_with_session() {
-        let cli = parse(&["relocal", "destroy", "s1"]);
-        match &cli.command {
-            Command::Destroy { session_name } => {
-                assert_eq!(session_name.as_deref(), Some("s1"));
-            }
-            _ => panic!("expected Destroy"),
+    fn sync_one_file_system_long_and_short() {
+        for flag in ["--one-file-system", "-x"] {
+            let cli = parse(&["relocal", "sync", "push", flag]);
+            assert!(matches!(
+                cli.command,
+                Command::Sync {
+                    command: Some(SyncCommand::Push {
+                        one_file_system: true,
+                        ..
+                    }),
+                    ..
+                }
+            ));
+            let cli = parse(&["relocal", "sync", "pull", flag]);
+            assert!(matches!(
+                cli.command,
+                Command::Sync {
+                    command: Some(SyncCommand::Pull {
+                        one_file_system: true,
+                        ..
+                    }),
+                    ..
+                }
+            ));
         }
     }
 
     #[test]
-    fn log_no_session() {
-        let cli = parse(&["relocal", "log"]);
-        assert!(matches!(cli.command, Command::Log { session_name: None }));
+    fn sync_checksum_long_and_short() {
+        for flag in ["--checksum", "-c"] {
+            let cli = parse(&["relocal", "sync", "push", flag]);
+            assert!(matches!(
+                cli.command,
+                Command::Sync {
+                    command: Some(SyncCommand::Push { checksum: true, .. }),
+                    ..
+                }
+            ));
+            let cli = parse(&["relocal", "sync", "pull", flag]);
+            assert!(matches!(
+                cli.command,
+                Command::Sync {
+                    command: Some(SyncCommand::Pull { checksum: true, .. }),
+                    ..
+                }
+            ));
+        }
     }
 
     #[test]
-    fn log_with_session() {
-        let cli = parse(&["relocal", "log", "s1"]);
-        match &cli.command {
-            Command::Log { session_name } => {
-                assert_eq!(session_name.as_deref(), Some("s1"));
-            }
-            _ => panic!("expected Log"),
+    fn sync_stats_json() {
+        for sub in ["push", "pull"] {
+            let cli = parse(&["relocal", "sync", sub, "--stats-json"]);
+            assert!(matches!(
+                cli.command,
+                Command::Sync {
+                    command: Some(
+                        SyncCommand::Push {
+                            stats_json: true,
+                            ..
+                        } | SyncCommand::Pull {
+                            stats_json: true,
+                            ..
+                        }
+                    ),
+                    ..
+                }
+            ));
         }
     }
 
     #[test]
-    fn verbosity_default_info() {
+    fn sync_stats_json_conflicts_with_dry_run() {
+        let result = Cli::try_parse_from(["relocal", "sync", "pull", "--stats-json", "--dry-run"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn sync_show_filters() {
+        for sub in ["push", "pull"] {
+            let cli = parse(&["relocal", "sync", sub, "--show-filters"]);
+            match cli.command {
+                Command::Sync {
+                    command:
+                        Some(
+                            SyncCommand::Push { show_filters, .. }
+                            | SyncCommand::Pull { show_filters, .. },
+                        ),
+                    ..
+                } => assert!(show_filters),
+                other => panic!("expected Sync Push or Pull, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn sync_filter_file_flags() {
+        for sub in ["push", "pull"] {
+            let cli = parse(&[
+                "relocal",
+                "sync",
+                sub,
+                "--include-from",
+                "keep.txt",
+                "--exclude-from",
+                "skip.txt",
+            ]);
+            let Command::Sync {
+                command: Some(command),
+                ..
+            } = cli.command
+            else {
+                panic!("expected Sync");
+            };
+            let (include_from, exclude_from) = match command {
+                SyncCommand::Push {
+                    include_from,
+                    exclude_from,
+                    ..
+                }
+                | SyncCommand::Pull {
+                    include_from,
+                    exclude_from,
+                    ..
+                } => (include_from, exclude_from),
+                other => panic!("expected Push or Pull, got {other:?}"),
+            };
+            assert_eq!(include_from.as_deref(), Some(Path::new("keep.txt")));
+            assert_eq!(exclude_from.as_deref(), Some(Path::new("skip.txt")));
+        }
+    }
+
+    #[test]
+    fn sync_push_check_case() {
+        let cli = parse(&["relocal", "sync", "push", "--check-case"]);
+        assert!(matches!(
+            cli.command,
+            Command::Sync {
+                command: Some(SyncCommand::Push {
+                    check_case: true,
+                    ..
+                }),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn sync_push_checksum_verify_conflicts_with_delete_dry_run() {
+        let result = Cli::try_parse_from([
+            "relocal",
+            "sync",
+            "push",
+            "--checksum-verify",
+            "--delete-dry-run",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn sync_push_compare_dest_conflicts_with_checksum_verify() {
+        let result = Cli::try_parse_from([
+            "relocal",
+            "sync",
+            "push",
+            "--compare-dest",
+            "/srv/ref",
+            "--checksum-verify",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn sync_pull_delete_dry_run() {
+        let cli = parse(&["relocal", "sync", "pull", "--delete-dry-run"]);
+        assert!(matches!(
+            cli.command,
+            Command::Sync {
+                command: Some(SyncCommand::Pull {
+                    session_name: None,
+                    dry_run: false,
+                    delete_dry_run: true,
+                    resume: false,
+                    local_path: None,
+                    force: false,
+                    one_file_system: false,
+                    checksum: false,
+                    stats_json: false,
+                    include_from: None,
+                    exclude_from: None,
+                    show_filters: false,
+                }),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn status_no_session() {
+        let cli = parse(&["relocal", "status"]);
+        assert!(matches!(
+            cli.command,
+            Command::Status {
+                session_name: None,
+                json: false
+            }
+        ));
+    }
+
+    #[test]
+    fn status_with_session() {
+        let cli = parse(&["relocal", "status", "s1"]);
+        match &cli.command {
+            Command::Status { session_name, .. } => {
+                assert_eq!(session_name.as_deref(), Some("s1"));
+            }
+            _ => panic!("expected Status"),
+        }
+    }
+
+    #[test]
+    fn status_json() {
+        let cli = parse(&["relocal", "status", "--json"]);
+        assert!(matches!(
+            cli.command,
+            Command::Status {
+                session_name: None,
+                json: true
+            }
+        ));
+    }
+
+    #[test]
+    fn list() {
+        let cli = parse(&["relocal", "list"]);
+        assert!(matches!(
+            cli.command,
+            Command::List {
+                detail: false,
+                json: false
+            }
+        ));
+    }
+
+    #[test]
+    fn sessions_lists_by_default() {
+        let cli = parse(&["relocal", "sessions"]);
+        assert!(matches!(cli.command, Command::Sessions { command: None }));
+    }
+
+    #[test]
+    fn sessions_forget() {
+        let cli = parse(&["relocal", "sessions", "forget", "s1"]);
+        match &cli.command {
+            Command::Sessions {
+                command: Some(SessionsCommand::Forget { name }),
+            } => assert_eq!(name, "s1"),
+            _ => panic!("expected Sessions Forget"),
+        }
+    }
+
+    #[test]
+    fn completions_shell() {
+        let cli = parse(&["relocal", "completions", "zsh"]);
+        assert!(matches!(
+            cli.command,
+            Command::Completions {
+                shell: clap_complete::Shell::Zsh
+            }
+        ));
+        assert!(Cli::try_parse_from(["relocal", "completions", "tcsh"]).is_err());
+    }
+
+    #[test]
+    fn doctor() {
+        let cli = parse(&["relocal", "doctor"]);
+        assert!(matches!(cli.command, Command::Doctor { fix: false }));
+    }
+
+    #[test]
+    fn doctor_fix() {
+        let cli = parse(&["relocal", "doctor", "--fix"]);
+        assert!(matches!(cli.command, Command::Doctor { fix: true }));
+    }
+
+    #[test]
+    fn destroy_no_session() {
+        let cli = parse(&["relocal", "destroy"]);
+        assert!(matches!(
+            cli.command,
+            Command::Destroy {
+                session_name: None,
+                force: false
+            }
+        ));
+    }
+
+    #[test]
+    fn destroy_force() {
+        let cli = parse(&["relocal", "destroy", "s1", "--force"]);
+        assert!(matches!(cli.command, Command::Destroy { force: true, .. }));
+    }
+
+    #[test]
+    fn destroy_with_session() {
+        let cli = parse(&["relocal", "destroy", "s1"]);
+        match &cli.command {
+            Command::Destroy { session_name, .. } => {
+                assert_eq!(session_name.as_deref(), Some("s1"));
+            }
+            _ => panic!("expected Destroy"),
+        }
+    }
+
+    #[test]
+    fn env_with_session() {
+        let cli = parse(&["relocal", "env", "s1"]);
+        match &cli.command {
+            Command::Env { session_name } => {
+                assert_eq!(session_name.as_deref(), Some("s1"));
+            }
+            _ => panic!("expected Env"),
+        }
+    }
+
+    #[test]
+    fn log_no_session() {
+        let cli = parse(&["relocal", "log"]);
+        assert!(matches!(cli.command, Command::Log { session_name: None }));
+    }
+
+    #[test]
+    fn log_with_session() {
+        let cli = parse(&["relocal", "log", "s1"]);
+        match &cli.command {
+            Command::Log { session_name } => {
+                assert_eq!(session_name.as_deref(), Some("s1"));
+            }
+            _ => panic!("expected Log"),
+        }
+    }
+
+    #[test]
+    fn verbosity_default_info() {
         let cli = parse(&["relocal", "init"]);
         assert_eq!(cli.verbose, 0);
         assert_eq!(cli.log_level(), tracing::Level::INFO);
@@ -422,12 +1313,139 @@ mod tests {
         assert_eq!(cli.log_level(), tracing::Level::TRACE);
     }
 
+    #[test]
+    fn quiet_warn() {
+        let cli = parse(&["relocal", "sync", "push", "-q"]);
+        assert!(cli.quiet);
+        assert_eq!(cli.log_level(), tracing::Level::WARN);
+    }
+
+    #[test]
+    fn quiet_conflicts_with_verbose() {
+        assert!(Cli::try_parse_from(["relocal", "-q", "-v", "init"]).is_err());
+    }
+
+    #[test]
+    fn color_only_on_terminal_without_no_color() {
+        assert!(color_enabled(None, true));
+        assert!(!color_enabled(None, false));
+        assert!(!color_enabled(Some("1".into()), true));
+        // An empty NO_COLOR does not disable color.
+        assert!(color_enabled(Some("".into()), true));
+    }
+
     #[test]
     fn verbosity_after_subcommand() {
         let cli = parse(&["relocal", "claude", "-vv"]);
         assert_eq!(cli.verbose, 2);
     }
 
+    #[test]
+    fn rename_takes_old_and_new() {
+        let cli = parse(&["relocal", "rename", "a", "b"]);
+        match &cli.command {
+            Command::Rename { old, new } => {
+                assert_eq!(old, "a");
+                assert_eq!(new, "b");
+            }
+            _ => panic!("expected Rename"),
+        }
+    }
+
+    #[test]
+    fn benchmark_defaults() {
+        let cli = parse(&["relocal", "benchmark"]);
+        assert!(matches!(
+            cli.command,
+            Command::Benchmark {
+                session_name: None,
+                count: 5
+            }
+        ));
+    }
+
+    #[test]
+    fn benchmark_count() {
+        let cli = parse(&["relocal", "benchmark", "s1", "-n", "20"]);
+        match cli.command {
+            Command::Benchmark {
+                session_name,
+                count,
+            } => {
+                assert_eq!(session_name.as_deref(), Some("s1"));
+                assert_eq!(count, 20);
+            }
+            _ => panic!("expected Benchmark"),
+        }
+    }
+
+    #[test]
+    fn benchmark_rejects_zero_count() {
+        assert!(Cli::try_parse_from(["relocal", "benchmark", "-n", "0"]).is_err());
+    }
+
+    #[test]
+    fn whoami() {
+        let cli = parse(&["relocal", "whoami"]);
+        assert!(matches!(cli.command, Command::Whoami));
+    }
+
+    #[test]
+    fn config_check() {
+        let cli = parse(&["relocal", "config", "check"]);
+        assert!(matches!(
+            cli.command,
+            Command::Config {
+                command: ConfigCommand::Check
+            }
+        ));
+    }
+
+    #[test]
+    fn prune_older_than() {
+        let cli = parse(&["relocal", "prune", "--older-than", "7d"]);
+        assert!(matches!(
+            cli.command,
+            Command::Prune {
+                older_than: 604_800,
+                include_locked: false,
+                force: false
+            }
+        ));
+    }
+
+    #[test]
+    fn prune_force() {
+        let cli = parse(&["relocal", "prune", "--older-than", "12h", "--force"]);
+        assert!(matches!(
+            cli.command,
+            Command::Prune {
+                older_than: 43_200,
+                include_locked: false,
+                force: true
+            }
+        ));
+    }
+
+    #[test]
+    fn prune_include_locked() {
+        let cli = parse(&["relocal", "prune", "--older-than", "7d", "--include-locked"]);
+        assert!(matches!(
+            cli.command,
+            Command::Prune {
+                include_locked: true,
+                force: false,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn prune_requires_valid_age() {
+        assert!(Cli::try_parse_from(["relocal", "prune"]).is_err());
+        assert!(Cli::try_parse_from(["relocal", "prune", "--older-than", "soon"]).is_err());
+    }
+
     #[test]
     fn daemon_subcommand() {
         let cli = parse(&["relocal", "_daemon", "my-session", "/tmp/repo"]);
@@ -435,11 +1453,301 @@ mod tests {
             Command::Daemon {
                 session_name,
                 repo_root,
+                ephemeral,
+                no_initial_push,
             } => {
                 assert_eq!(session_name, "my-session");
                 assert_eq!(repo_root, "/tmp/repo");
+                assert!(!ephemeral);
+                assert!(!no_initial_push);
             }
             _ => panic!("expected Daemon"),
         }
     }
+
+    #[test]
+    fn daemon_subcommand_ephemeral() {
+        let cli = parse(&[
+            "relocal",
+            "_daemon",
+            "my-session",
+            "/tmp/repo",
+            "--ephemeral",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Command::Daemon {
+                ephemeral: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn daemon_subcommand_no_initial_push() {
+        let cli = parse(&[
+            "relocal",
+            "_daemon",
+            "my-session",
+            "/tmp/repo",
+            "--no-initial-push",
+        ]);
+        assert!(matches!(
+            cli.command,
+            Command::Daemon {
+                no_initial_push: true,
+                ephemeral: false,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn exec_with_session_and_command() {
+        let cli = parse(&["relocal", "exec", "s1", "--", "ls", "-la"]);
+        match &cli.command {
+            Command::Exec {
+                session_name,
+                command,
+            } => {
+                assert_eq!(session_name.as_deref(), Some("s1"));
+                assert_eq!(command, &["ls", "-la"]);
+            }
+            _ => panic!("expected Exec"),
+        }
+    }
+
+    #[test]
+    fn exec_without_session() {
+        let cli = parse(&["relocal", "exec", "--", "cargo", "test"]);
+        match &cli.command {
+            Command::Exec {
+                session_name,
+                command,
+            } => {
+                assert!(session_name.is_none());
+                assert_eq!(command, &["cargo", "test"]);
+            }
+            _ => panic!("expected Exec"),
+        }
+    }
+
+    #[test]
+    fn exec_requires_command() {
+        assert!(Cli::try_parse_from(["relocal", "exec", "s1"]).is_err());
+    }
+
+    #[test]
+    fn list_detail() {
+        let cli = parse(&["relocal", "list", "--detail"]);
+        assert!(matches!(
+            cli.command,
+            Command::List {
+                detail: true,
+                json: false
+            }
+        ));
+    }
+
+    #[test]
+    fn list_json() {
+        let cli = parse(&["relocal", "list", "--json"]);
+        assert!(matches!(
+            cli.command,
+            Command::List {
+                detail: false,
+                json: true
+            }
+        ));
+    }
+
+    #[test]
+    fn sync_resume_flag() {
+        let cli = parse(&["relocal", "sync", "pull", "--resume", "s1"]);
+        assert!(matches!(
+            cli.command,
+            Command::Sync {
+                command: Some(SyncCommand::Pull { resume: true, .. }),
+                ..
+            }
+        ));
+        let cli = parse(&["relocal", "sync", "push", "--resume"]);
+        assert!(matches!(
+            cli.command,
+            Command::Sync {
+                command: Some(SyncCommand::Push { resume: true, .. }),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn sync_dry_run_flag() {
+        let cli = parse(&["relocal", "sync", "push", "--dry-run"]);
+        assert!(matches!(
+            cli.command,
+            Command::Sync {
+                command: Some(SyncCommand::Push { dry_run: true, .. }),
+                ..
+            }
+        ));
+        let cli = parse(&["relocal", "sync", "pull", "--dry-run", "s1"]);
+        assert!(matches!(
+            cli.command,
+            Command::Sync {
+                command: Some(SyncCommand::Pull { dry_run: true, .. }),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn sync_dry_run_conflicts_with_delete_dry_run() {
+        let result =
+            Cli::try_parse_from(["relocal", "sync", "pull", "--dry-run", "--delete-dry-run"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn sync_copy() {
+        let cli = parse(&["relocal", "sync", "copy", "a", "b"]);
+        match &cli.command {
+            Command::Sync {
+                command: Some(SyncCommand::Copy { from, to }),
+                ..
+            } => {
+                assert_eq!(from, "a");
+                assert_eq!(to, "b");
+            }
+            _ => panic!("expected Sync Copy"),
+        }
+    }
+
+    #[test]
+    fn sync_copy_requires_both_sessions() {
+        assert!(Cli::try_parse_from(["relocal", "sync", "copy", "a"]).is_err());
+    }
+
+    #[test]
+    fn watch_with_and_without_session() {
+        let cli = parse(&["relocal", "watch"]);
+        assert!(matches!(cli.command, Command::Watch { session_name: None }));
+        let cli = parse(&["relocal", "watch", "s1"]);
+        match &cli.command {
+            Command::Watch { session_name } => assert_eq!(session_name.as_deref(), Some("s1")),
+            _ => panic!("expected Watch"),
+        }
+    }
+
+    #[test]
+    fn init_from_existing() {
+        let cli = parse(&["relocal", "init", "--from-existing", "../other"]);
+        match &cli.command {
+            Command::Init { from_existing } => {
+                assert_eq!(from_existing.as_deref(), Some(Path::new("../other")));
+            }
+            _ => panic!("expected Init"),
+        }
+    }
+
+    #[test]
+    fn sync_pull_local_path_and_force() {
+        let cli = parse(&[
+            "relocal",
+            "sync",
+            "pull",
+            "--local-path",
+            "/tmp/scratch",
+            "--force",
+        ]);
+        match &cli.command {
+            Command::Sync {
+                command:
+                    Some(SyncCommand::Pull {
+                        local_path, force, ..
+                    }),
+                ..
+            } => {
+                assert_eq!(local_path.as_deref(), Some(Path::new("/tmp/scratch")));
+                assert!(force);
+            }
+            _ => panic!("expected Sync Pull"),
+        }
+    }
+
+    #[test]
+    fn sync_pull_force_requires_local_path() {
+        assert!(Cli::try_parse_from(["relocal", "sync", "pull", "--force"]).is_err());
+    }
+
+    #[test]
+    fn sync_push_no_delete() {
+        let cli = parse(&["relocal", "sync", "push", "--no-delete"]);
+        assert!(matches!(
+            cli.command,
+            Command::Sync {
+                command: Some(SyncCommand::Push {
+                    no_delete: true,
+                    ..
+                }),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn sync_bidirectional_safe() {
+        let cli = parse(&["relocal", "sync", "--bidirectional-safe", "s1"]);
+        match &cli.command {
+            Command::Sync {
+                command: None,
+                prefer: None,
+                bidirectional_safe: true,
+                session_name,
+            } => assert_eq!(session_name.as_deref(), Some("s1")),
+            _ => panic!("expected Sync --bidirectional-safe"),
+        }
+    }
+
+    #[test]
+    fn sync_without_subcommand_reconciles() {
+        for args in [&["relocal", "sync"][..], &["relocal", "sync", "s1"]] {
+            assert!(matches!(
+                parse(args).command,
+                Command::Sync {
+                    command: None,
+                    prefer: None,
+                    bidirectional_safe: false,
+                    ..
+                }
+            ));
+        }
+    }
+
+    #[test]
+    fn sync_prefer_side() {
+        let cli = parse(&["relocal", "sync", "--prefer", "remote", "s1"]);
+        assert!(matches!(
+            cli.command,
+            Command::Sync {
+                prefer: Some(Side::Remote),
+                ..
+            }
+        ));
+        assert!(Cli::try_parse_from(["relocal", "sync", "--prefer", "both"]).is_err());
+        assert!(Cli::try_parse_from([
+            "relocal",
+            "sync",
+            "--prefer",
+            "local",
+            "--bidirectional-safe"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn sync_bidirectional_safe_conflicts_with_subcommand() {
+        let result = Cli::try_parse_from(["relocal", "sync", "push", "--bidirectional-safe"]);
+        assert!(result.is_err());
+    }
 }
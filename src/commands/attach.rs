@@ -0,0 +1,86 @@
+//! `relocal attach [session-name]` — reconnect to a detached session.
+//!
+//! Attaches the terminal to the tmux session started by
+//! `relocal claude --detach`. Detaching again (`Ctrl-b d`) or the agent
+//! exiting ends the command. The session daemon keeps syncing in the
+//! background either way, so attaching does not connect to it.
+
+use tracing::{info, warn};
+
+use crate::config::Config;
+use crate::daemon_client;
+use crate::error::{Error, Result};
+use crate::runner::CommandRunner;
+use crate::ssh;
+
+pub fn run(runner: &dyn CommandRunner, config: &Config, session_name: &str) -> Result<()> {
+    if !ssh::run_status_check(
+        runner,
+        &config.remote,
+        &ssh::check_detached_session(session_name),
+    )? {
+        return Err(Error::Remote {
+            remote: config.remote.clone(),
+            message: format!(
+                "no detached session '{session_name}' is running. Start one with `relocal claude --detach`."
+            ),
+        });
+    }
+    if !daemon_client::is_daemon_running(session_name, &config.remote) {
+        warn!("No session daemon is running for {session_name}: remote changes are not synced in the background.");
+        warn!("Use `relocal sync pull {session_name}` to fetch them.");
+    }
+
+    info!("Attaching to {session_name} (detach with Ctrl-b d)...");
+    let status =
+        runner.run_ssh_interactive(&config.remote, &ssh::attach_detached_session(session_name))?;
+    if !status.success() {
+        return Err(Error::CommandFailed {
+            command: "tmux attach-session".to_string(),
+            message: format!(
+                "exited with {}",
+                status
+                    .code()
+                    .map_or("signal".to_string(), |c: i32| c.to_string())
+            ),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ssh::{STATUS_CHECK_FALSE, STATUS_CHECK_TRUE};
+    use crate::test_support::{Invocation, MockResponse, MockRunner};
+
+    fn test_config() -> Config {
+        Config::parse("remote = \"user@host\"").unwrap()
+    }
+
+    #[test]
+    fn attaches_to_running_detached_session() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        mock.add_response(MockResponse::Ok(String::new()));
+
+        run(&mock, &test_config(), "s1").unwrap();
+
+        match &mock.invocations()[1] {
+            Invocation::SshInteractive { command, .. } => {
+                assert!(command.contains("tmux attach-session -t =relocal-s1"));
+            }
+            _ => panic!("expected SshInteractive"),
+        }
+    }
+
+    #[test]
+    fn missing_detached_session_fails_before_attaching() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into()));
+
+        let err = run(&mock, &test_config(), "s1").unwrap_err();
+        assert!(err.to_string().contains("relocal claude --detach"), "{err}");
+        assert_eq!(mock.invocations().len(), 1);
+    }
+}
@@ -0,0 +1,191 @@
+//! `relocal benchmark [session-name]` — measures sync round-trip latency.
+//!
+//! Runs the push rsync `count` times as a dry run over one shared SSH
+//! connection and reports min/median/max wall-clock time. Each run does the
+//! full file-list exchange and comparison a real sync does, but transfers and
+//! deletes nothing, so it is safe against a live session. Useful for tuning
+//! `sync_interval`.
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use tracing::{debug, info};
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::rsync::{build_rsync_args, Direction, SyncOptions};
+use crate::runner::CommandRunner;
+use crate::ssh;
+
+/// Aggregated timings of the benchmark runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyStats {
+    pub min: Duration,
+    /// Middle sample; the mean of the two middle samples for an even count.
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl LatencyStats {
+    /// Aggregates `samples`, or `None` if there are none.
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        let mid = sorted.len() / 2;
+        let median = match sorted.len() {
+            0 => return None,
+            n if n % 2 == 0 => (sorted[mid - 1] + sorted[mid]) / 2,
+            _ => sorted[mid],
+        };
+        Some(LatencyStats {
+            min: sorted[0],
+            median,
+            max: sorted[sorted.len() - 1],
+        })
+    }
+
+    /// One-line summary, e.g. `min 120 ms, median 135 ms, max 410 ms`.
+    pub fn summary(&self) -> String {
+        format!(
+            "min {} ms, median {} ms, max {} ms",
+            self.min.as_millis(),
+            self.median.as_millis(),
+            self.max.as_millis()
+        )
+    }
+}
+
+/// Times `count` dry-run pushes of `repo_root` to `session_name`.
+pub fn run(
+    runner: &dyn CommandRunner,
+    config: &Config,
+    session_name: &str,
+    repo_root: &Path,
+    count: u32,
+) -> Result<LatencyStats> {
+    if !ssh::run_status_check(
+        runner,
+        &config.remote,
+        &ssh::check_work_dir_exists(session_name),
+    )? {
+        return Err(Error::Remote {
+            remote: config.remote.clone(),
+            message: format!("session '{session_name}' not found. No working directory exists."),
+        });
+    }
+
+    let options = SyncOptions {
+        dry_run: true,
+        ..Default::default()
+    };
+    let params = build_rsync_args(config, Direction::Push, session_name, repo_root, &options);
+    info!("Timing {count} dry-run push(es) to {session_name}...");
+    let mut samples = Vec::new();
+    for i in 1..=count {
+        let started = Instant::now();
+        runner.run_rsync(&params)?.check("rsync")?;
+        let elapsed = started.elapsed();
+        debug!("run {i}: {} ms", elapsed.as_millis());
+        samples.push(elapsed);
+    }
+
+    let stats = LatencyStats::from_samples(&samples).ok_or_else(|| Error::CommandFailed {
+        command: "benchmark".to_string(),
+        message: "no runs to time".to_string(),
+    })?;
+    println!("Sync round trip over {count} run(s): {}.", stats.summary());
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ssh::{STATUS_CHECK_FALSE, STATUS_CHECK_TRUE};
+    use crate::test_support::{Invocation, MockResponse, MockRunner};
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    fn test_config() -> Config {
+        Config::parse("remote = \"user@host\"").unwrap()
+    }
+
+    #[test]
+    fn stats_odd_count_takes_middle() {
+        let stats = LatencyStats::from_samples(&[ms(30), ms(10), ms(20)]).unwrap();
+        assert_eq!(
+            stats,
+            LatencyStats {
+                min: ms(10),
+                median: ms(20),
+                max: ms(30),
+            }
+        );
+    }
+
+    #[test]
+    fn stats_even_count_averages_middle_two() {
+        let stats = LatencyStats::from_samples(&[ms(40), ms(10), ms(20), ms(100)]).unwrap();
+        assert_eq!(stats.median, ms(30));
+        assert_eq!(stats.min, ms(10));
+        assert_eq!(stats.max, ms(100));
+    }
+
+    #[test]
+    fn stats_single_sample() {
+        let stats = LatencyStats::from_samples(&[ms(7)]).unwrap();
+        assert_eq!((stats.min, stats.median, stats.max), (ms(7), ms(7), ms(7)));
+        assert_eq!(stats.summary(), "min 7 ms, median 7 ms, max 7 ms");
+    }
+
+    #[test]
+    fn stats_empty_is_none() {
+        assert_eq!(LatencyStats::from_samples(&[]), None);
+    }
+
+    #[test]
+    fn runs_count_dry_run_pushes() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // dir exists
+        for _ in 0..3 {
+            mock.add_response(MockResponse::Ok(String::new()));
+        }
+
+        run(&mock, &test_config(), "s1", Path::new("/repo"), 3).unwrap();
+
+        let rsyncs: Vec<_> = mock
+            .invocations()
+            .into_iter()
+            .filter_map(|inv| match inv {
+                Invocation::Rsync { args, .. } => Some(args),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(rsyncs.len(), 3);
+        assert!(rsyncs
+            .iter()
+            .all(|args| args.contains(&"--dry-run".to_string())));
+    }
+
+    #[test]
+    fn missing_session_fails_before_rsync() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into()));
+
+        let err = run(&mock, &test_config(), "s1", Path::new("/repo"), 3).unwrap_err();
+
+        assert!(err.to_string().contains("not found"), "{err}");
+        assert_eq!(mock.invocations().len(), 1);
+    }
+
+    #[test]
+    fn failing_rsync_aborts() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        mock.add_response(MockResponse::Fail("connection closed".into()));
+
+        assert!(run(&mock, &test_config(), "s1", Path::new("/repo"), 3).is_err());
+        assert_eq!(mock.invocations().len(), 2);
+    }
+}
@@ -2,26 +2,61 @@
 //!
 //! Syncs the repo to the remote, launches an interactive Claude session with
 //! continuous background synchronization, and performs a final pull on clean exit.
+//! With `--detach`, Claude runs in a tmux session on the remote instead and
+//! `relocal attach` reconnects to it.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::commands::session::ToolConfig;
+use tracing::warn;
+
+use crate::commands::session::{Launch, ToolConfig};
 use crate::config::Config;
+use crate::daemon::DaemonOptions;
 use crate::error::Result;
 use crate::ssh;
 
-const TOOL: ToolConfig = ToolConfig {
+pub(crate) const TOOL: ToolConfig = ToolConfig {
     display_name: "Claude Code",
-    check_installed: ssh::check_claude_installed,
-    start_session: ssh::start_claude_session,
+    check_installed: |config| ssh::check_claude_installed(&config.claude_bin),
+    start_session: |config, session, args| {
+        ssh::start_claude_session(session, &config.claude_bin, config.skip_permissions, args)
+    },
 };
 
+/// Marker recording that the `--dangerously-skip-permissions` warning has
+/// been shown on this machine.
+pub fn skip_permissions_marker_path(home: &Path) -> PathBuf {
+    home.join(".relocal").join("skip-permissions-warned")
+}
+
+/// Warns once per machine that Claude is launched with
+/// `--dangerously-skip-permissions` when that comes from the default rather
+/// than an explicit `skip_permissions` setting. Returns whether it warned.
+pub fn warn_skip_permissions_once(home: &Path, config: &Config) -> Result<bool> {
+    let marker = skip_permissions_marker_path(home);
+    if !config.skip_permissions || config.skip_permissions_explicit || marker.exists() {
+        return Ok(false);
+    }
+    warn!(
+        "Claude runs with --dangerously-skip-permissions on the remote. Set \
+         `skip_permissions = false` in relocal.toml (or pass --no-skip-permissions) for \
+         permission prompts, or `skip_permissions = true` to silence this warning."
+    );
+    if let Some(parent) = marker.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&marker, "")?;
+    Ok(true)
+}
+
 pub fn run(
     config: &Config,
     session_name: &str,
     repo_root: &Path,
     verbosity: u8,
+    options: DaemonOptions,
     claude_args: &[String],
+    detach: bool,
 ) -> Result<()> {
     super::session::run(
         &TOOL,
@@ -29,6 +64,51 @@ pub fn run(
         session_name,
         repo_root,
         verbosity,
-        claude_args,
+        options,
+        Launch {
+            extra_args: claude_args,
+            detach,
+        },
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn config(toml: &str) -> Config {
+        Config::parse(&format!("remote = \"user@host\"\n{toml}")).unwrap()
+    }
+
+    #[test]
+    fn skip_permissions_false_omits_dangerous_flag() {
+        let command = (TOOL.start_session)(&config("skip_permissions = false"), "s1", &[]);
+        assert!(
+            !command.contains("--dangerously-skip-permissions"),
+            "{command}"
+        );
+        let command = (TOOL.start_session)(&config(""), "s1", &[]);
+        assert!(
+            command.contains("--dangerously-skip-permissions"),
+            "{command}"
+        );
+    }
+
+    #[test]
+    fn default_skip_permissions_warns_once() {
+        let home = TempDir::new().unwrap();
+        assert!(warn_skip_permissions_once(home.path(), &config("")).unwrap());
+        assert!(skip_permissions_marker_path(home.path()).exists());
+        assert!(!warn_skip_permissions_once(home.path(), &config("")).unwrap());
+    }
+
+    #[test]
+    fn explicit_or_disabled_skip_permissions_does_not_warn() {
+        let home = TempDir::new().unwrap();
+        for toml in ["skip_permissions = true", "skip_permissions = false"] {
+            assert!(!warn_skip_permissions_once(home.path(), &config(toml)).unwrap());
+        }
+        assert!(!skip_permissions_marker_path(home.path()).exists());
+    }
+}
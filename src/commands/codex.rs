@@ -6,15 +6,18 @@
 
 use std::path::Path;
 
-use crate::commands::session::ToolConfig;
+use crate::commands::session::{Launch, ToolConfig};
 use crate::config::Config;
+use crate::daemon::DaemonOptions;
 use crate::error::Result;
 use crate::ssh;
 
-const TOOL: ToolConfig = ToolConfig {
+pub(crate) const TOOL: ToolConfig = ToolConfig {
     display_name: "Codex",
-    check_installed: ssh::check_codex_installed,
-    start_session: ssh::start_codex_session,
+    check_installed: |_config| ssh::check_codex_installed(),
+    start_session: |config, session, args| {
+        ssh::start_codex_session(session, config.skip_permissions, args)
+    },
 };
 
 pub fn run(
@@ -22,6 +25,7 @@ pub fn run(
     session_name: &str,
     repo_root: &Path,
     verbosity: u8,
+    options: DaemonOptions,
     codex_args: &[String],
 ) -> Result<()> {
     super::session::run(
@@ -30,6 +34,10 @@ pub fn run(
         session_name,
         repo_root,
         verbosity,
-        codex_args,
+        options,
+        Launch {
+            extra_args: codex_args,
+            detach: false,
+        },
     )
 }
@@ -0,0 +1,57 @@
+//! `relocal completions <shell>` — prints a shell completion script.
+//!
+//! The script is written to stdout so it can be redirected into the shell's
+//! completion directory, e.g. `relocal completions zsh > ~/.zfunc/_relocal`.
+
+use std::io::Write;
+
+use clap_complete::{Generator, Shell};
+
+use crate::error::Result;
+
+/// Writes the completion script for `cmd` (the full CLI definition) to `out`.
+///
+/// Uses `try_generate` so a closed pipe (`relocal completions bash | head`)
+/// is an error rather than a panic.
+pub fn write(cmd: &mut clap::Command, shell: Shell, out: &mut dyn Write) -> Result<()> {
+    let name = cmd.get_name().to_string();
+    cmd.set_bin_name(name);
+    cmd.build();
+    shell.try_generate(cmd, out)?;
+    Ok(())
+}
+
+/// Prints the completion script for `cmd` to stdout.
+pub fn run(cmd: &mut clap::Command, shell: Shell) -> Result<()> {
+    write(cmd, shell, &mut std::io::stdout().lock())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> clap::Command {
+        clap::Command::new("relocal")
+            .subcommand(clap::Command::new("status"))
+            .subcommand(clap::Command::new("sync").subcommand(clap::Command::new("push")))
+    }
+
+    #[test]
+    fn bash_script_names_binary_and_subcommands() {
+        let mut out = Vec::new();
+        write(&mut sample(), Shell::Bash, &mut out).unwrap();
+        let script = String::from_utf8(out).unwrap();
+        assert!(script.contains("_relocal"), "{script}");
+        assert!(script.contains("status"));
+        assert!(script.contains("push"));
+    }
+
+    #[test]
+    fn every_supported_shell_generates() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let mut out = Vec::new();
+            write(&mut sample(), shell, &mut out).unwrap();
+            assert!(!out.is_empty(), "{shell} script is empty");
+        }
+    }
+}
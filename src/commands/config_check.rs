@@ -0,0 +1,51 @@
+//! `relocal config check` — validates relocal.toml without touching the remote.
+//!
+//! Loading already rejects syntax errors, unknown keys and out-of-range values.
+//! This adds the checks [`Config::problems`] performs and reports every problem
+//! found rather than stopping at the first.
+
+use std::path::Path;
+
+use tracing::warn;
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+
+/// Reports every problem with `config`, failing if there were any.
+pub fn run(config: &Config, repo_root: &Path) -> Result<()> {
+    let problems = config.problems(repo_root);
+    if problems.is_empty() {
+        println!("relocal.toml OK.");
+        return Ok(());
+    }
+    for problem in &problems {
+        warn!("{problem}");
+    }
+    Err(Error::ConfigParse {
+        path: repo_root.join("relocal.toml").display().to_string(),
+        reason: format!("{} problem(s) found", problems.len()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn valid_config_passes() {
+        let repo = TempDir::new().unwrap();
+        let config = Config::parse("remote = \"user@host\"").unwrap();
+        run(&config, repo.path()).unwrap();
+    }
+
+    #[test]
+    fn problems_fail_with_count() {
+        let repo = TempDir::new().unwrap();
+        let config =
+            Config::parse("remote = \"user@host\"\nexclude = [\"\"]\nexclude_from = \"nope\"")
+                .unwrap();
+        let err = run(&config, repo.path()).unwrap_err();
+        assert!(err.to_string().contains("2 problem(s)"), "{err}");
+    }
+}
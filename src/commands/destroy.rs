@@ -11,21 +11,37 @@ use crate::error::{Error, Result};
 use crate::runner::CommandRunner;
 use crate::ssh;
 
-/// Removes a session's remote working directory.
-///
-/// If `confirm` is true, prompts the user for confirmation before proceeding.
-/// Pass `false` in tests to skip the interactive prompt.
-///
-/// If `check_daemon` is true, refuses to proceed when a daemon is running
-/// for this session. Pass `false` in tests to skip the daemon check.
+/// Safety checks for [`run`] (and [`prune::run`](crate::commands::prune::run)).
+/// The default skips the prompt and the daemon check, as tests need.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DestroyOptions {
+    /// Prompt the user for confirmation before proceeding.
+    pub confirm: bool,
+    /// Refuse to proceed when a daemon is running for the session.
+    pub check_daemon: bool,
+    /// Allow a remote outside `allowed_remotes` (`--force`).
+    pub force: bool,
+}
+
+/// What [`run`] did when it didn't fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DestroyOutcome {
+    Destroyed,
+    /// The user declined the confirmation prompt; nothing was removed.
+    Aborted,
+}
+
+/// Removes a session's remote working directory, with the checks `options`
+/// asks for.
 pub fn run(
     runner: &dyn CommandRunner,
     config: &Config,
     session_name: &str,
-    confirm: bool,
-    check_daemon: bool,
-) -> Result<()> {
-    if check_daemon && daemon_client::is_daemon_running(session_name, &config.remote) {
+    options: DestroyOptions,
+) -> Result<DestroyOutcome> {
+    config.check_remote_allowed(options.force)?;
+
+    if options.check_daemon && daemon_client::is_daemon_running(session_name, &config.remote) {
         return Err(Error::Remote {
             remote: config.remote.clone(),
             message: format!(
@@ -48,7 +64,7 @@ pub fn run(
         });
     }
 
-    if confirm {
+    if options.confirm {
         let prompt = format!(
             "Remove session '{session_name}' on {}? This deletes {}.",
             config.remote,
@@ -62,7 +78,7 @@ pub fn run(
 
         if !confirmed {
             info!("Aborted.");
-            return Ok(());
+            return Ok(DestroyOutcome::Aborted);
         }
     }
 
@@ -101,7 +117,7 @@ pub fn run(
     }
 
     info!("Session '{session_name}' destroyed.");
-    Ok(())
+    Ok(DestroyOutcome::Destroyed)
 }
 
 #[cfg(test)]
@@ -121,7 +137,14 @@ mod tests {
         mock.add_response(MockResponse::Ok(String::new()));
         mock.add_response(MockResponse::Ok(String::new()));
 
-        run(&mock, &test_config(), "my-session", false, false).unwrap();
+        let outcome = run(
+            &mock,
+            &test_config(),
+            "my-session",
+            DestroyOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(outcome, DestroyOutcome::Destroyed);
 
         let inv = mock.invocations();
         assert_eq!(inv.len(), 3);
@@ -152,7 +175,7 @@ mod tests {
         mock.add_response(MockResponse::Ok(String::new()));
 
         let config = Config::parse("remote = \"deploy@prod\"").unwrap();
-        run(&mock, &config, "s1", false, false).unwrap();
+        run(&mock, &config, "s1", DestroyOptions::default()).unwrap();
 
         let inv = mock.invocations();
         for i in &inv {
@@ -169,7 +192,7 @@ mod tests {
         mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
         mock.add_response(MockResponse::Fail("permission denied".into()));
 
-        let result = run(&mock, &test_config(), "s1", false, false);
+        let result = run(&mock, &test_config(), "s1", DestroyOptions::default());
         assert!(result.is_err());
     }
 
@@ -180,7 +203,7 @@ mod tests {
         mock.add_response(MockResponse::Ok(String::new()));
         mock.add_response(MockResponse::Fail("permission denied".into()));
 
-        let result = run(&mock, &test_config(), "s1", false, false);
+        let result = run(&mock, &test_config(), "s1", DestroyOptions::default());
         assert!(result.is_err());
     }
 
@@ -189,9 +212,47 @@ mod tests {
         let mock = MockRunner::new();
         mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into()));
 
-        let result = run(&mock, &test_config(), "no-such-session", false, false);
+        let result = run(
+            &mock,
+            &test_config(),
+            "no-such-session",
+            DestroyOptions::default(),
+        );
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(err.to_string().contains("not found"));
     }
+
+    #[test]
+    fn refuses_remote_outside_allowlist() {
+        let mock = MockRunner::new();
+        let config =
+            Config::parse("remote = \"deploy@prod\"\nallowed_remotes = [\"me@dev\"]").unwrap();
+
+        let err = run(&mock, &config, "s1", DestroyOptions::default()).unwrap_err();
+        assert!(matches!(err, Error::RemoteNotAllowed { .. }));
+        assert!(mock.invocations().is_empty());
+    }
+
+    #[test]
+    fn force_overrides_allowlist() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        mock.add_response(MockResponse::Ok(String::new()));
+        mock.add_response(MockResponse::Ok(String::new()));
+        let config =
+            Config::parse("remote = \"deploy@prod\"\nallowed_remotes = [\"me@dev\"]").unwrap();
+
+        run(
+            &mock,
+            &config,
+            "s1",
+            DestroyOptions {
+                force: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(mock.invocations().len(), 3);
+    }
 }
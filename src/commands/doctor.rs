@@ -0,0 +1,417 @@
+//! `relocal doctor` — read-only diagnosis of the remote environment.
+//!
+//! Runs the same probes `relocal remote install` uses to decide what to skip
+//! (tools on PATH, authentication) plus a few layout checks, and prints a
+//! pass/fail checklist with a remediation hint for each failure. Nothing on
+//! the remote is modified. A failed SSH connection is reported as the only
+//! check, since every other probe would fail the same way.
+//!
+//! With `--fix`, idempotent repairs run first and each change is reported:
+//! a missing `~/relocal/` is created and orphaned session lock files (see
+//! [`ssh::remove_orphan_lock_files`]) are removed. Tool installation and
+//! authentication are left to `relocal remote install`.
+
+use tracing::info;
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::remote_meta;
+use crate::runner::CommandRunner;
+use crate::ssh;
+
+/// Outcome of a single doctor check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: &'static str,
+    pub passed: bool,
+    /// What went wrong and how to fix it; empty when the check passed.
+    pub hint: String,
+}
+
+impl Check {
+    fn pass(name: &'static str) -> Self {
+        Self {
+            name,
+            passed: true,
+            hint: String::new(),
+        }
+    }
+
+    fn fail(name: &'static str, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            passed: false,
+            hint: hint.into(),
+        }
+    }
+}
+
+const INSTALL_HINT: &str = "run `relocal remote install`";
+
+/// Runs every check against the configured remote.
+pub fn collect(runner: &dyn CommandRunner, config: &Config) -> Vec<Check> {
+    let remote = &config.remote;
+    let connected = match runner.run_ssh(remote, "true") {
+        Ok(output) if output.status.success() => Check::pass("SSH connection"),
+        Ok(output) => {
+            let hint = format!("check `remote` in relocal.toml and that `ssh {remote}` works");
+            let stderr = output.stderr.trim();
+            if stderr.is_empty() {
+                Check::fail("SSH connection", hint)
+            } else {
+                Check::fail("SSH connection", format!("{stderr}; {hint}"))
+            }
+        }
+        Err(e) => Check::fail("SSH connection", e.to_string()),
+    };
+    if !connected.passed {
+        return vec![connected];
+    }
+
+    let probe = |name: &'static str, command: String, hint: &str| match ssh::run_status_check(
+        runner, remote, &command,
+    ) {
+        Ok(true) => Check::pass(name),
+        Ok(false) => Check::fail(name, hint),
+        Err(e) => Check::fail(name, e.to_string()),
+    };
+
+    vec![
+        connected,
+        probe("Rust (rustup)", ssh::check_rustup_installed(), INSTALL_HINT),
+        probe(
+            "Claude Code",
+            ssh::check_claude_installed(&config.claude_bin),
+            INSTALL_HINT,
+        ),
+        probe(
+            "Claude authentication",
+            ssh::check_claude_authenticated(&config.claude_bin),
+            "run `relocal remote install` to log in, or `claude login` on the remote",
+        ),
+        probe("Codex CLI", ssh::check_codex_installed(), INSTALL_HINT),
+        probe(
+            "Codex authentication",
+            ssh::check_codex_authenticated(),
+            "run `relocal remote install` to log in, or `codex login --device-auth` on the remote",
+        ),
+        probe(
+            "~/relocal directory",
+            ssh::check_relocal_dir_exists(),
+            INSTALL_HINT,
+        ),
+        check_remote_meta(runner, remote),
+    ]
+}
+
+/// Passes when the remote metadata is absent or written by a compatible protocol.
+fn check_remote_meta(runner: &dyn CommandRunner, remote: &str) -> Check {
+    const NAME: &str = "Remote compatibility";
+    match remote_meta::read(runner, remote)
+        .and_then(|meta| remote_meta::check_compat(remote, meta.as_ref()))
+    {
+        Ok(()) => Check::pass(NAME),
+        Err(e) => Check::fail(NAME, e.to_string()),
+    }
+}
+
+/// Applies the `--fix` repairs and returns a description of each change.
+pub fn repair(runner: &dyn CommandRunner, config: &Config) -> Result<Vec<String>> {
+    let remote = &config.remote;
+    let mut changes = Vec::new();
+
+    if !ssh::run_status_check(runner, remote, &ssh::check_relocal_dir_exists())? {
+        runner
+            .run_ssh(remote, &ssh::mkdir_relocal_dir())?
+            .check("create ~/relocal")?;
+        changes.push("created ~/relocal".to_string());
+    }
+
+    let output = runner
+        .run_ssh(remote, &ssh::remove_orphan_lock_files())?
+        .check("remove orphaned lock files")?;
+    changes.extend(
+        output
+            .stdout
+            .lines()
+            .filter(|l| !l.is_empty())
+            .map(|session| format!("removed orphaned lock file for session {session}")),
+    );
+
+    Ok(changes)
+}
+
+/// Prints the checklist, after applying repairs when `fix` is set; fails if
+/// any check did.
+pub fn run(runner: &dyn CommandRunner, config: &Config, fix: bool) -> Result<()> {
+    if fix {
+        let changes = repair(runner, config)?;
+        if changes.is_empty() {
+            info!("Nothing to fix.");
+        }
+        for change in &changes {
+            info!("Fixed: {change}");
+        }
+    }
+
+    let checks = collect(runner, config);
+    for check in &checks {
+        if check.passed {
+            println!("[ok]   {}", check.name);
+        } else {
+            println!("[FAIL] {}: {}", check.name, check.hint);
+        }
+    }
+
+    let failed = checks.iter().filter(|c| !c.passed).count();
+    if failed > 0 {
+        return Err(Error::Remote {
+            remote: config.remote.clone(),
+            message: format!("{failed} doctor check(s) failed"),
+        });
+    }
+    println!("All checks passed.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ssh::{STATUS_CHECK_FALSE, STATUS_CHECK_TRUE};
+    use crate::test_support::{Invocation, MockResponse, MockRunner};
+
+    fn test_config() -> Config {
+        Config::parse("remote = \"user@host\"").unwrap()
+    }
+
+    fn ssh_commands(mock: &MockRunner) -> Vec<String> {
+        mock.invocations()
+            .into_iter()
+            .map(|i| match i {
+                Invocation::Ssh { command, .. } => command,
+                other => panic!("expected Ssh, got {other:?}"),
+            })
+            .collect()
+    }
+
+    /// Queues a connected remote where every probe returns `probe` and the
+    /// metadata file holds `meta`.
+    fn queue_all(mock: &MockRunner, probe: &str, meta: &str) {
+        mock.add_response(MockResponse::Ok(String::new())); // ssh
+        for _ in 0..6 {
+            mock.add_response(MockResponse::Ok(probe.into()));
+        }
+        mock.add_response(MockResponse::Ok(meta.into()));
+    }
+
+    #[test]
+    fn healthy_remote_passes_every_check() {
+        let mock = MockRunner::new();
+        queue_all(&mock, STATUS_CHECK_TRUE, "");
+
+        run(&mock, &test_config(), false).unwrap();
+
+        let cmds = ssh_commands(&mock);
+        assert_eq!(cmds.len(), 8);
+        assert_eq!(cmds[0], "true");
+        assert!(cmds[1].contains("command -v rustup"));
+        assert!(cmds[2].contains("command -v claude"));
+        assert!(cmds[3].contains("claude auth status"));
+        assert!(cmds[4].contains("command -v codex"));
+        assert!(cmds[5].contains("test -e ~/.codex/auth.json"));
+        assert!(cmds[6].contains("test -d ~/relocal"));
+        assert!(cmds[7].contains(".relocal-meta.json"));
+    }
+
+    #[test]
+    fn only_reads_from_the_remote() {
+        let mock = MockRunner::new();
+        queue_all(&mock, STATUS_CHECK_FALSE, "");
+
+        let _ = run(&mock, &test_config(), false);
+
+        for cmd in ssh_commands(&mock) {
+            assert!(!cmd.contains("mkdir"), "unexpected write: {cmd}");
+            assert!(!cmd.contains("rm "), "unexpected write: {cmd}");
+            assert!(!cmd.contains("> ~"), "unexpected write: {cmd}");
+        }
+    }
+
+    #[test]
+    fn missing_tools_fail_with_install_hint() {
+        let mock = MockRunner::new();
+        queue_all(&mock, STATUS_CHECK_FALSE, "");
+
+        let checks = collect(&mock, &test_config());
+        assert_eq!(checks.len(), 8);
+        assert!(checks[0].passed);
+        for check in &checks[1..7] {
+            assert!(!check.passed, "{} should fail", check.name);
+            assert!(check.hint.contains("relocal remote install"));
+        }
+        assert!(checks[7].passed);
+    }
+
+    #[test]
+    fn failures_make_run_return_error() {
+        let mock = MockRunner::new();
+        queue_all(&mock, STATUS_CHECK_FALSE, "");
+
+        let err = run(&mock, &test_config(), false).unwrap_err();
+        assert!(err.to_string().contains("6 doctor check(s) failed"));
+    }
+
+    #[test]
+    fn ssh_failure_skips_remaining_checks() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Exit(255, "Connection refused".into()));
+
+        let checks = collect(&mock, &test_config());
+        assert_eq!(checks.len(), 1);
+        assert!(!checks[0].passed);
+        assert!(checks[0].hint.contains("Connection refused"));
+        assert!(checks[0].hint.contains("ssh user@host"));
+        assert_eq!(mock.invocations().len(), 1);
+    }
+
+    #[test]
+    fn ssh_transport_error_is_reported() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Err("ssh not found".into()));
+
+        let checks = collect(&mock, &test_config());
+        assert_eq!(checks.len(), 1);
+        assert!(!checks[0].passed);
+        assert!(checks[0].hint.contains("ssh not found"));
+    }
+
+    #[test]
+    fn probe_error_fails_that_check_only() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // ssh
+        mock.add_response(MockResponse::Exit(2, "boom".into())); // rustup
+        for _ in 0..5 {
+            mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        }
+        mock.add_response(MockResponse::Ok(String::new())); // meta
+
+        let checks = collect(&mock, &test_config());
+        assert!(!checks[1].passed);
+        assert!(checks[1].hint.contains("boom"));
+        assert!(checks.iter().skip(2).all(|c| c.passed));
+    }
+
+    #[test]
+    fn newer_remote_protocol_fails_compatibility() {
+        let mock = MockRunner::new();
+        let meta = format!(
+            r#"{{"installed_version":"99.0.0","protocol_version":{}}}"#,
+            remote_meta::PROTOCOL_VERSION + 1
+        );
+        queue_all(&mock, STATUS_CHECK_TRUE, &meta);
+
+        let checks = collect(&mock, &test_config());
+        let last = checks.last().unwrap();
+        assert_eq!(last.name, "Remote compatibility");
+        assert!(!last.passed);
+        assert!(last.hint.contains("99.0.0"));
+    }
+
+    #[test]
+    fn malformed_meta_fails_compatibility() {
+        let mock = MockRunner::new();
+        queue_all(&mock, STATUS_CHECK_TRUE, "not json");
+
+        let checks = collect(&mock, &test_config());
+        assert!(!checks.last().unwrap().passed);
+    }
+
+    #[test]
+    fn repair_creates_missing_relocal_dir() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into())); // dir
+        mock.add_response(MockResponse::Ok(String::new())); // mkdir
+        mock.add_response(MockResponse::Ok(String::new())); // locks
+
+        let changes = repair(&mock, &test_config()).unwrap();
+        assert_eq!(changes, vec!["created ~/relocal"]);
+
+        let cmds = ssh_commands(&mock);
+        assert!(cmds[0].contains("test -d ~/relocal"));
+        assert_eq!(cmds[1], "mkdir -p ~/relocal");
+        assert!(cmds[2].contains(".locks"));
+    }
+
+    #[test]
+    fn repair_leaves_existing_relocal_dir() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // dir
+        mock.add_response(MockResponse::Ok(String::new())); // locks
+
+        let changes = repair(&mock, &test_config()).unwrap();
+        assert!(changes.is_empty());
+
+        let cmds = ssh_commands(&mock);
+        assert_eq!(cmds.len(), 2);
+        assert!(!cmds.iter().any(|c| c.contains("mkdir")));
+    }
+
+    #[test]
+    fn repair_reports_removed_orphan_locks() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // dir
+        mock.add_response(MockResponse::Ok("old-1\nold-2\n".into())); // locks
+
+        let changes = repair(&mock, &test_config()).unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                "removed orphaned lock file for session old-1",
+                "removed orphaned lock file for session old-2",
+            ]
+        );
+    }
+
+    #[test]
+    fn repair_mkdir_failure_returns_error() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into())); // dir
+        mock.add_response(MockResponse::Fail("permission denied".into())); // mkdir
+
+        assert!(repair(&mock, &test_config()).is_err());
+        assert_eq!(mock.invocations().len(), 2);
+    }
+
+    #[test]
+    fn repair_lock_sweep_failure_returns_error() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // dir
+        mock.add_response(MockResponse::Fail("rm: cannot remove".into())); // locks
+
+        assert!(repair(&mock, &test_config()).is_err());
+    }
+
+    #[test]
+    fn repair_ssh_failure_returns_error() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Exit(255, "Connection refused".into()));
+
+        assert!(repair(&mock, &test_config()).is_err());
+    }
+
+    #[test]
+    fn fix_repairs_before_checking() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into())); // dir
+        mock.add_response(MockResponse::Ok(String::new())); // mkdir
+        mock.add_response(MockResponse::Ok(String::new())); // locks
+        queue_all(&mock, STATUS_CHECK_TRUE, "");
+
+        run(&mock, &test_config(), true).unwrap();
+
+        let cmds = ssh_commands(&mock);
+        assert_eq!(cmds.len(), 11);
+        assert_eq!(cmds[1], "mkdir -p ~/relocal");
+        assert_eq!(cmds[3], "true");
+    }
+}
@@ -0,0 +1,72 @@
+//! `relocal env` — print shell `export` lines describing a session.
+//!
+//! Meant for wrapper scripts: `eval "$(relocal env s1)"` sets
+//! `RELOCAL_SESSION`, `RELOCAL_REMOTE` and `RELOCAL_REMOTE_DIR` without the
+//! script re-deriving session names or remote paths.
+
+use shell_quote::{QuoteRefExt, Sh};
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::ssh;
+
+/// Builds the `export` lines for `session_name`, one per variable, each
+/// terminated by `;` so they also work when word-split by an unquoted
+/// `eval $(relocal env)`.
+///
+/// `RELOCAL_REMOTE_DIR` keeps its leading `~`, which the remote shell expands.
+pub fn export_lines(config: &Config, session_name: &str) -> String {
+    [
+        ("RELOCAL_SESSION", session_name.to_string()),
+        ("RELOCAL_REMOTE", config.remote.clone()),
+        ("RELOCAL_REMOTE_DIR", ssh::remote_work_dir(session_name)),
+    ]
+    .iter()
+    .map(|(name, value)| {
+        let quoted: Vec<u8> = value.quoted(Sh);
+        let quoted = String::from_utf8(quoted).expect("quoting preserves UTF-8");
+        format!("export {name}={quoted};\n")
+    })
+    .collect()
+}
+
+/// Prints the `export` lines for `session_name` to stdout.
+pub fn run(config: &Config, session_name: &str) -> Result<()> {
+    print!("{}", export_lines(config, session_name));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_lines_format() {
+        let config = Config::parse("remote = \"user@host\"").unwrap();
+        assert_eq!(
+            export_lines(&config, "s1"),
+            "export RELOCAL_SESSION=s1;\n\
+             export RELOCAL_REMOTE=user'@host';\n\
+             export RELOCAL_REMOTE_DIR='~/relocal/s1';\n"
+        );
+    }
+
+    #[test]
+    fn export_lines_roundtrip_through_sh() {
+        // Not a loadable remote; set directly to exercise the quoting.
+        let mut config = Config::parse("remote = \"user@host\"").unwrap();
+        config.remote = "it's me@host".to_string();
+        let script = format!(
+            "{}printf '%s|%s|%s' \"$RELOCAL_SESSION\" \"$RELOCAL_REMOTE\" \"$RELOCAL_REMOTE_DIR\"",
+            export_lines(&config, "s1")
+        );
+        let output = std::process::Command::new("sh")
+            .args(["-c", &script])
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "s1|it's me@host|~/relocal/s1"
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! `relocal exec [session-name] -- <cmd...>` — run a one-off command in the
+//! remote session directory.
+//!
+//! The command runs over interactive SSH so it inherits the terminal (colors,
+//! Ctrl-C). Its exit code is returned so `relocal exec` can pass it through.
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::runner::CommandRunner;
+use crate::ssh;
+
+/// Runs `command` in the session's working directory and returns its exit code.
+///
+/// Fails without running anything if the working directory does not exist.
+pub fn run(
+    runner: &dyn CommandRunner,
+    config: &Config,
+    session_name: &str,
+    command: &[String],
+) -> Result<i32> {
+    let dir_exists = ssh::run_status_check(
+        runner,
+        &config.remote,
+        &ssh::check_work_dir_exists(session_name),
+    )?;
+    if !dir_exists {
+        return Err(Error::Remote {
+            remote: config.remote.clone(),
+            message: format!("session '{session_name}' not found. No working directory exists."),
+        });
+    }
+
+    let status = runner.run_ssh_interactive(
+        &config.remote,
+        &ssh::exec_in_work_dir(session_name, command),
+    )?;
+    status.code().ok_or_else(|| Error::CommandFailed {
+        command: "exec".to_string(),
+        message: "remote command terminated by signal".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ssh::{STATUS_CHECK_FALSE, STATUS_CHECK_TRUE};
+    use crate::test_support::{Invocation, MockResponse, MockRunner};
+
+    fn test_config() -> Config {
+        Config::parse("remote = \"user@host\"").unwrap()
+    }
+
+    fn cmd(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn runs_command_in_session_dir() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        mock.add_response(MockResponse::Ok(String::new()));
+
+        let code = run(&mock, &test_config(), "s1", &cmd(&["cargo", "test"])).unwrap();
+        assert_eq!(code, 0);
+
+        let inv = mock.invocations();
+        assert_eq!(inv.len(), 2);
+        match &inv[1] {
+            Invocation::SshInteractive { remote, command } => {
+                assert_eq!(remote, "user@host");
+                assert_eq!(command, "cd ~/relocal/s1 && cargo test");
+            }
+            _ => panic!("expected SshInteractive, got {:?}", inv[1]),
+        }
+    }
+
+    #[test]
+    fn missing_work_dir_fails_without_running_command() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into()));
+
+        let err = run(&mock, &test_config(), "gone", &cmd(&["ls"])).unwrap_err();
+        assert!(err.to_string().contains("not found"));
+        assert_eq!(mock.invocations().len(), 1);
+    }
+
+    #[test]
+    fn nonzero_exit_code_is_returned() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        mock.add_response(MockResponse::Fail(String::new()));
+
+        let code = run(&mock, &test_config(), "s1", &cmd(&["false"])).unwrap();
+        assert_ne!(code, 0);
+    }
+
+    #[test]
+    fn probe_transport_failure_propagates() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Fail("ssh: connect timeout".into()));
+
+        assert!(run(&mock, &test_config(), "s1", &cmd(&["ls"])).is_err());
+        assert_eq!(mock.invocations().len(), 1);
+    }
+}
@@ -0,0 +1,295 @@
+//! `relocal import-session <remote-path> [session-name]` — adopts an existing
+//! remote directory as a session.
+//!
+//! Nothing is pushed: `~/relocal/<session>` is symlinked to the remote
+//! directory, the session's origin is recorded, a `relocal.toml` naming the
+//! remote is written into the (empty) local directory, and the remote
+//! contents are pulled into it. Destroying the session later removes only
+//! the symlink. If the pull fails, the symlink, origin record and
+//! `relocal.toml` are removed again.
+
+use std::path::Path;
+
+use tracing::{info, warn};
+
+use crate::commands::init::generate_toml;
+use crate::commands::sync::sync_pull;
+use crate::config::{Config, PartialConfig};
+use crate::error::{Error, Result};
+use crate::remote_meta;
+use crate::rsync::SyncOptions;
+use crate::runner::CommandRunner;
+use crate::session::{hashed_session_name_with_origin, validate_session_name};
+use crate::ssh;
+
+/// Contents of the `relocal.toml` written into the imported repo: just the
+/// remote, every other setting at its default.
+pub fn local_config_toml(remote: &str) -> Result<String> {
+    let config = PartialConfig {
+        remote: Some(remote.to_string()),
+        ..Default::default()
+    }
+    .resolve()?;
+    Ok(generate_toml(&config))
+}
+
+/// Adopts `remote_path` as `session_name` and pulls it into `repo_root`,
+/// which must be an empty directory. Returns the session name.
+///
+/// Without a `session_name`, the default name is derived from `repo_root`
+/// and the remote directory's git origin, i.e. the name every later command
+/// computes once the pull has brought `.git` in.
+pub fn run(
+    runner: &dyn CommandRunner,
+    config: &Config,
+    remote_path: &str,
+    session_name: Option<&str>,
+    repo_root: &Path,
+) -> Result<String> {
+    if let Some(name) = session_name {
+        validate_session_name(name)?;
+    }
+    if std::fs::read_dir(repo_root)?.next().is_some() {
+        return Err(Error::CommandFailed {
+            command: "import-session".to_string(),
+            message: format!(
+                "{} is not empty; run import-session in a new directory",
+                repo_root.display()
+            ),
+        });
+    }
+
+    if !ssh::run_status_check(runner, &config.remote, &ssh::check_dir_exists(remote_path))? {
+        return Err(Error::Remote {
+            remote: config.remote.clone(),
+            message: format!("directory {remote_path} not found."),
+        });
+    }
+    let session_name = match session_name {
+        Some(name) => name.to_string(),
+        None => {
+            let origin = runner
+                .run_ssh(&config.remote, &ssh::git_origin_url(remote_path))?
+                .check("read git origin")?;
+            hashed_session_name_with_origin(repo_root, origin.stdout.trim())?
+        }
+    };
+    let session_name = session_name.as_str();
+    if ssh::run_status_check(
+        runner,
+        &config.remote,
+        &ssh::check_work_dir_exists(session_name),
+    )? {
+        return Err(Error::Remote {
+            remote: config.remote.clone(),
+            message: format!("session '{session_name}' already exists."),
+        });
+    }
+
+    info!("Adopting {remote_path} as session '{session_name}'...");
+    runner
+        .run_ssh(
+            &config.remote,
+            &ssh::link_work_dir(session_name, remote_path),
+        )?
+        .check("link work dir")?;
+    if let Err(e) = adopt(runner, config, session_name, repo_root) {
+        undo_link(runner, config, session_name, repo_root);
+        return Err(e);
+    }
+    info!(
+        "Session '{session_name}' imported into {}.",
+        repo_root.display()
+    );
+    Ok(session_name.to_string())
+}
+
+/// Everything after linking: the origin record, `relocal.toml` and the pull.
+fn adopt(
+    runner: &dyn CommandRunner,
+    config: &Config,
+    session_name: &str,
+    repo_root: &Path,
+) -> Result<()> {
+    remote_meta::write_origin(runner, &config.remote, session_name, repo_root)?;
+
+    // The pull target check needs relocal.toml in place first.
+    let toml_path = repo_root.join("relocal.toml");
+    std::fs::write(&toml_path, local_config_toml(&config.remote)?)?;
+    info!("Created {}", toml_path.display());
+
+    sync_pull(
+        runner,
+        config,
+        session_name,
+        repo_root,
+        &SyncOptions::default(),
+    )
+}
+
+/// Best-effort cleanup after a failed [`adopt`]: a failure here is only
+/// logged, so the original error is what gets reported.
+fn undo_link(runner: &dyn CommandRunner, config: &Config, session_name: &str, repo_root: &Path) {
+    info!("Import failed; removing session '{session_name}' again...");
+    match runner.run_ssh(&config.remote, &ssh::unlink_work_dir(session_name)) {
+        Ok(output) if output.status.success() => {}
+        Ok(output) => warn!(
+            "Could not remove the session link: {}",
+            output.stderr.trim()
+        ),
+        Err(e) => warn!("Could not remove the session link: {e}"),
+    }
+    let toml_path = repo_root.join("relocal.toml");
+    if toml_path.exists() {
+        if let Err(e) = std::fs::remove_file(&toml_path) {
+            warn!("Could not remove {}: {e}", toml_path.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rsync::Direction;
+    use crate::ssh::{STATUS_CHECK_FALSE, STATUS_CHECK_TRUE};
+    use crate::test_support::{Invocation, MockResponse, MockRunner};
+    use tempfile::TempDir;
+
+    fn test_config() -> Config {
+        Config::parse("remote = \"user@host\"").unwrap()
+    }
+
+    #[test]
+    fn local_config_names_only_the_remote() {
+        let toml = local_config_toml("user@host").unwrap();
+        assert_eq!(toml, "remote = \"user@host\"\n");
+        assert_eq!(Config::parse(&toml).unwrap(), test_config());
+    }
+
+    #[test]
+    fn links_records_origin_and_pulls_without_pushing() {
+        let dir = TempDir::new().unwrap();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // remote dir
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into())); // session absent
+        mock.add_response(MockResponse::Ok(String::new())); // link
+        mock.add_response(MockResponse::Ok(String::new())); // origin
+        mock.add_response(MockResponse::Ok(String::new())); // fsck
+        mock.add_response(MockResponse::Ok(String::new())); // rsync
+
+        let session = run(&mock, &test_config(), "~/work/app", Some("s1"), dir.path()).unwrap();
+        assert_eq!(session, "s1");
+
+        let inv = mock.invocations();
+        assert_eq!(inv.len(), 6);
+        match &inv[2] {
+            Invocation::Ssh { command, .. } => {
+                assert_eq!(command, &ssh::link_work_dir("s1", "~/work/app"));
+            }
+            other => panic!("expected Ssh, got {other:?}"),
+        }
+        match &inv[3] {
+            Invocation::Ssh { command, .. } => assert!(command.contains("origin.json")),
+            other => panic!("expected Ssh, got {other:?}"),
+        }
+        let rsyncs: Vec<_> = inv
+            .iter()
+            .filter_map(|i| match i {
+                Invocation::Rsync { direction, .. } => Some(*direction),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(rsyncs, vec![Direction::Pull]);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("relocal.toml")).unwrap(),
+            "remote = \"user@host\"\n"
+        );
+    }
+
+    #[test]
+    fn refuses_non_empty_local_dir() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("README"), "").unwrap();
+        let mock = MockRunner::new();
+
+        let err = run(&mock, &test_config(), "~/work/app", Some("s1"), dir.path()).unwrap_err();
+
+        assert!(err.to_string().contains("not empty"), "{err}");
+        assert!(mock.invocations().is_empty());
+    }
+
+    #[test]
+    fn missing_remote_dir_fails_before_linking() {
+        let dir = TempDir::new().unwrap();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into()));
+
+        let err = run(&mock, &test_config(), "~/nope", Some("s1"), dir.path()).unwrap_err();
+
+        assert!(err.to_string().contains("~/nope not found"), "{err}");
+        assert_eq!(mock.invocations().len(), 1);
+        assert!(!dir.path().join("relocal.toml").exists());
+    }
+
+    #[test]
+    fn existing_session_is_not_replaced() {
+        let dir = TempDir::new().unwrap();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+
+        let err = run(&mock, &test_config(), "~/work/app", Some("s1"), dir.path()).unwrap_err();
+
+        assert!(err.to_string().contains("already exists"), "{err}");
+        assert_eq!(mock.invocations().len(), 2);
+    }
+
+    #[test]
+    fn default_name_uses_the_remote_git_origin() {
+        let parent = TempDir::new().unwrap();
+        let dir = parent.path().join("app");
+        std::fs::create_dir(&dir).unwrap();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // remote dir
+        mock.add_response(MockResponse::Ok("git@example.com:me/app.git\n".into()));
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into())); // session absent
+        mock.add_response(MockResponse::Ok(String::new())); // link
+        mock.add_response(MockResponse::Ok(String::new())); // origin
+        mock.add_response(MockResponse::Ok(String::new())); // fsck
+        mock.add_response(MockResponse::Ok(String::new())); // rsync
+
+        let session = run(&mock, &test_config(), "~/work/app", None, &dir).unwrap();
+
+        // What the next command computes once the pull brought `.git` in.
+        let expected = hashed_session_name_with_origin(&dir, "git@example.com:me/app.git").unwrap();
+        assert_eq!(session, expected);
+        assert_ne!(
+            session,
+            crate::session::hashed_session_name(&dir).unwrap(),
+            "the empty directory has no origin yet"
+        );
+    }
+
+    #[test]
+    fn failed_pull_removes_link_origin_and_config() {
+        let dir = TempDir::new().unwrap();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // remote dir
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into())); // session absent
+        mock.add_response(MockResponse::Ok(String::new())); // link
+        mock.add_response(MockResponse::Ok(String::new())); // origin
+        mock.add_response(MockResponse::Fail("not a git repository".into())); // fsck
+        mock.add_response(MockResponse::Ok(String::new())); // unlink
+
+        let err = run(&mock, &test_config(), "~/work/app", Some("s1"), dir.path()).unwrap_err();
+
+        assert!(matches!(err, Error::RemoteGitFsckFailed { .. }), "{err}");
+        match mock.invocations().last() {
+            Some(Invocation::Ssh { command, .. }) => {
+                assert_eq!(command, &ssh::unlink_work_dir("s1"));
+            }
+            other => panic!("expected Ssh, got {other:?}"),
+        }
+        assert!(!dir.path().join("relocal.toml").exists());
+    }
+}
@@ -2,57 +2,81 @@
 //!
 //! The command prompts for configuration values and writes the file to the
 //! current directory. It is the only command that does not require an existing
-//! `relocal.toml`.
+//! `relocal.toml`. With `--from-existing <path>`, every setting is copied from
+//! another repo's `relocal.toml` and only the remote is prompted for.
 
 use std::path::Path;
 
 use tracing::{info, warn};
 
+use crate::config::{Config, PartialConfig};
 use crate::error::Result;
 
-/// Generates the TOML content for a `relocal.toml` file from collected inputs.
+/// Generates the TOML content for a `relocal.toml` file. Fields at their
+/// default value are omitted.
 ///
 /// This is a pure function (no I/O) so it can be unit-tested independently
 /// of the interactive prompts.
-pub fn generate_toml(remote: &str, exclude: &[String], apt_packages: &[String]) -> String {
-    let mut toml = format!("remote = \"{remote}\"\n");
-
-    if !exclude.is_empty() {
-        toml.push_str(&format!(
-            "exclude = [{}]\n",
-            exclude
-                .iter()
-                .map(|s| format!("\"{s}\""))
-                .collect::<Vec<_>>()
-                .join(", ")
-        ));
-    }
-
-    if !apt_packages.is_empty() {
-        toml.push_str(&format!(
-            "apt_packages = [{}]\n",
-            apt_packages
-                .iter()
-                .map(|s| format!("\"{s}\""))
-                .collect::<Vec<_>>()
-                .join(", ")
-        ));
-    }
-
-    toml
+pub fn generate_toml(config: &Config) -> String {
+    toml::to_string(config).expect("Config serialization cannot fail")
+}
+
+/// Reads the config to import for `--from-existing`. `path` may be a repo
+/// directory or a config file.
+pub fn import_config(path: &Path) -> Result<PartialConfig> {
+    let file = if path.is_dir() {
+        path.join("relocal.toml")
+    } else {
+        path.to_path_buf()
+    };
+    PartialConfig::load(&file)
 }
 
 /// Runs the interactive `relocal init` command, prompting the user and writing
 /// `relocal.toml` to `dir`.
-pub fn run(dir: &Path) -> Result<()> {
+pub fn run(dir: &Path, from_existing: Option<&Path>) -> Result<()> {
     let toml_path = dir.join("relocal.toml");
     if toml_path.exists() {
         warn!("relocal.toml already exists in {}", dir.display());
         return Ok(());
     }
 
+    let contents = match from_existing {
+        Some(path) => {
+            let mut config = import_config(path)?;
+            if config.remote.is_none() && config.remote_encrypted.is_some() {
+                info!("Keeping the encrypted remote from {}", path.display());
+            } else {
+                let mut prompt =
+                    dialoguer::Input::new().with_prompt("Remote (user@host or ssh alias)");
+                if let Some(remote) = config.remote.take() {
+                    prompt = prompt.default(remote);
+                }
+                config.remote = Some(prompt.interact_text().map_err(std::io::Error::other)?);
+            }
+            generate_imported_toml(&config)?
+        }
+        // Resolving validates values (e.g. `bwlimit`) before anything is written.
+        None => generate_toml(&prompt_config()?.resolve()?),
+    };
+    std::fs::write(&toml_path, contents)?;
+
+    info!("Created {}", toml_path.display());
+    Ok(())
+}
+
+/// Generates the TOML for `init --from-existing`: the imported fields as
+/// written, so an encrypted remote stays encrypted and paths stay
+/// unexpanded. Fails if any value is invalid.
+pub fn generate_imported_toml(config: &PartialConfig) -> Result<String> {
+    config.validate()?;
+    Ok(toml::to_string(config).expect("PartialConfig serialization cannot fail"))
+}
+
+/// Prompts for the basic settings of a fresh config.
+fn prompt_config() -> Result<PartialConfig> {
     let remote: String = dialoguer::Input::new()
-        .with_prompt("Remote (user@host)")
+        .with_prompt("Remote (user@host or ssh alias)")
         .interact_text()
         .map_err(std::io::Error::other)?;
 
@@ -63,19 +87,20 @@ pub fn run(dir: &Path) -> Result<()> {
         .map_err(std::io::Error::other)?;
 
     let apt_input: String = dialoguer::Input::new()
-        .with_prompt("APT packages (comma-separated, or empty)")
+        .with_prompt("System packages (comma-separated, or empty)")
         .default(String::new())
         .interact_text()
         .map_err(std::io::Error::other)?;
 
-    let exclude: Vec<String> = parse_comma_list(&exclude_input);
-    let apt_packages: Vec<String> = parse_comma_list(&apt_input);
+    let exclude = parse_comma_list(&exclude_input);
+    let apt_packages = parse_comma_list(&apt_input);
 
-    let content = generate_toml(&remote, &exclude, &apt_packages);
-    std::fs::write(&toml_path, &content)?;
-
-    info!("Created {}", toml_path.display());
-    Ok(())
+    Ok(PartialConfig {
+        remote: Some(remote),
+        exclude: (!exclude.is_empty()).then_some(exclude),
+        apt_packages: (!apt_packages.is_empty()).then_some(apt_packages),
+        ..Default::default()
+    })
 }
 
 /// Splits a comma-separated string into a vec, trimming whitespace and
@@ -92,9 +117,24 @@ fn parse_comma_list(input: &str) -> Vec<String> {
 mod tests {
     use super::*;
 
+    fn generate(config: PartialConfig) -> String {
+        generate_toml(&config.resolve().unwrap())
+    }
+
+    fn partial(remote: &str) -> PartialConfig {
+        PartialConfig {
+            remote: Some(remote.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn strings(items: &[&str]) -> Option<Vec<String>> {
+        Some(items.iter().map(|s| s.to_string()).collect())
+    }
+
     #[test]
     fn generate_minimal() {
-        let toml = generate_toml("user@host", &[], &[]);
+        let toml = generate(partial("user@host"));
         assert_eq!(toml, "remote = \"user@host\"\n");
 
         // Verify it parses back correctly
@@ -106,7 +146,10 @@ mod tests {
 
     #[test]
     fn generate_with_exclude() {
-        let toml = generate_toml("u@h", &[".env".to_string(), "secrets/".to_string()], &[]);
+        let toml = generate(PartialConfig {
+            exclude: strings(&[".env", "secrets/"]),
+            ..partial("u@h")
+        });
         assert!(toml.contains("exclude = [\".env\", \"secrets/\"]"));
 
         let config = crate::config::Config::parse(&toml).unwrap();
@@ -115,11 +158,10 @@ mod tests {
 
     #[test]
     fn generate_with_apt_packages() {
-        let toml = generate_toml(
-            "u@h",
-            &[],
-            &["libssl-dev".to_string(), "pkg-config".to_string()],
-        );
+        let toml = generate(PartialConfig {
+            apt_packages: strings(&["libssl-dev", "pkg-config"]),
+            ..partial("u@h")
+        });
         assert!(toml.contains("apt_packages = [\"libssl-dev\", \"pkg-config\"]"));
 
         let config = crate::config::Config::parse(&toml).unwrap();
@@ -127,17 +169,142 @@ mod tests {
     }
 
     #[test]
-    fn generate_full() {
-        let toml = generate_toml(
-            "user@host",
-            &[".env".to_string()],
-            &["build-essential".to_string()],
+    fn generate_escapes_quotes() {
+        let toml = generate(PartialConfig {
+            exclude: strings(&["we\"ird"]),
+            ..partial("u@h")
+        });
+        let config = crate::config::Config::parse(&toml).unwrap();
+        assert_eq!(config.exclude, vec!["we\"ird"]);
+    }
+
+    fn full_config() -> PartialConfig {
+        PartialConfig {
+            remote: Some("user@host".into()),
+            exclude: strings(&[".env"]),
+            exclude_from: Some(".relocalignore".into()),
+            always_include: strings(&["config/local.toml"]),
+            apt_packages: strings(&["build-essential"]),
+            package_manager: Some(crate::config::PackageManager::Dnf),
+            allowed_remotes: strings(&["user@host"]),
+            bwlimit: Some("2m".into()),
+            rsync_rsh: Some("my-ssh-wrapper".into()),
+            preserve_perms: Some(false),
+            chmod: Some("ug=rwX,o=".into()),
+            verbose_rsync_flags: strings(&["--itemize-changes"]),
+            ignore_vanished: Some(false),
+            ignore_partial: Some(true),
+            pull_fsck: Some(false),
+            ssh_retries: Some(5),
+            push_no_delete: Some(true),
+            one_file_system: Some(true),
+            checksum: Some(true),
+            strict_host_key: Some(true),
+            control_persist: Some(60),
+            force_tty: Some(false),
+            skip_permissions: Some(false),
+            claude_bin: Some("~/.local/bin/claude".into()),
+            claude_version: Some("1.0.30".into()),
+            node_version: Some("20".into()),
+            sync_interval: Some(10),
+            check_timeout: Some(5),
+            install_timeout: Some(900),
+            rsync_timeout: Some(3600),
+            // Fresh configs only get a plaintext remote; imports keep these.
+            remote_encrypted: None,
+            remote_decrypt_command: None,
+        }
+    }
+
+    #[test]
+    fn generate_full_roundtrips_every_field() {
+        let config = full_config();
+        let toml = generate(config.clone());
+        assert_eq!(PartialConfig::parse(&toml, "relocal.toml").unwrap(), config);
+    }
+
+    #[test]
+    fn generate_omits_fields_at_default() {
+        let toml = generate(PartialConfig {
+            exclude: Some(Vec::new()),
+            ignore_vanished: Some(true),
+            ssh_retries: Some(crate::runner::DEFAULT_SSH_RETRIES),
+            skip_permissions: Some(true),
+            ..partial("u@h")
+        });
+        assert_eq!(toml, "remote = \"u@h\"\n");
+    }
+
+    #[test]
+    fn generated_config_resolves_to_same_config() {
+        let config = full_config().resolve().unwrap();
+        assert_eq!(Config::parse(&generate_toml(&config)).unwrap(), config);
+    }
+
+    #[test]
+    fn import_from_repo_dir_roundtrips() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("relocal.toml"), generate(full_config())).unwrap();
+
+        assert_eq!(import_config(dir.path()).unwrap(), full_config());
+    }
+
+    #[test]
+    fn import_from_file_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("other.toml");
+        std::fs::write(&file, "remote = \"a@b\"\nbwlimit = \"1m\"\n").unwrap();
+
+        let config = import_config(&file).unwrap();
+        assert_eq!(config.remote.as_deref(), Some("a@b"));
+        assert_eq!(config.bwlimit.as_deref(), Some("1m"));
+        assert_eq!(config.exclude, None);
+    }
+
+    #[test]
+    fn imported_encrypted_remote_stays_encrypted() {
+        let src = tempfile::tempdir().unwrap();
+        let imported = PartialConfig {
+            remote: None,
+            remote_encrypted: Some("CIPHER".into()),
+            remote_decrypt_command: Some("my-decrypt".into()),
+            ..full_config()
+        };
+        std::fs::write(
+            src.path().join("relocal.toml"),
+            generate_imported_toml(&imported).unwrap(),
+        )
+        .unwrap();
+        let dest = tempfile::tempdir().unwrap();
+
+        // No prompt: the encrypted remote is kept as is.
+        run(dest.path(), Some(src.path())).unwrap();
+
+        let written = std::fs::read_to_string(dest.path().join("relocal.toml")).unwrap();
+        assert!(
+            !written.lines().any(|l| l.starts_with("remote =")),
+            "{written}"
         );
+        assert_eq!(
+            PartialConfig::parse(&written, "relocal.toml").unwrap(),
+            imported
+        );
+    }
 
-        let config = crate::config::Config::parse(&toml).unwrap();
-        assert_eq!(config.remote, "user@host");
-        assert_eq!(config.exclude, vec![".env"]);
-        assert_eq!(config.apt_packages, vec!["build-essential"]);
+    #[test]
+    fn imported_toml_rejects_invalid_values() {
+        let config = PartialConfig {
+            bwlimit: Some("fast".into()),
+            ..partial("u@h")
+        };
+        assert!(generate_imported_toml(&config).is_err());
+    }
+
+    #[test]
+    fn import_missing_config_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = import_config(dir.path()).unwrap_err().to_string();
+        assert!(err.contains("relocal.toml"));
     }
 
     #[test]
@@ -1,67 +1,208 @@
 //! `relocal remote install` — installs the full environment on the remote host.
 //!
-//! Performs eight idempotent steps: APT packages, Homebrew, gh, Rust, Claude Code,
-//! Codex CLI, Claude auth, and Codex auth. Safe to re-run at any time.
+//! Performs eight idempotent steps: system packages, Homebrew, gh, Rust, Claude Code,
+//! Codex CLI, Claude auth, and Codex auth. Safe to re-run at any time. With
+//! `node_version` set, the npm installs wait for a check that the remote's
+//! Node.js matches; `claude_version` pins the Claude Code release. Finishes
+//! by recording this binary's version in the remote compatibility metadata
+//! (see [`crate::remote_meta`]).
 
 use tracing::info;
 
-use crate::config::Config;
+use crate::config::{Config, PackageManager};
 use crate::error::Result;
-use crate::runner::CommandRunner;
+use crate::remote_meta;
+use crate::runner::{CommandRunner, Operation};
 use crate::ssh;
 
-/// Runs all remote installation steps in order.
-pub fn run(runner: &dyn CommandRunner, config: &Config) -> Result<()> {
-    install_apt_packages(runner, config)?;
-    install_homebrew(runner, config)?;
-    install_if_absent(
-        runner,
-        &config.remote,
-        "GitHub CLI",
-        "gh",
-        "brew install gh",
-    )?;
-    install_if_absent(
-        runner,
-        &config.remote,
-        "Rust",
-        "rustup",
-        "curl --proto '=https' --tlsv1.2 -sSf https://sh.rustup.rs | sh -s -- -y",
-    )?;
-    install_if_absent(
-        runner,
-        &config.remote,
-        "Claude Code",
-        "claude",
-        "npm install -g @anthropic-ai/claude-code",
-    )?;
-    install_if_absent(
-        runner,
-        &config.remote,
-        "Codex CLI",
-        "codex",
-        "npm install -g @openai/codex",
-    )?;
-    authenticate_claude(runner, config)?;
-    authenticate_codex(runner, config)?;
+/// A step of the installation, named on the command line by [`Step::name`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Packages,
+    Homebrew,
+    Gh,
+    Rust,
+    Claude,
+    Codex,
+    ClaudeAuth,
+    CodexAuth,
+}
+
+impl Step {
+    /// Every step, in the order they run.
+    pub const ALL: [Step; 8] = [
+        Step::Packages,
+        Step::Homebrew,
+        Step::Gh,
+        Step::Rust,
+        Step::Claude,
+        Step::Codex,
+        Step::ClaudeAuth,
+        Step::CodexAuth,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Step::Packages => "packages",
+            Step::Homebrew => "homebrew",
+            Step::Gh => "gh",
+            Step::Rust => "rust",
+            Step::Claude => "claude",
+            Step::Codex => "codex",
+            Step::ClaudeAuth => "claude-auth",
+            Step::CodexAuth => "codex-auth",
+        }
+    }
+}
+
+/// Parses a step name for `--only` / `--skip`.
+pub fn parse_step(input: &str) -> std::result::Result<Step, String> {
+    Step::ALL
+        .into_iter()
+        .find(|step| step.name() == input)
+        .ok_or_else(|| {
+            let names: Vec<_> = Step::ALL.iter().map(|step| step.name()).collect();
+            format!(
+                "unknown step `{input}`: expected one of {}",
+                names.join(", ")
+            )
+        })
+}
+
+/// Which steps to run: those in `only` (every step when empty), minus those
+/// in `skip`.
+#[derive(Debug, Clone, Default)]
+pub struct StepSelection {
+    pub only: Vec<Step>,
+    pub skip: Vec<Step>,
+}
+
+impl StepSelection {
+    pub fn includes(&self, step: Step) -> bool {
+        (self.only.is_empty() || self.only.contains(&step)) && !self.skip.contains(&step)
+    }
+}
+
+/// Runs the selected remote installation steps in order.
+pub fn run(runner: &dyn CommandRunner, config: &Config, steps: &StepSelection) -> Result<()> {
+    // Never downgrade a remote that a newer relocal has already set up.
+    remote_meta::ensure_compatible(runner, &config.remote)?;
+    for step in Step::ALL {
+        if !steps.includes(step) {
+            info!("Skipping step {}.", step.name());
+            continue;
+        }
+        match step {
+            Step::Packages => install_packages(runner, config)?,
+            Step::Homebrew => install_homebrew(runner, config)?,
+            Step::Gh => install_if_absent(
+                runner,
+                &config.remote,
+                "GitHub CLI",
+                "gh",
+                "brew install gh",
+            )?,
+            Step::Rust => install_if_absent(
+                runner,
+                &config.remote,
+                "Rust",
+                "rustup",
+                "curl --proto '=https' --tlsv1.2 -sSf https://sh.rustup.rs | sh -s -- -y",
+            )?,
+            Step::Claude => {
+                check_node_version(runner, config)?;
+                install_claude_code(runner, config)?;
+            }
+            Step::Codex => {
+                if !steps.includes(Step::Claude) {
+                    check_node_version(runner, config)?;
+                }
+                install_if_absent(
+                    runner,
+                    &config.remote,
+                    "Codex CLI",
+                    "codex",
+                    "npm install -g @openai/codex",
+                )?;
+            }
+            Step::ClaudeAuth => authenticate_claude(runner, config)?,
+            Step::CodexAuth => authenticate_codex(runner, config)?,
+        }
+    }
+    remote_meta::write(runner, &config.remote)?;
 
     info!("Remote installation complete.");
     Ok(())
 }
 
-fn install_apt_packages(runner: &dyn CommandRunner, config: &Config) -> Result<()> {
-    info!("Installing APT packages...");
-    let mut packages = vec![
-        "build-essential".to_string(),
-        "git".to_string(),
-        "nodejs".to_string(),
-        "npm".to_string(),
-    ];
+/// Baseline packages for `manager`: a C toolchain, git and Node.js with npm.
+fn baseline_packages(manager: PackageManager) -> &'static [&'static str] {
+    match manager {
+        PackageManager::Apt => &["build-essential", "git", "nodejs", "npm"],
+        PackageManager::Dnf | PackageManager::Yum => {
+            &["gcc", "gcc-c++", "make", "git", "nodejs", "npm"]
+        }
+        PackageManager::Pacman => &["base-devel", "git", "nodejs", "npm"],
+    }
+}
+
+/// Command that succeeds when every package in `pkg_list` is installed. It
+/// fails with exit code 1 (never more, see [`ssh::run_status_check`]) when
+/// one is missing or the package manager's query tool is not there.
+fn packages_installed_check(manager: PackageManager, pkg_list: &str) -> String {
+    match manager {
+        // Any package not fully installed (or no dpkg at all) prints a line
+        // that is not `ii `.
+        PackageManager::Apt => format!(
+            "test -z \"$(dpkg-query -W -f='${{db:Status-Abbrev}}\\n' {pkg_list} 2>&1 | grep -v '^ii ')\""
+        ),
+        // `rpm -q` exits with the number of missing packages.
+        PackageManager::Dnf | PackageManager::Yum => format!("rpm -q {pkg_list} || false"),
+        PackageManager::Pacman => format!("pacman -Q {pkg_list} || false"),
+    }
+}
+
+/// Command that installs `pkg_list` with `manager`, and the program named
+/// in its error.
+fn package_install_command(manager: PackageManager, pkg_list: &str) -> (String, &'static str) {
+    match manager {
+        PackageManager::Apt => (
+            format!("sudo apt-get update && sudo apt-get install -y {pkg_list}"),
+            "apt-get",
+        ),
+        PackageManager::Dnf => (format!("sudo dnf install -y {pkg_list}"), "dnf"),
+        PackageManager::Yum => (format!("sudo yum install -y {pkg_list}"), "yum"),
+        PackageManager::Pacman => (
+            format!("sudo pacman -Syu --needed --noconfirm {pkg_list}"),
+            "pacman",
+        ),
+    }
+}
+
+/// Installs the baseline and `apt_packages` packages with the configured
+/// `package_manager`, skipping it (and sudo) entirely when every one is
+/// already installed.
+fn install_packages(runner: &dyn CommandRunner, config: &Config) -> Result<()> {
+    let manager = config.package_manager;
+    let mut packages: Vec<String> = baseline_packages(manager)
+        .iter()
+        .map(|p| p.to_string())
+        .collect();
     packages.extend(config.apt_packages.clone());
 
     let pkg_list = packages.join(" ");
-    let cmd = format!("sudo apt-get update && sudo apt-get install -y {pkg_list}");
-    runner.run_ssh(&config.remote, &cmd)?.check("apt-get")?;
+    info!("Checking system packages...");
+    let check = packages_installed_check(manager, &pkg_list);
+    if ssh::run_status_check(runner, &config.remote, &check)? {
+        info!("System packages already installed, skipping.");
+        return Ok(());
+    }
+
+    info!("Installing system packages...");
+    let (cmd, program) = package_install_command(manager, &pkg_list);
+    runner
+        .run_ssh_operation(Operation::Install, &config.remote, &cmd)?
+        .check(program)?;
     Ok(())
 }
 
@@ -73,7 +214,8 @@ fn install_homebrew(runner: &dyn CommandRunner, config: &Config) -> Result<()> {
     }
 
     info!("Installing Homebrew (Linuxbrew)...");
-    runner.run_ssh(
+    runner.run_ssh_operation(
+        Operation::Install,
         &config.remote,
         "NONINTERACTIVE=1 bash -c 'curl -fsSL https://raw.githubusercontent.com/Homebrew/install/HEAD/install.sh | bash'",
     )?.check("homebrew install")?;
@@ -103,20 +245,78 @@ fn install_if_absent(
 
     info!("Installing {name}...");
     runner
-        .run_ssh(remote, install_cmd)?
+        .run_ssh_operation(Operation::Install, remote, install_cmd)?
         .check(&format!("{name} install"))?;
     Ok(())
 }
 
+/// npm package spec for Claude Code, pinned to `claude_version` if set.
+fn claude_code_package(config: &Config) -> String {
+    match &config.claude_version {
+        Some(version) => format!("@anthropic-ai/claude-code@{version}"),
+        None => "@anthropic-ai/claude-code".to_string(),
+    }
+}
+
+/// Installs Claude Code if absent. With `claude_version` set, also replaces
+/// an installed Claude Code that reports a different version.
+fn install_claude_code(runner: &dyn CommandRunner, config: &Config) -> Result<()> {
+    let claude = ssh::shell_path(&config.claude_bin);
+    let install_cmd = format!("npm install -g {}", claude_code_package(config));
+    let Some(version) = &config.claude_version else {
+        return install_if_absent(runner, &config.remote, "Claude Code", &claude, &install_cmd);
+    };
+
+    info!("Checking for Claude Code {version}...");
+    // `claude --version` prints e.g. `1.0.30 (Claude Code)`.
+    let check = format!("test \"$({claude} --version 2>/dev/null | cut -d' ' -f1)\" = {version}");
+    if ssh::run_status_check(runner, &config.remote, &check)? {
+        info!("Claude Code {version} already installed, skipping.");
+        return Ok(());
+    }
+
+    info!("Installing Claude Code {version}...");
+    runner
+        .run_ssh_operation(Operation::Install, &config.remote, &install_cmd)?
+        .check("Claude Code install")?;
+    Ok(())
+}
+
+/// With `node_version` set, fails unless the remote's `node` is that
+/// version (`20` matches any `v20.x`). relocal does not manage Node.js
+/// itself; the error says how to install it.
+fn check_node_version(runner: &dyn CommandRunner, config: &Config) -> Result<()> {
+    let Some(version) = &config.node_version else {
+        return Ok(());
+    };
+    info!("Checking for Node.js {version}...");
+    let check = format!(
+        "case \"$(node --version 2>/dev/null)\" in v{version} | v{version}.*) true ;; *) false ;; esac"
+    );
+    if ssh::run_status_check(runner, &config.remote, &check)? {
+        return Ok(());
+    }
+    Err(crate::error::Error::Remote {
+        remote: config.remote.clone(),
+        message: format!(
+            "node_version {version} is required but the remote's node is missing or a different \
+             version. Install it (e.g. `nvm install {version} && nvm alias default {version}`) \
+             and re-run."
+        ),
+    })
+}
+
 fn authenticate_claude(runner: &dyn CommandRunner, config: &Config) -> Result<()> {
     info!("Checking Claude authentication...");
-    if ssh::run_status_check(runner, &config.remote, "claude auth status")? {
+    let auth_check = ssh::check_claude_authenticated(&config.claude_bin);
+    if ssh::run_status_check(runner, &config.remote, &auth_check)? {
         info!("Claude already authenticated, skipping.");
         return Ok(());
     }
 
     info!("Running claude login (interactive)...");
-    let status = runner.run_ssh_interactive(&config.remote, "claude login")?;
+    let login = format!("{} login", ssh::shell_path(&config.claude_bin));
+    let status = runner.run_ssh_interactive(&config.remote, &login)?;
     if !status.success() {
         return Err(crate::error::Error::CommandFailed {
             command: "claude login".to_string(),
@@ -128,7 +328,7 @@ fn authenticate_claude(runner: &dyn CommandRunner, config: &Config) -> Result<()
 
 fn authenticate_codex(runner: &dyn CommandRunner, config: &Config) -> Result<()> {
     info!("Checking Codex authentication...");
-    if ssh::run_status_check(runner, &config.remote, "test -e ~/.codex/auth.json")? {
+    if ssh::run_status_check(runner, &config.remote, &ssh::check_codex_authenticated())? {
         info!("Codex already authenticated, skipping.");
         return Ok(());
     }
@@ -167,12 +367,13 @@ apt_packages = ["libssl-dev", "pkg-config"]
     #[test]
     fn apt_packages_includes_git() {
         let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into()));
         mock.add_response(MockResponse::Ok(String::new()));
 
-        install_apt_packages(&mock, &test_config()).unwrap();
+        install_packages(&mock, &test_config()).unwrap();
 
         let inv = mock.invocations();
-        match &inv[0] {
+        match &inv[1] {
             Invocation::Ssh { command, .. } => {
                 assert!(command.contains("git"));
             }
@@ -183,14 +384,15 @@ apt_packages = ["libssl-dev", "pkg-config"]
     #[test]
     fn apt_packages_includes_baseline_and_user_packages() {
         let mock = MockRunner::new();
-        // APT install
+        // APT check -> something missing, then install
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into()));
         mock.add_response(MockResponse::Ok(String::new()));
 
-        install_apt_packages(&mock, &config_with_packages()).unwrap();
+        install_packages(&mock, &config_with_packages()).unwrap();
 
         let inv = mock.invocations();
-        assert_eq!(inv.len(), 1);
-        match &inv[0] {
+        assert_eq!(inv.len(), 2);
+        match &inv[1] {
             Invocation::Ssh { command, .. } => {
                 assert!(command.contains("build-essential"));
                 assert!(command.contains("nodejs"));
@@ -315,6 +517,10 @@ apt_packages = ["libssl-dev", "pkg-config"]
             }
             _ => panic!("expected Ssh"),
         }
+        assert_eq!(
+            mock.ssh_operations(),
+            vec![Operation::Check, Operation::Install]
+        );
     }
 
     #[test]
@@ -449,12 +655,78 @@ apt_packages = ["libssl-dev", "pkg-config"]
         assert!(result.unwrap_err().to_string().contains("status probe"));
     }
 
+    #[test]
+    fn apt_skipped_when_all_packages_installed() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+
+        install_packages(&mock, &config_with_packages()).unwrap();
+
+        let inv = mock.invocations();
+        assert_eq!(inv.len(), 1);
+        match &inv[0] {
+            Invocation::Ssh { command, .. } => {
+                assert!(command.contains("dpkg-query"), "{command}");
+                assert!(command.contains("build-essential git nodejs npm libssl-dev pkg-config"));
+                assert!(!command.contains("apt-get"), "{command}");
+            }
+            _ => panic!("expected Ssh"),
+        }
+    }
+
+    #[test]
+    fn packages_use_configured_manager() {
+        let cases = [
+            (
+                "dnf",
+                "rpm -q gcc gcc-c++ make git nodejs npm libssl-dev",
+                "sudo dnf install -y gcc gcc-c++ make git nodejs npm libssl-dev",
+            ),
+            (
+                "yum",
+                "rpm -q gcc gcc-c++ make git nodejs npm libssl-dev",
+                "sudo yum install -y gcc gcc-c++ make git nodejs npm libssl-dev",
+            ),
+            (
+                "pacman",
+                "pacman -Q base-devel git nodejs npm libssl-dev",
+                "sudo pacman -Syu --needed --noconfirm base-devel git nodejs npm libssl-dev",
+            ),
+            (
+                "apt",
+                "dpkg-query",
+                "sudo apt-get update && sudo apt-get install -y build-essential git nodejs npm libssl-dev",
+            ),
+        ];
+        for (manager, check, install) in cases {
+            let config = Config::parse(&format!(
+                "remote = \"user@host\"\npackage_manager = \"{manager}\"\napt_packages = [\"libssl-dev\"]"
+            ))
+            .unwrap();
+            let mock = MockRunner::new();
+            mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into()));
+            mock.add_response(MockResponse::Ok(String::new()));
+
+            install_packages(&mock, &config).unwrap();
+
+            let inv = mock.invocations();
+            match (&inv[0], &inv[1]) {
+                (Invocation::Ssh { command: probe, .. }, Invocation::Ssh { command, .. }) => {
+                    assert!(probe.contains(check), "{manager}: {probe}");
+                    assert_eq!(command, install, "{manager}");
+                }
+                _ => panic!("expected Ssh"),
+            }
+        }
+    }
+
     #[test]
     fn apt_install_failure_returns_error() {
         let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into()));
         mock.add_response(MockResponse::Fail("E: Unable to locate package".into()));
 
-        let result = install_apt_packages(&mock, &test_config());
+        let result = install_packages(&mock, &test_config());
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("apt-get"));
     }
@@ -469,10 +741,79 @@ apt_packages = ["libssl-dev", "pkg-config"]
         assert!(result.is_err());
     }
 
+    #[test]
+    fn claude_code_installs_pinned_version() {
+        let config = Config::parse("remote = \"user@host\"\nclaude_version = \"1.0.30\"").unwrap();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into())); // other version
+        mock.add_response(MockResponse::Ok(String::new())); // install
+
+        install_claude_code(&mock, &config).unwrap();
+
+        let inv = mock.invocations();
+        assert_eq!(inv.len(), 2);
+        match (&inv[0], &inv[1]) {
+            (Invocation::Ssh { command: check, .. }, Invocation::Ssh { command, .. }) => {
+                assert!(check.contains("claude --version"), "{check}");
+                assert!(check.contains("= 1.0.30"), "{check}");
+                assert_eq!(command, "npm install -g @anthropic-ai/claude-code@1.0.30");
+            }
+            _ => panic!("expected Ssh"),
+        }
+    }
+
+    #[test]
+    fn claude_code_pinned_version_skipped_when_installed() {
+        let config = Config::parse("remote = \"user@host\"\nclaude_version = \"1.0.30\"").unwrap();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+
+        install_claude_code(&mock, &config).unwrap();
+        assert_eq!(mock.invocations().len(), 1);
+    }
+
+    #[test]
+    fn claude_code_unpinned_installs_latest_when_absent() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into()));
+        mock.add_response(MockResponse::Ok(String::new()));
+
+        install_claude_code(&mock, &test_config()).unwrap();
+
+        match &mock.invocations()[1] {
+            Invocation::Ssh { command, .. } => {
+                assert_eq!(command, "npm install -g @anthropic-ai/claude-code");
+            }
+            _ => panic!("expected Ssh"),
+        }
+    }
+
+    #[test]
+    fn node_version_checked_only_when_set() {
+        let mock = MockRunner::new();
+        check_node_version(&mock, &test_config()).unwrap();
+        assert!(mock.invocations().is_empty());
+
+        let config = Config::parse("remote = \"user@host\"\nnode_version = \"20\"").unwrap();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        check_node_version(&mock, &config).unwrap();
+        match &mock.invocations()[0] {
+            Invocation::Ssh { command, .. } => assert!(command.contains("v20.*"), "{command}"),
+            _ => panic!("expected Ssh"),
+        }
+
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into()));
+        let err = check_node_version(&mock, &config).unwrap_err();
+        assert!(err.to_string().contains("nvm install 20"), "{err}");
+    }
+
     #[test]
     fn full_run_installs_everything_when_absent() {
         let mock = MockRunner::new();
-        // 1. APT
+        // remote metadata read -> absent
+        mock.add_response(MockResponse::Ok(String::new()));
+        // 1. APT check -> missing, install
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into()));
         mock.add_response(MockResponse::Ok(String::new()));
         // 2. brew check -> absent, install, PATH setup
         mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into()));
@@ -496,8 +837,10 @@ apt_packages = ["libssl-dev", "pkg-config"]
         // 8. codex auth check -> not authenticated, login succeeds
         mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into()));
         mock.add_response(MockResponse::Ok(String::new()));
+        // remote metadata write
+        mock.add_response(MockResponse::Ok(String::new()));
 
-        run(&mock, &test_config()).unwrap();
+        run(&mock, &test_config(), &StepSelection::default()).unwrap();
 
         let inv = mock.invocations();
         let cmds: Vec<&str> = inv
@@ -524,8 +867,10 @@ apt_packages = ["libssl-dev", "pkg-config"]
     #[test]
     fn full_run_issues_all_steps() {
         let mock = MockRunner::new();
-        // 1. APT
+        // remote metadata read -> absent
         mock.add_response(MockResponse::Ok(String::new()));
+        // 1. APT check -> all present
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
         // 2. brew check -> present
         mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
         // 3. gh check -> present
@@ -540,12 +885,15 @@ apt_packages = ["libssl-dev", "pkg-config"]
         mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
         // 8. codex auth check -> authenticated
         mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        // remote metadata write
+        mock.add_response(MockResponse::Ok(String::new()));
 
-        run(&mock, &test_config()).unwrap();
+        run(&mock, &test_config(), &StepSelection::default()).unwrap();
 
         let inv = mock.invocations();
-        // APT(1) + brew(1) + gh(1) + rustup(1) + claude(1) + codex(1) + claude auth(1) + codex auth(1) = 8
-        assert_eq!(inv.len(), 8);
+        // meta read(1) + APT check(1) + brew(1) + gh(1) + rustup(1) + claude(1) + codex(1)
+        // + claude auth(1) + codex auth(1) + meta write(1) = 10
+        assert_eq!(inv.len(), 10);
 
         // All commands go to the right remote
         for i in &inv {
@@ -557,4 +905,116 @@ apt_packages = ["libssl-dev", "pkg-config"]
             }
         }
     }
+
+    #[test]
+    fn only_runs_the_named_steps() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // meta read
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // claude auth
+        mock.add_response(MockResponse::Ok(String::new())); // meta write
+        let steps = StepSelection {
+            only: vec![Step::ClaudeAuth],
+            ..Default::default()
+        };
+
+        run(&mock, &test_config(), &steps).unwrap();
+
+        let inv = mock.invocations();
+        assert_eq!(inv.len(), 3);
+        match &inv[1] {
+            Invocation::Ssh { command, .. } => assert!(command.contains("claude auth status")),
+            other => panic!("expected Ssh, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn skip_leaves_out_the_named_steps() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // meta read
+        for _ in 0..6 {
+            mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        }
+        mock.add_response(MockResponse::Ok(String::new())); // meta write
+        let steps = StepSelection {
+            skip: vec![Step::Packages, Step::Rust],
+            ..Default::default()
+        };
+
+        run(&mock, &test_config(), &steps).unwrap();
+
+        let cmds: Vec<String> = mock
+            .invocations()
+            .into_iter()
+            .filter_map(|i| match i {
+                Invocation::Ssh { command, .. } => Some(command),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(cmds.len(), 8);
+        assert!(!cmds
+            .iter()
+            .any(|c| c.contains("dpkg-query") || c.contains("rustup")));
+    }
+
+    #[test]
+    fn node_version_checked_once_for_codex_alone() {
+        let config = Config::parse("remote = \"user@host\"\nnode_version = \"20\"").unwrap();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // meta read
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // node
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // codex
+        mock.add_response(MockResponse::Ok(String::new())); // meta write
+        let steps = StepSelection {
+            only: vec![Step::Codex],
+            ..Default::default()
+        };
+
+        run(&mock, &config, &steps).unwrap();
+        assert_eq!(mock.invocations().len(), 4);
+    }
+
+    #[test]
+    fn parse_step_names() {
+        for step in Step::ALL {
+            assert_eq!(parse_step(step.name()), Ok(step));
+        }
+        let err = parse_step("hooks").unwrap_err();
+        assert!(err.contains("claude-auth"), "{err}");
+    }
+
+    #[test]
+    fn full_run_refuses_remote_with_newer_protocol() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(format!(
+            "{{\"installed_version\":\"99.0.0\",\"protocol_version\":{}}}",
+            remote_meta::PROTOCOL_VERSION + 1
+        )));
+
+        let result = run(&mock, &test_config(), &StepSelection::default());
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::IncompatibleRemote { .. })
+        ));
+        assert_eq!(mock.invocations().len(), 1);
+    }
+
+    #[test]
+    fn full_run_writes_remote_metadata_last() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new()));
+        for _ in 0..8 {
+            mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        }
+        mock.add_response(MockResponse::Ok(String::new()));
+
+        run(&mock, &test_config(), &StepSelection::default()).unwrap();
+
+        match mock.invocations().last().unwrap() {
+            Invocation::Ssh { command, .. } => {
+                assert!(command.contains(".relocal-meta.json"));
+                assert!(command.contains("printf"));
+            }
+            other => panic!("expected Ssh, got {other:?}"),
+        }
+    }
 }
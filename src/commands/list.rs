@@ -1,37 +1,103 @@
 //! `relocal list` — lists all sessions on the remote.
 //!
-//! Lists directories under `~/relocal/` and prints each session name.
+//! Lists directories under `~/relocal/` and prints each session name. With
+//! `--detail`, also shows each session's recorded origin (see
+//! [`remote_meta::SessionOrigin`]). Output goes to stdout: text lines, or a
+//! JSON array with `--json`.
 
-use tracing::info;
+use serde::Serialize;
 
 use crate::config::Config;
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::remote_meta::{self, SessionOrigin};
 use crate::runner::CommandRunner;
 use crate::ssh;
 
+/// One session as reported by `relocal list`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SessionEntry {
+    pub name: String,
+    pub size: String,
+    /// Only looked up with `--detail`; omitted from JSON when absent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<SessionOrigin>,
+}
+
 /// Lists all sessions on the remote.
-pub fn run(runner: &dyn CommandRunner, config: &Config) -> Result<()> {
-    let output = runner.run_ssh(&config.remote, &ssh::list_sessions())?;
+pub fn run(runner: &dyn CommandRunner, config: &Config, detail: bool, json: bool) -> Result<()> {
+    let command = if detail {
+        ssh::list_sessions_detail()
+    } else {
+        ssh::list_sessions()
+    };
+    let output = runner.run_ssh(&config.remote, &command)?;
 
-    if !output.status.success() || output.stdout.trim().is_empty() {
-        info!("No sessions found on {}.", config.remote);
-        return Ok(());
+    // The listing succeeds with no output when `~/relocal/` does not exist
+    // yet, so a failure here (e.g. ssh's 255) is a real error.
+    if !output.status.success() {
+        return Err(Error::Remote {
+            remote: config.remote.clone(),
+            message: format!("listing sessions failed: {}", output.stderr.trim()),
+        });
     }
+    let entries = parse_entries(&output.stdout, detail);
 
-    for line in output.stdout.lines() {
-        let line = line.trim();
-        if !line.is_empty() {
-            if let Some((name, size)) = line.split_once('\t') {
-                info!("{name}\t{size}");
-            } else {
-                info!("{line}");
-            }
-        }
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&entries).expect("SessionEntry serialization cannot fail")
+        );
+        return Ok(());
     }
 
+    if entries.is_empty() {
+        println!("No sessions found on {}.", config.remote);
+        return Ok(());
+    }
+    for entry in &entries {
+        println!("{}", format_entry(entry, detail));
+    }
     Ok(())
 }
 
+/// Parses `<name>\t<size>[\t<origin-json>]` lines from the listing command.
+fn parse_entries(stdout: &str, detail: bool) -> Vec<SessionEntry> {
+    stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let name = fields.next().unwrap_or_default().to_string();
+            let size = fields.next().unwrap_or_default().to_string();
+            let origin = if detail {
+                fields.next().and_then(remote_meta::parse_origin)
+            } else {
+                None
+            };
+            SessionEntry { name, size, origin }
+        })
+        .collect()
+}
+
+/// Formats an entry as a tab-separated text line.
+fn format_entry(entry: &SessionEntry, detail: bool) -> String {
+    let mut line = if entry.size.is_empty() {
+        entry.name.clone()
+    } else {
+        format!("{}\t{}", entry.name, entry.size)
+    };
+    if detail {
+        let origin = match &entry.origin {
+            Some(origin) => format!("{}  {}", origin.remote, origin.local_path),
+            None => "(no origin recorded)".to_string(),
+        };
+        line.push('\t');
+        line.push_str(&origin);
+    }
+    line
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,7 +112,7 @@ mod tests {
         let mock = MockRunner::new();
         mock.add_response(MockResponse::Ok("project-a\t4.0K\nproject-b\t12K\n".into()));
 
-        run(&mock, &test_config()).unwrap();
+        run(&mock, &test_config(), false, false).unwrap();
 
         let inv = mock.invocations();
         assert_eq!(inv.len(), 1);
@@ -60,13 +126,13 @@ mod tests {
     }
 
     #[test]
-    fn handles_no_sessions() {
+    fn failed_listing_is_an_error() {
         let mock = MockRunner::new();
-        // ls fails or returns empty (no ~/relocal/ dir yet)
-        mock.add_response(MockResponse::Fail(String::new()));
+        mock.add_response(MockResponse::Exit(255, "Connection refused".into()));
 
-        // Should not error
-        run(&mock, &test_config()).unwrap();
+        let err = run(&mock, &test_config(), false, false).unwrap_err();
+        assert!(matches!(err, Error::Remote { .. }), "{err:?}");
+        assert!(err.to_string().contains("Connection refused"), "{err}");
     }
 
     #[test]
@@ -74,6 +140,111 @@ mod tests {
         let mock = MockRunner::new();
         mock.add_response(MockResponse::Ok(String::new()));
 
-        run(&mock, &test_config()).unwrap();
+        run(&mock, &test_config(), false, false).unwrap();
+    }
+
+    #[test]
+    fn detail_uses_origin_listing_command() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(
+            "a\t4.0K\t{\"remote\":\"user@host\",\"local_path\":\"/home/u/a\"}\n".into(),
+        ));
+
+        run(&mock, &test_config(), true, false).unwrap();
+
+        match &mock.invocations()[0] {
+            Invocation::Ssh { command, .. } => {
+                assert!(command.contains(".relocal/origin.json"));
+            }
+            _ => panic!("expected Ssh"),
+        }
+    }
+
+    fn detail_line(line: &str) -> String {
+        let entries = parse_entries(line, true);
+        format_entry(&entries[0], true)
+    }
+
+    #[test]
+    fn detail_line_shows_origin() {
+        let line = "a\t4.0K\t{\"remote\":\"user@host\",\"local_path\":\"/home/u/a\"}";
+        assert_eq!(detail_line(line), "a\t4.0K\tuser@host  /home/u/a");
+    }
+
+    #[test]
+    fn detail_line_without_origin() {
+        assert_eq!(detail_line("old\t12K\t"), "old\t12K\t(no origin recorded)");
+        assert_eq!(detail_line("old\t12K"), "old\t12K\t(no origin recorded)");
+    }
+
+    #[test]
+    fn detail_line_with_malformed_origin() {
+        assert_eq!(detail_line("x\t1K\t{oops"), "x\t1K\t(no origin recorded)");
+    }
+
+    #[test]
+    fn parse_entries_name_and_size() {
+        let entries = parse_entries("project-a\t4.0K\n\nproject-b\t12K\n", false);
+        assert_eq!(
+            entries,
+            vec![
+                SessionEntry {
+                    name: "project-a".to_string(),
+                    size: "4.0K".to_string(),
+                    origin: None,
+                },
+                SessionEntry {
+                    name: "project-b".to_string(),
+                    size: "12K".to_string(),
+                    origin: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_entries_ignores_origin_without_detail() {
+        let line = "a\t4.0K\t{\"remote\":\"h\",\"local_path\":\"/p\"}";
+        assert_eq!(parse_entries(line, false)[0].origin, None);
+    }
+
+    #[test]
+    fn json_entries_have_name_and_size() {
+        let entries = parse_entries("a\t4.0K\n", false);
+        let value = serde_json::to_value(&entries).unwrap();
+        assert_eq!(value, serde_json::json!([{ "name": "a", "size": "4.0K" }]));
+    }
+
+    #[test]
+    fn json_entries_include_origin_with_detail() {
+        let entries = parse_entries("a\t4.0K\t{\"remote\":\"h\",\"local_path\":\"/p\"}\n", true);
+        let value = serde_json::to_value(&entries).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!([{
+                "name": "a",
+                "size": "4.0K",
+                "origin": { "remote": "h", "local_path": "/p" }
+            }])
+        );
+    }
+
+    #[test]
+    fn json_mode_handles_no_sessions() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new()));
+
+        // Prints `[]` rather than the text message; must not error.
+        run(&mock, &test_config(), false, true).unwrap();
+        assert!(parse_entries("", false).is_empty());
+    }
+
+    #[test]
+    fn json_mode_does_not_hide_a_failed_connection() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Exit(255, "Connection refused".into()));
+
+        let err = run(&mock, &test_config(), false, true).unwrap_err();
+        assert!(matches!(err, Error::Remote { .. }), "{err:?}");
     }
 }
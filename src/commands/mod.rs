@@ -1,14 +1,27 @@
 //! Implementations of each CLI subcommand.
 
+pub mod attach;
+pub mod benchmark;
 pub mod claude;
 pub mod codex;
+pub mod completions;
+pub mod config_check;
 pub mod destroy;
+pub mod doctor;
+pub mod env;
+pub mod exec;
+pub mod import_session;
 pub mod init;
 pub mod install;
 pub mod list;
 pub mod log;
 pub mod nuke;
+pub mod prune;
+pub mod rename;
 pub mod session;
+pub mod sessions;
 pub mod ssh;
 pub mod status;
 pub mod sync;
+pub mod watch;
+pub mod whoami;
@@ -1,7 +1,7 @@
 //! `relocal remote nuke` — deletes everything under `~/relocal/` on the remote.
 //!
 //! This is a development/upgrade escape hatch for when you want a clean slate.
-//! It does NOT uninstall APT packages, Rust, Claude Code, or Codex.
+//! It does NOT uninstall system packages, Rust, Claude Code, or Codex.
 
 use tracing::info;
 
@@ -14,7 +14,11 @@ use crate::ssh;
 ///
 /// If `confirm` is true, prompts the user for confirmation before proceeding.
 /// Pass `false` in tests to skip the interactive prompt.
-pub fn run(runner: &dyn CommandRunner, config: &Config, confirm: bool) -> Result<()> {
+///
+/// Refuses a remote outside `allowed_remotes` unless `force` is set.
+pub fn run(runner: &dyn CommandRunner, config: &Config, confirm: bool, force: bool) -> Result<()> {
+    config.check_remote_allowed(force)?;
+
     if confirm {
         let prompt = format!(
             "Delete ALL relocal data on {}? This removes ~/relocal/ entirely \
@@ -56,7 +60,7 @@ mod tests {
         let mock = MockRunner::new();
         mock.add_response(MockResponse::Ok(String::new()));
 
-        run(&mock, &test_config(), false).unwrap();
+        run(&mock, &test_config(), false, false).unwrap();
 
         let inv = mock.invocations();
         assert_eq!(inv.len(), 1);
@@ -76,7 +80,7 @@ mod tests {
         mock.add_response(MockResponse::Ok(String::new()));
 
         let config = Config::parse("remote = \"deploy@prod\"").unwrap();
-        run(&mock, &config, false).unwrap();
+        run(&mock, &config, false, false).unwrap();
 
         let inv = mock.invocations();
         match &inv[0] {
@@ -90,7 +94,39 @@ mod tests {
         let mock = MockRunner::new();
         mock.add_response(MockResponse::Fail("permission denied".into()));
 
-        let result = run(&mock, &test_config(), false);
+        let result = run(&mock, &test_config(), false, false);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn refuses_remote_outside_allowlist() {
+        let mock = MockRunner::new();
+        let config =
+            Config::parse("remote = \"deploy@prod\"\nallowed_remotes = [\"me@dev\"]").unwrap();
+
+        let err = run(&mock, &config, false, false).unwrap_err();
+        assert!(matches!(err, crate::error::Error::RemoteNotAllowed { .. }));
+        assert!(mock.invocations().is_empty());
+    }
+
+    #[test]
+    fn proceeds_for_allowlisted_remote() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new()));
+        let config = Config::parse("remote = \"me@dev\"\nallowed_remotes = [\"me@dev\"]").unwrap();
+
+        run(&mock, &config, false, false).unwrap();
+        assert_eq!(mock.invocations().len(), 1);
+    }
+
+    #[test]
+    fn force_overrides_allowlist() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new()));
+        let config =
+            Config::parse("remote = \"deploy@prod\"\nallowed_remotes = [\"me@dev\"]").unwrap();
+
+        run(&mock, &config, false, true).unwrap();
+        assert_eq!(mock.invocations().len(), 1);
+    }
 }
@@ -0,0 +1,365 @@
+//! `relocal prune --older-than <age>` — removes stale sessions from the remote.
+//!
+//! Lists every session with its age (since relocal last set it up, synced it
+//! or heard the daemon's heartbeat, measured against the remote's clock; see
+//! [`ssh::list_sessions_with_age`]) and destroys those older than the
+//! threshold after one confirmation. Sessions with a lock file may be in use
+//! and are skipped unless `--include-locked` is given.
+
+use tracing::{info, warn};
+
+use crate::commands::destroy::{self, DestroyOptions};
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::runner::CommandRunner;
+use crate::ssh;
+
+/// One session as reported by [`ssh::list_sessions_with_age`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionAge {
+    pub name: String,
+    /// Seconds since relocal last touched the session.
+    pub age_secs: u64,
+    /// Whether the session has a lock file (a session may be running).
+    pub locked: bool,
+}
+
+/// Destroys every session older than `older_than` seconds and returns the
+/// names of the sessions removed.
+///
+/// Locked sessions are skipped unless `include_locked`. With
+/// `options.confirm`, prompts once for the whole batch; the other options are
+/// passed on to [`destroy::run`] for each session.
+pub fn run(
+    runner: &dyn CommandRunner,
+    config: &Config,
+    older_than: u64,
+    include_locked: bool,
+    options: DestroyOptions,
+) -> Result<Vec<String>> {
+    config.check_remote_allowed(options.force)?;
+
+    let output = runner.run_ssh(&config.remote, &ssh::list_sessions_with_age())?;
+    // The listing succeeds with no output when `~/relocal/` does not exist
+    // yet, so a failure here (e.g. ssh's 255) is a real error.
+    if !output.status.success() {
+        return Err(Error::Remote {
+            remote: config.remote.clone(),
+            message: format!("listing sessions failed: {}", output.stderr.trim()),
+        });
+    }
+    let sessions = parse_session_ages(&output.stdout);
+
+    let mut stale = Vec::new();
+    for session in sessions.iter().filter(|s| s.age_secs > older_than) {
+        if session.locked && !include_locked {
+            info!(
+                "Skipping '{}' ({}): it is locked and may be in use. Use --include-locked to prune it anyway.",
+                session.name,
+                format_age(session.age_secs)
+            );
+        } else {
+            stale.push(session);
+        }
+    }
+    if stale.is_empty() {
+        info!(
+            "No sessions older than {} on {}.",
+            format_age(older_than),
+            config.remote
+        );
+        return Ok(Vec::new());
+    }
+
+    info!(
+        "Sessions older than {} on {}:",
+        format_age(older_than),
+        config.remote
+    );
+    for session in &stale {
+        info!("  {}\t{} old", session.name, format_age(session.age_secs));
+    }
+
+    if options.confirm {
+        let confirmed = dialoguer::Confirm::new()
+            .with_prompt(format!("Destroy {} session(s)?", stale.len()))
+            .default(false)
+            .interact()
+            .map_err(std::io::Error::other)?;
+        if !confirmed {
+            info!("Aborted.");
+            return Ok(Vec::new());
+        }
+    }
+
+    let mut pruned = Vec::new();
+    let mut failed = 0;
+    for session in stale {
+        match destroy::run(
+            runner,
+            config,
+            &session.name,
+            DestroyOptions {
+                confirm: false,
+                ..options
+            },
+        ) {
+            Ok(_) => pruned.push(session.name.clone()),
+            Err(e) => {
+                warn!("Could not prune '{}': {e}", session.name);
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(Error::Remote {
+            remote: config.remote.clone(),
+            message: format!("{failed} session(s) could not be pruned"),
+        });
+    }
+    Ok(pruned)
+}
+
+/// Parses `<name>\t<age-secs>\t<locked>` lines. Malformed lines are skipped.
+fn parse_session_ages(stdout: &str) -> Vec<SessionAge> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.trim().split('\t');
+            let name = fields.next().filter(|n| !n.is_empty())?;
+            let age_secs = fields.next()?.parse().ok()?;
+            let locked = fields.next()? == "1";
+            Some(SessionAge {
+                name: name.to_string(),
+                age_secs,
+                locked,
+            })
+        })
+        .collect()
+}
+
+/// Parses an age like `7d`, `12h`, `30m` or `90s` (a bare number is days)
+/// into seconds.
+pub fn parse_age(input: &str) -> std::result::Result<u64, String> {
+    let number = input.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let multiplier = match &input[number.len()..] {
+        "" | "d" => 86_400,
+        "h" => 3_600,
+        "m" => 60,
+        "s" => 1,
+        unit => return Err(format!("unknown unit `{unit}`: expected d, h, m or s")),
+    };
+    let value: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid age `{input}`: expected e.g. 7d, 12h or 30m"))?;
+    value
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("age `{input}` is too large"))
+}
+
+/// Coarse human-readable duration, e.g. `3d` or `5h`.
+fn format_age(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ssh::STATUS_CHECK_TRUE;
+    use crate::test_support::{Invocation, MockResponse, MockRunner};
+
+    const DAY: u64 = 86_400;
+
+    fn test_config() -> Config {
+        Config::parse("remote = \"user@host\"").unwrap()
+    }
+
+    fn ssh_commands(mock: &MockRunner) -> Vec<String> {
+        mock.invocations()
+            .into_iter()
+            .filter_map(|inv| match inv {
+                Invocation::Ssh { command, .. } => Some(command),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Queues the responses `destroy::run` consumes for one session.
+    fn queue_destroy(mock: &MockRunner) {
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // dir exists
+        mock.add_response(MockResponse::Ok(String::new())); // rm work dir
+        mock.add_response(MockResponse::Ok(String::new())); // rm lock
+    }
+
+    #[test]
+    fn parse_age_units() {
+        assert_eq!(parse_age("7d"), Ok(7 * DAY));
+        assert_eq!(parse_age("7"), Ok(7 * DAY));
+        assert_eq!(parse_age("12h"), Ok(12 * 3_600));
+        assert_eq!(parse_age("30m"), Ok(1_800));
+        assert_eq!(parse_age("90s"), Ok(90));
+    }
+
+    #[test]
+    fn parse_age_rejects_garbage() {
+        assert!(parse_age("7w").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("-1d").is_err());
+        assert!(parse_age("").is_err());
+    }
+
+    #[test]
+    fn parse_session_ages_reads_fields() {
+        let sessions = parse_session_ages("old\t900000\t0\nbusy\t5\t1\nbroken\tx\t0\n\n");
+        assert_eq!(
+            sessions,
+            vec![
+                SessionAge {
+                    name: "old".into(),
+                    age_secs: 900_000,
+                    locked: false,
+                },
+                SessionAge {
+                    name: "busy".into(),
+                    age_secs: 5,
+                    locked: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn prunes_only_sessions_past_the_threshold() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(format!(
+            "stale\t{}\t0\nfresh\t{}\t0\n",
+            10 * DAY,
+            DAY
+        )));
+        queue_destroy(&mock);
+
+        let pruned = run(
+            &mock,
+            &test_config(),
+            7 * DAY,
+            false,
+            DestroyOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(pruned, vec!["stale"]);
+        let cmds = ssh_commands(&mock);
+        assert_eq!(cmds.len(), 4);
+        assert!(cmds.iter().any(|c| c == "rm -rf ~/relocal/stale"));
+        assert!(!cmds.iter().any(|c| c.contains("fresh")));
+    }
+
+    #[test]
+    fn skips_locked_sessions_even_with_force() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(format!("busy\t{}\t1\n", 10 * DAY)));
+
+        let pruned = run(
+            &mock,
+            &test_config(),
+            7 * DAY,
+            false,
+            DestroyOptions {
+                force: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert!(pruned.is_empty());
+        assert_eq!(mock.invocations().len(), 1);
+    }
+
+    #[test]
+    fn include_locked_prunes_locked_sessions() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(format!("busy\t{}\t1\n", 10 * DAY)));
+        queue_destroy(&mock);
+
+        let pruned = run(
+            &mock,
+            &test_config(),
+            7 * DAY,
+            true,
+            DestroyOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(pruned, vec!["busy"]);
+        assert!(ssh_commands(&mock)
+            .iter()
+            .any(|c| c == "rm -rf ~/relocal/busy"));
+    }
+
+    #[test]
+    fn missing_relocal_dir_prunes_nothing() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new()));
+
+        let pruned = run(&mock, &test_config(), DAY, false, DestroyOptions::default()).unwrap();
+
+        assert!(pruned.is_empty());
+        assert_eq!(mock.invocations().len(), 1);
+    }
+
+    #[test]
+    fn failed_listing_is_an_error() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Exit(255, "Connection refused".into()));
+
+        let err = run(&mock, &test_config(), DAY, false, DestroyOptions::default()).unwrap_err();
+
+        assert!(matches!(err, Error::Remote { .. }), "{err:?}");
+        assert!(err.to_string().contains("Connection refused"), "{err}");
+        assert_eq!(mock.invocations().len(), 1);
+    }
+
+    #[test]
+    fn failed_destroy_is_reported_after_the_rest() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(format!(
+            "a\t{0}\t0\nb\t{0}\t0\n",
+            10 * DAY
+        )));
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // a: dir exists
+        mock.add_response(MockResponse::Fail("permission denied".into())); // a: rm
+        queue_destroy(&mock); // b
+
+        let err = run(
+            &mock,
+            &test_config(),
+            7 * DAY,
+            false,
+            DestroyOptions::default(),
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("1 session(s)"), "{err}");
+        assert!(ssh_commands(&mock)
+            .iter()
+            .any(|c| c == "rm -rf ~/relocal/b"));
+    }
+
+    #[test]
+    fn refused_outside_allowed_remotes() {
+        let config =
+            Config::parse("remote = \"user@host\"\nallowed_remotes = [\"me@dev\"]").unwrap();
+        let mock = MockRunner::new();
+
+        assert!(matches!(
+            run(&mock, &config, DAY, false, DestroyOptions::default()),
+            Err(Error::RemoteNotAllowed { .. })
+        ));
+        assert!(mock.invocations().is_empty());
+    }
+}
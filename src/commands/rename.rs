@@ -0,0 +1,158 @@
+//! `relocal rename <old> <new>` — renames a session on the remote.
+//!
+//! Moves the remote working copy `~/relocal/<old>/` to `~/relocal/<new>/`,
+//! keeping its contents (including the `.relocal/` origin record). Refuses
+//! while the session is active: a running daemon or a remote lock file.
+
+use tracing::info;
+
+use crate::config::Config;
+use crate::daemon_client;
+use crate::error::{Error, Result};
+use crate::runner::CommandRunner;
+use crate::session::validate_session_name;
+use crate::ssh;
+
+/// Renames session `old` to `new` on the remote.
+///
+/// If `check_daemon` is true, refuses to proceed when a daemon is running
+/// for `old`. Pass `false` in tests to skip the daemon check.
+pub fn run(
+    runner: &dyn CommandRunner,
+    config: &Config,
+    old: &str,
+    new: &str,
+    check_daemon: bool,
+) -> Result<()> {
+    validate_session_name(old)?;
+    validate_session_name(new)?;
+    if old == new {
+        return Err(Error::CommandFailed {
+            command: "rename".to_string(),
+            message: "old and new session names are the same".to_string(),
+        });
+    }
+
+    if check_daemon && daemon_client::is_daemon_running(old, &config.remote) {
+        return Err(Error::Remote {
+            remote: config.remote.clone(),
+            message: format!(
+                "session '{old}' has a running daemon. \
+                 Exit all relocal claude/codex/ssh sessions for this project first, \
+                 then retry."
+            ),
+        });
+    }
+
+    if !ssh::run_status_check(runner, &config.remote, &ssh::check_work_dir_exists(old))? {
+        return Err(Error::Remote {
+            remote: config.remote.clone(),
+            message: format!("session '{old}' not found. No working directory exists."),
+        });
+    }
+    if ssh::run_status_check(runner, &config.remote, &ssh::check_work_dir_exists(new))? {
+        return Err(Error::Remote {
+            remote: config.remote.clone(),
+            message: format!("session '{new}' already exists."),
+        });
+    }
+    if ssh::run_status_check(runner, &config.remote, &ssh::check_lock_file_exists(old))? {
+        return Err(Error::SessionInUse {
+            session: old.to_string(),
+        });
+    }
+
+    info!(
+        "Renaming session '{old}' to '{new}' on {}...",
+        config.remote
+    );
+    runner
+        .run_ssh(&config.remote, &ssh::mv_work_dir(old, new))?
+        .check("mv work dir")?;
+    info!("Session '{old}' renamed to '{new}'.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ssh::{STATUS_CHECK_FALSE, STATUS_CHECK_TRUE};
+    use crate::test_support::{Invocation, MockResponse, MockRunner};
+
+    fn test_config() -> Config {
+        Config::parse("remote = \"user@host\"").unwrap()
+    }
+
+    fn ssh_commands(mock: &MockRunner) -> Vec<String> {
+        mock.invocations()
+            .into_iter()
+            .filter_map(|inv| match inv {
+                Invocation::Ssh { command, .. } => Some(command),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn moves_the_work_dir() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // old exists
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into())); // new absent
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into())); // no lock
+        mock.add_response(MockResponse::Ok(String::new())); // mv
+
+        run(&mock, &test_config(), "old", "new", false).unwrap();
+
+        let cmds = ssh_commands(&mock);
+        assert_eq!(cmds.len(), 4);
+        assert_eq!(cmds[3], ssh::mv_work_dir("old", "new"));
+    }
+
+    #[test]
+    fn invalid_new_name_rejected_before_connecting() {
+        let mock = MockRunner::new();
+        let err = run(&mock, &test_config(), "old", "../escape", false).unwrap_err();
+        assert!(matches!(err, Error::InvalidSessionName { .. }), "{err}");
+        assert!(mock.invocations().is_empty());
+    }
+
+    #[test]
+    fn same_name_rejected() {
+        let mock = MockRunner::new();
+        assert!(run(&mock, &test_config(), "s1", "s1", false).is_err());
+        assert!(mock.invocations().is_empty());
+    }
+
+    #[test]
+    fn missing_old_session_fails() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into())); // old absent
+
+        let err = run(&mock, &test_config(), "old", "new", false).unwrap_err();
+        assert!(err.to_string().contains("not found"), "{err}");
+        assert_eq!(mock.invocations().len(), 1);
+    }
+
+    #[test]
+    fn existing_new_session_fails() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // old exists
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // new exists
+
+        let err = run(&mock, &test_config(), "old", "new", false).unwrap_err();
+        assert!(err.to_string().contains("already exists"), "{err}");
+        assert_eq!(mock.invocations().len(), 2);
+    }
+
+    #[test]
+    fn locked_session_refused() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // old exists
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into())); // new absent
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // locked
+
+        let err = run(&mock, &test_config(), "old", "new", false).unwrap_err();
+        assert!(matches!(err, Error::SessionInUse { ref session } if session == "old"));
+        assert!(!ssh_commands(&mock).iter().any(|c| c.contains("mv ")));
+    }
+}
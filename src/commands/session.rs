@@ -2,7 +2,7 @@
 //!
 //! Both commands follow the same lifecycle: connect to (or spawn) the session
 //! daemon, verify the tool is installed on the remote, launch an interactive
-//! SSH session, and disconnect. The daemon owns the ControlMaster, background
+//! SSH session (or, detached, a tmux session), and disconnect. The daemon owns the ControlMaster, background
 //! sync loop, and remote lock file.
 
 use std::path::Path;
@@ -10,6 +10,7 @@ use std::path::Path;
 use tracing::{debug, error, info, warn};
 
 use crate::config::Config;
+use crate::daemon::DaemonOptions;
 use crate::daemon_client;
 use crate::error::{Error, Result};
 use crate::runner::{CommandRunner, ProcessRunner};
@@ -21,37 +22,73 @@ pub struct ToolConfig {
     pub display_name: &'static str,
 
     /// Shell command to check whether the tool is installed on the remote.
-    pub check_installed: fn() -> String,
+    pub check_installed: fn(&Config) -> String,
 
-    /// Shell command to launch an interactive session in the remote working directory.
-    pub start_session: fn(&str, &[String]) -> String,
+    /// Shell command to launch an interactive session in the remote working
+    /// directory, given the config, the session name and extra args.
+    pub start_session: fn(&Config, &str, &[String]) -> String,
+}
+
+/// How to launch the tool once the session is up.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Launch<'a> {
+    /// Extra arguments passed through to the tool.
+    pub extra_args: &'a [String],
+    /// Launch in a detached tmux session and return right away; the daemon
+    /// keeps syncing until that session ends (see [`launch_detached`]).
+    pub detach: bool,
 }
 
 /// Connects to the session daemon, checks the tool, and runs an interactive session.
+///
+/// `options` only apply if this call spawns the daemon (see
+/// [`DaemonOptions`]).
 pub fn run(
     tool: &ToolConfig,
     config: &Config,
     session_name: &str,
     repo_root: &Path,
     verbosity: u8,
-    extra_args: &[String],
+    options: DaemonOptions,
+    launch: Launch,
 ) -> Result<()> {
+    if options.ephemeral {
+        warn!(
+            "Ephemeral session: {} is deleted when the session ends. Remote changes are \
+             pulled first; if that pull fails the directory is kept.",
+            ssh::remote_work_dir(session_name)
+        );
+    }
     debug!("Connecting to session daemon for {session_name}...");
-    let daemon_conn =
-        daemon_client::connect_or_spawn(session_name, &config.remote, repo_root, verbosity)
-            .inspect_err(|_| {
-                info!("Run `relocal log {session_name}` to see daemon logs.");
-            })?;
+    let daemon_conn = daemon_client::connect_or_spawn(
+        session_name,
+        &config.remote,
+        repo_root,
+        config.config_path.as_deref(),
+        verbosity,
+        options,
+    )
+    .inspect_err(|_| {
+        info!("Run `relocal log {session_name}` to see daemon logs.");
+    })?;
     debug!(
         "Daemon connected, control master at {}",
         daemon_conn.control_master_path().display()
     );
-    let runner = ProcessRunner::with_control_path(daemon_conn.control_master_path());
+    let runner = ProcessRunner::with_control_path(daemon_conn.control_master_path())
+        .with_config(config)
+        .with_strict_host_key(config.strict_host_key);
 
     check_tool_installed(tool, &runner, config)?;
     debug!("{} installation verified", tool.display_name);
 
-    let remote_cmd = (tool.start_session)(session_name, extra_args);
+    let remote_cmd = (tool.start_session)(config, session_name, launch.extra_args);
+    if launch.detach {
+        let result = launch_detached(tool, &runner, config, session_name, &remote_cmd);
+        // The daemon sees the tmux session and defers its cleanup.
+        drop(daemon_conn);
+        return result;
+    }
     info!("Launching {} on {}...", tool.display_name, config.remote);
 
     let ssh_result = runner.run_ssh_interactive(&config.remote, &remote_cmd);
@@ -84,7 +121,7 @@ fn check_tool_installed(
     config: &Config,
 ) -> Result<()> {
     info!("Checking {} installation...", tool.display_name);
-    let installed = ssh::run_status_check(runner, &config.remote, &(tool.check_installed)())?;
+    let installed = ssh::run_status_check(runner, &config.remote, &(tool.check_installed)(config))?;
     if !installed {
         return Err(Error::Remote {
             remote: config.remote.clone(),
@@ -97,6 +134,42 @@ fn check_tool_installed(
     Ok(())
 }
 
+/// Launches `remote_cmd` in the session's detached tmux session.
+///
+/// When the last client disconnects, the daemon checks for that tmux session
+/// and, while it runs, keeps pulling in the background instead of doing its
+/// final pull and cleanup; those happen once the tool exits.
+fn launch_detached(
+    tool: &ToolConfig,
+    runner: &dyn crate::runner::CommandRunner,
+    config: &Config,
+    session_name: &str,
+    remote_cmd: &str,
+) -> Result<()> {
+    if !ssh::run_status_check(runner, &config.remote, &ssh::check_tmux_installed())? {
+        return Err(Error::Remote {
+            remote: config.remote.clone(),
+            message: "tmux is not installed; --detach needs it to keep the session running."
+                .to_string(),
+        });
+    }
+    info!(
+        "Launching {} detached on {}...",
+        tool.display_name, config.remote
+    );
+    runner
+        .run_ssh(
+            &config.remote,
+            &ssh::start_detached_session(session_name, remote_cmd),
+        )?
+        .check("tmux new-session")?;
+    info!(
+        "{} is running detached. Reattach with `relocal attach {session_name}`.",
+        tool.display_name
+    );
+    Ok(())
+}
+
 fn print_summary(session_name: &str, config: &Config) {
     info!(
         "Session ended: {session_name} (remote: {}, dir: {})",
@@ -129,8 +202,8 @@ mod tests {
     fn test_tool() -> ToolConfig {
         ToolConfig {
             display_name: "TestTool",
-            check_installed: || "command -v testtool".to_string(),
-            start_session: |_session, _args| "testtool".to_string(),
+            check_installed: |_config| "command -v testtool".to_string(),
+            start_session: |_config, _session, _args| "testtool".to_string(),
         }
     }
 
@@ -153,17 +226,51 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("not installed"));
     }
 
+    #[test]
+    fn launch_detached_starts_tmux_session() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // tmux
+        mock.add_response(MockResponse::Ok(String::new())); // new-session
+
+        launch_detached(&test_tool(), &mock, &test_config(), "s1", "testtool").unwrap();
+
+        use crate::test_support::Invocation;
+        let inv = mock.invocations();
+        assert_eq!(inv.len(), 2);
+        match &inv[1] {
+            Invocation::Ssh { command, .. } => {
+                assert_eq!(command, "tmux new-session -d -s relocal-s1 testtool");
+            }
+            _ => panic!("expected Ssh"),
+        }
+    }
+
+    #[test]
+    fn launch_detached_requires_tmux() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into()));
+
+        let err =
+            launch_detached(&test_tool(), &mock, &test_config(), "s1", "testtool").unwrap_err();
+        assert!(err.to_string().contains("tmux is not installed"), "{err}");
+        assert_eq!(mock.invocations().len(), 1);
+    }
+
+    #[test]
+    fn launch_detached_fails_when_already_running() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        mock.add_response(MockResponse::Fail("duplicate session: relocal-s1".into()));
+
+        assert!(launch_detached(&test_tool(), &mock, &test_config(), "s1", "testtool").is_err());
+    }
+
     #[test]
     fn check_tool_installed_uses_claude_check() {
-        let claude = ToolConfig {
-            display_name: "Claude Code",
-            check_installed: ssh::check_claude_installed,
-            start_session: ssh::start_claude_session,
-        };
         let mock = MockRunner::new();
         mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
 
-        check_tool_installed(&claude, &mock, &test_config()).unwrap();
+        check_tool_installed(&crate::commands::claude::TOOL, &mock, &test_config()).unwrap();
 
         use crate::test_support::Invocation;
         match &mock.invocations()[0] {
@@ -174,17 +281,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn claude_tool_uses_configured_binary() {
+        let config =
+            Config::parse("remote = \"user@host\"\nclaude_bin = \"~/.local/bin/claude\"").unwrap();
+        let tool = &crate::commands::claude::TOOL;
+        assert_eq!(
+            (tool.check_installed)(&config),
+            "command -v ~/.local/bin/claude"
+        );
+        assert_eq!(
+            (tool.start_session)(&config, "s1", &[]),
+            "cd ~/relocal/s1 && ~/.local/bin/claude --dangerously-skip-permissions"
+        );
+    }
+
     #[test]
     fn check_tool_installed_uses_codex_check() {
-        let codex = ToolConfig {
-            display_name: "Codex",
-            check_installed: ssh::check_codex_installed,
-            start_session: ssh::start_codex_session,
-        };
         let mock = MockRunner::new();
         mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
 
-        check_tool_installed(&codex, &mock, &test_config()).unwrap();
+        check_tool_installed(&crate::commands::codex::TOOL, &mock, &test_config()).unwrap();
 
         use crate::test_support::Invocation;
         match &mock.invocations()[0] {
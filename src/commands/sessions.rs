@@ -0,0 +1,129 @@
+//! `relocal sessions` — lists and prunes the local session registry.
+//!
+//! Unlike `relocal list`, which asks the remote, this only reads
+//! `~/.relocal/sessions.toml` (see [`crate::registry`]).
+
+use std::path::Path;
+
+use tracing::info;
+
+use crate::error::{Error, Result};
+use crate::registry::{self, Registry, RegistryEntry};
+
+/// Prints every registered session, most recently used first.
+pub fn list(home: &Path) -> Result<()> {
+    let registry = Registry::load(&registry::registry_path(home))?;
+    if registry.sessions.is_empty() {
+        println!("No sessions recorded on this machine.");
+        return Ok(());
+    }
+    let now = registry::now_secs();
+    for entry in registry.by_last_used() {
+        println!("{}", format_entry(entry, now));
+    }
+    Ok(())
+}
+
+/// Removes `name` from the registry, then prunes stale entries (see
+/// [`registry::prune_stale`]). Does not touch the remote; use
+/// `relocal destroy` for that.
+pub fn forget(home: &Path, name: &str) -> Result<()> {
+    if registry::forget_session(home, name)? == 0 {
+        return Err(Error::CommandFailed {
+            command: "sessions forget".to_string(),
+            message: format!("no session '{name}' in the local registry"),
+        });
+    }
+    info!("Forgot session '{name}'.");
+    for entry in registry::prune_stale(home, registry::now_secs())? {
+        info!("Pruned stale session '{}' ({}).", entry.name, entry.remote);
+    }
+    Ok(())
+}
+
+/// Formats one registry line: `<name>  <remote>  <repo>  <age>`.
+fn format_entry(entry: &RegistryEntry, now: u64) -> String {
+    format!(
+        "{}  {}  {}  {}",
+        entry.name,
+        entry.remote,
+        entry.repo_root.display(),
+        format_age(now.saturating_sub(entry.last_used))
+    )
+}
+
+/// Coarse human-readable age, e.g. `5m ago`.
+fn format_age(secs: u64) -> String {
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[test]
+    fn format_entry_fields() {
+        let entry = RegistryEntry {
+            name: "s1".into(),
+            remote: "user@host".into(),
+            repo_root: PathBuf::from("/work/api"),
+            last_used: 1_000,
+        };
+        assert_eq!(
+            format_entry(&entry, 1_000 + 7_200),
+            "s1  user@host  /work/api  2h ago"
+        );
+    }
+
+    #[test]
+    fn format_age_units() {
+        assert_eq!(format_age(5), "just now");
+        assert_eq!(format_age(120), "2m ago");
+        assert_eq!(format_age(3 * 86_400), "3d ago");
+    }
+
+    #[test]
+    fn forget_unknown_session_errors() {
+        let home = TempDir::new().unwrap();
+        let err = forget(home.path(), "nope").unwrap_err();
+        assert!(err.to_string().contains("nope"), "{err}");
+    }
+
+    #[test]
+    fn forget_also_prunes_stale_sessions() {
+        let home = TempDir::new().unwrap();
+        let repo = TempDir::new().unwrap();
+        registry::record_session(home.path(), "s1", "u@h", repo.path()).unwrap();
+        registry::record_session(home.path(), "s2", "u@h", repo.path()).unwrap();
+        registry::record_session(home.path(), "gone", "u@h", Path::new("/nonexistent/repo"))
+            .unwrap();
+
+        forget(home.path(), "s1").unwrap();
+
+        let names: Vec<String> = Registry::load(&registry::registry_path(home.path()))
+            .unwrap()
+            .sessions
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        assert_eq!(names, vec!["s2"]);
+    }
+
+    #[test]
+    fn forget_known_session() {
+        let home = TempDir::new().unwrap();
+        registry::record_session(home.path(), "s1", "u@h", Path::new("/r")).unwrap();
+        forget(home.path(), "s1").unwrap();
+        assert!(Registry::load(&registry::registry_path(home.path()))
+            .unwrap()
+            .sessions
+            .is_empty());
+    }
+}
@@ -1,54 +1,116 @@
 //! `relocal status [session-name]` — shows information about a session.
 //!
 //! Checks the remote for: working directory existence and tool installation.
-//! All checks are done via SSH through the [`CommandRunner`] trait.
+//! All checks run in a single SSH round trip ([`ssh::status_probe`]) through
+//! the [`CommandRunner`] trait. The
+//! results are gathered into a [`SessionStatus`] and printed on stdout,
+//! either as human-readable lines (default) or as JSON (`--json`).
 
-use tracing::info;
+use serde::Serialize;
 
 use crate::config::Config;
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::runner::CommandRunner;
 use crate::ssh;
 
-/// Prints session status.
-pub fn run(runner: &dyn CommandRunner, config: &Config, session_name: &str) -> Result<()> {
-    info!("Session:    {session_name}");
-    info!("Remote:     {}", config.remote);
-    info!("Remote dir: {}", ssh::remote_work_dir(session_name));
+/// Everything `relocal status` reports. Field names are the stable `--json`
+/// schema; add fields rather than renaming them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SessionStatus {
+    pub session: String,
+    pub remote: String,
+    pub remote_dir: String,
+    pub directory_exists: bool,
+    pub claude_installed: bool,
+    pub codex_installed: bool,
+}
 
-    let dir_exists = ssh::run_status_check(
-        runner,
+/// Runs the remote probes for a session.
+pub fn collect(
+    runner: &dyn CommandRunner,
+    config: &Config,
+    session_name: &str,
+) -> Result<SessionStatus> {
+    let output = runner.run_ssh(
         &config.remote,
-        &ssh::check_work_dir_exists(session_name),
+        &ssh::status_probe(session_name, &config.claude_bin),
     )?;
-    info!(
-        "Directory:  {}",
-        if dir_exists { "exists" } else { "not found" }
-    );
+    let probe_error = |message: String| Error::Remote {
+        remote: config.remote.clone(),
+        message,
+    };
+    if !output.status.success() {
+        return Err(probe_error(format!(
+            "status probe failed: {}",
+            output.stderr.trim()
+        )));
+    }
+    let check = |name: &str| {
+        probe_result(&output.stdout, name).ok_or_else(|| {
+            probe_error(format!(
+                "status probe returned unexpected output: {:?}",
+                output.stdout.trim()
+            ))
+        })
+    };
+
+    Ok(SessionStatus {
+        session: session_name.to_string(),
+        remote: config.remote.clone(),
+        remote_dir: ssh::remote_work_dir(session_name),
+        directory_exists: check("dir")?,
+        claude_installed: check("claude")?,
+        codex_installed: check("codex")?,
+    })
+}
 
-    let claude_installed =
-        ssh::run_status_check(runner, &config.remote, &ssh::check_claude_installed())?;
-    info!(
-        "Claude:     {}",
-        if claude_installed {
-            "installed"
-        } else {
-            "not installed"
-        }
-    );
+/// Reads `name=<0|1>` from [`ssh::status_probe`] output.
+fn probe_result(output: &str, name: &str) -> Option<bool> {
+    let value = output
+        .split_whitespace()
+        .find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))?;
+    match value {
+        "1" => Some(true),
+        "0" => Some(false),
+        _ => None,
+    }
+}
 
-    let codex_installed =
-        ssh::run_status_check(runner, &config.remote, &ssh::check_codex_installed())?;
-    info!(
-        "Codex:      {}",
-        if codex_installed {
-            "installed"
+/// Prints session status, as JSON on stdout when `json` is set.
+pub fn run(
+    runner: &dyn CommandRunner,
+    config: &Config,
+    session_name: &str,
+    json: bool,
+) -> Result<()> {
+    let status = collect(runner, config, session_name)?;
+    if json {
+        println!("{}", to_json(&status));
+    } else {
+        print_human(&status);
+    }
+    Ok(())
+}
+
+fn to_json(status: &SessionStatus) -> String {
+    serde_json::to_string_pretty(status).expect("SessionStatus serialization cannot fail")
+}
+
+fn print_human(status: &SessionStatus) {
+    let installed = |yes: bool| if yes { "installed" } else { "not installed" };
+    println!("Session:    {}", status.session);
+    println!("Remote:     {}", status.remote);
+    println!("Remote dir: {}", status.remote_dir);
+    println!(
+        "Directory:  {}",
+        if status.directory_exists {
+            "exists"
         } else {
-            "not installed"
+            "not found"
         }
     );
-
-    Ok(())
+    println!("Claude:     {}", installed(status.claude_installed));
+    println!("Codex:      {}", installed(status.codex_installed));
 }
 
 #[cfg(test)]
@@ -61,64 +123,121 @@ mod tests {
     }
 
     #[test]
-    fn checks_all_conditions() {
+    fn checks_all_conditions_in_one_round_trip() {
         let mock = MockRunner::new();
-        mock.add_response(MockResponse::Ok(ssh::STATUS_CHECK_TRUE.into())); // dir
-        mock.add_response(MockResponse::Ok(ssh::STATUS_CHECK_TRUE.into())); // claude
-        mock.add_response(MockResponse::Ok(ssh::STATUS_CHECK_TRUE.into())); // codex
+        mock.add_response(MockResponse::Ok("dir=1 claude=1 codex=1 ".into()));
 
-        run(&mock, &test_config(), "my-session").unwrap();
+        run(&mock, &test_config(), "my-session", false).unwrap();
 
         let inv = mock.invocations();
-        assert_eq!(inv.len(), 3);
+        assert_eq!(
+            inv,
+            vec![Invocation::Ssh {
+                remote: "user@host".into(),
+                command: ssh::status_probe("my-session", "claude"),
+            }]
+        );
+    }
 
-        for i in &inv {
-            match i {
-                Invocation::Ssh { remote, .. } => assert_eq!(remote, "user@host"),
-                _ => panic!("expected Ssh"),
-            }
-        }
+    #[test]
+    fn reports_when_nothing_exists() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok("dir=0 claude=0 codex=0 ".into()));
 
-        match &inv[0] {
-            Invocation::Ssh { command, .. } => {
-                assert!(command.contains("test -d"));
-                assert!(command.contains("my-session"));
-            }
-            _ => panic!("expected Ssh"),
-        }
+        run(&mock, &test_config(), "s1", false).unwrap();
+    }
 
-        match &inv[1] {
-            Invocation::Ssh { command, .. } => {
-                assert!(command.contains("command -v claude"));
+    #[test]
+    fn collect_builds_status_struct() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok("dir=1 claude=0 codex=1 ".into()));
+
+        let status = collect(&mock, &test_config(), "s1").unwrap();
+        assert_eq!(
+            status,
+            SessionStatus {
+                session: "s1".to_string(),
+                remote: "user@host".to_string(),
+                remote_dir: "~/relocal/s1".to_string(),
+                directory_exists: true,
+                claude_installed: false,
+                codex_installed: true,
             }
-            _ => panic!("expected Ssh"),
-        }
+        );
+    }
 
-        match &inv[2] {
-            Invocation::Ssh { command, .. } => {
-                assert!(command.contains("command -v codex"));
-            }
-            _ => panic!("expected Ssh"),
+    #[test]
+    fn probe_uses_configured_claude_bin() {
+        let config =
+            Config::parse("remote = \"user@host\"\nclaude_bin = \"~/bin/claude\"").unwrap();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok("dir=1 claude=1 codex=1".into()));
+
+        collect(&mock, &config, "s1").unwrap();
+
+        match &mock.invocations()[0] {
+            Invocation::Ssh { command, .. } => assert!(command.contains("command -v ~/bin/claude")),
+            other => panic!("expected Ssh, got {other:?}"),
         }
     }
 
     #[test]
-    fn reports_when_everything_exists() {
+    fn probe_result_parses_pairs() {
+        let output = "dir=1 claude=0 codex=1 ";
+        assert_eq!(probe_result(output, "dir"), Some(true));
+        assert_eq!(probe_result(output, "claude"), Some(false));
+        assert_eq!(probe_result("dir=1", "codex"), None);
+        assert_eq!(probe_result("dir=yes", "dir"), None);
+    }
+
+    #[test]
+    fn json_has_stable_field_names() {
+        let status = SessionStatus {
+            session: "s1".to_string(),
+            remote: "user@host".to_string(),
+            remote_dir: "~/relocal/s1".to_string(),
+            directory_exists: false,
+            claude_installed: true,
+            codex_installed: false,
+        };
+        let value: serde_json::Value = serde_json::from_str(&to_json(&status)).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "session": "s1",
+                "remote": "user@host",
+                "remote_dir": "~/relocal/s1",
+                "directory_exists": false,
+                "claude_installed": true,
+                "codex_installed": false,
+            })
+        );
+    }
+
+    #[test]
+    fn json_mode_runs_same_probe() {
         let mock = MockRunner::new();
-        mock.add_response(MockResponse::Ok(ssh::STATUS_CHECK_TRUE.into()));
-        mock.add_response(MockResponse::Ok(ssh::STATUS_CHECK_TRUE.into()));
-        mock.add_response(MockResponse::Ok(ssh::STATUS_CHECK_TRUE.into()));
+        mock.add_response(MockResponse::Ok("dir=1 claude=1 codex=1 ".into()));
 
-        run(&mock, &test_config(), "s1").unwrap();
+        run(&mock, &test_config(), "s1", true).unwrap();
+        assert_eq!(mock.invocations().len(), 1);
     }
 
     #[test]
-    fn reports_when_nothing_exists() {
+    fn probe_failure_returns_error() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Fail("ssh: connect timeout".into()));
+
+        let err = run(&mock, &test_config(), "s1", true).unwrap_err();
+        assert!(err.to_string().contains("connect timeout"), "{err}");
+    }
+
+    #[test]
+    fn garbled_probe_output_returns_error() {
         let mock = MockRunner::new();
-        mock.add_response(MockResponse::Ok(ssh::STATUS_CHECK_FALSE.into()));
-        mock.add_response(MockResponse::Ok(ssh::STATUS_CHECK_FALSE.into()));
-        mock.add_response(MockResponse::Ok(ssh::STATUS_CHECK_FALSE.into()));
+        mock.add_response(MockResponse::Ok("Welcome to host!".into()));
 
-        run(&mock, &test_config(), "s1").unwrap();
+        let err = collect(&mock, &test_config(), "s1").unwrap_err();
+        assert!(err.to_string().contains("unexpected output"), "{err}");
     }
 }
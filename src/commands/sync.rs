@@ -1,39 +1,300 @@
-//! `relocal sync push` / `relocal sync pull` — manual sync commands.
+//! `relocal sync push` / `pull` / `copy` — manual sync commands.
 //!
-//! Push runs rsync (local → remote). Pull runs rsync (remote → local).
+//! Push runs rsync (local → remote). Pull runs rsync (remote → local). Copy
+//! runs rsync on the remote between two session directories.
+//! `relocal sync` without a subcommand reconciles both sides, syncing each
+//! path from the side that changed it (see [`crate::reconcile`]).
+//! `relocal sync --bidirectional-safe` pushes then pulls with `--update` and
+//! without `--delete`.
+//! With `--delete-dry-run`, either direction runs rsync in dry-run mode and
+//! only reports the deletions it would perform; `--dry-run` reports every
+//! itemized change instead. With `--checksum-verify`,
+//! push additionally compares checksum manifests of both sides afterwards.
+//! With `--check-case`, push first warns about local paths that differ only
+//! in case. With `--stats-json`, either direction prints its transfer stats
+//! as JSON on stdout.
 
 use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use tracing::info;
+use serde::Serialize;
+use tracing::{debug, info, warn};
 
+use crate::case_check::{case_collisions, list_files_command, parse_file_list};
+use crate::checksum::{diff_manifests, manifest_command, parse_manifest, quote_local_dir};
 use crate::config::Config;
-use crate::error::Result;
-use crate::rsync::{build_rsync_args, Direction};
-use crate::runner::CommandRunner;
+use crate::error::{Error, Result};
+use crate::reconcile::{
+    changed_locally, parse_baseline, parse_differences, plan, Difference, Side,
+};
+use crate::rsync::{
+    build_rsync_args, parse_change_summary, parse_deletions, parse_rsync_stats, skips_delete,
+    strip_stats, Direction, FilterSet, RsyncParams, RsyncStats, SyncOptions,
+};
+use crate::runner::{CommandOutput, CommandRunner};
+use crate::session;
 use crate::ssh;
 
+/// Running totals of the syncs performed during a session.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncStats {
+    pub pushes: u64,
+    pub pulls: u64,
+    /// Failed syncs in either direction (also counted in `pushes`/`pulls`).
+    pub errors: u64,
+    /// When the last successful sync finished.
+    pub last_sync: Option<SystemTime>,
+}
+
+impl SyncStats {
+    /// Records one sync attempt in `direction`.
+    pub fn record(&mut self, direction: Direction, result: &Result<()>) {
+        match direction {
+            Direction::Push => self.pushes += 1,
+            Direction::Pull => self.pulls += 1,
+        }
+        match result {
+            Ok(()) => self.last_sync = Some(SystemTime::now()),
+            Err(_) => self.errors += 1,
+        }
+    }
+
+    /// One-line summary, e.g. `1 push(es), 40 pull(s), 2 failed`.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} push(es), {} pull(s), {} failed",
+            self.pushes, self.pulls, self.errors
+        )
+    }
+}
+
+/// Prints the sync's include/exclude rules with their sources, in the order
+/// rsync applies them (see [`FilterSet`]).
+pub fn show_filters(config: &Config, repo_root: &Path, options: &SyncOptions) {
+    for line in FilterSet::new(config, repo_root, options).describe() {
+        println!("{line}");
+    }
+}
+
+/// Runs rsync, streaming its output in verbose mode so `--progress` shows up
+/// while the transfer runs rather than after it.
+fn run_rsync(
+    runner: &dyn CommandRunner,
+    params: &RsyncParams,
+    options: &SyncOptions,
+) -> Result<CommandOutput> {
+    if options.verbose {
+        runner.run_rsync_streaming(params)
+    } else {
+        runner.run_rsync(params)
+    }
+}
+
 /// Pushes local files to the remote.
 pub fn sync_push(
     runner: &dyn CommandRunner,
     config: &Config,
     session_name: &str,
     repo_root: &Path,
-    verbose: bool,
+    options: &SyncOptions,
 ) -> Result<()> {
+    check_filter_files(config, repo_root, options)?;
+    if options.check_case {
+        warn_case_collisions(runner, config, repo_root, options)?;
+    }
+    if skips_delete(config, Direction::Push, options) && !options.update {
+        warn!("Pushing without --delete: files deleted locally stay on the remote.");
+    }
     info!("Pushing to remote...");
-    let params = build_rsync_args(config, Direction::Push, session_name, repo_root, verbose);
-    let rsync_result = runner.run_rsync(&params)?;
-    if !rsync_result.status.success() {
-        return Err(crate::error::Error::CommandFailed {
-            command: "rsync".to_string(),
-            message: rsync_result.stderr,
-        });
+    let params = build_rsync_args(config, Direction::Push, session_name, repo_root, options);
+    let started = Instant::now();
+    let rsync_result = run_rsync(runner, &params, options)?;
+    let duration = started.elapsed();
+    check_rsync_status(config, &rsync_result)?;
+
+    if options.dry_run {
+        report_changes(&rsync_result.stdout);
+        return Ok(());
+    }
+    if options.delete_dry_run {
+        report_deletions(&rsync_result.stdout, "remote");
+        return Ok(());
+    }
+
+    if options.checksum_verify && options.compare_dest.is_some() {
+        // Files identical to the reference tree are never created remotely,
+        // so every one of them would be reported missing.
+        warn!("Skipping --checksum-verify: --compare-dest leaves files out of the session.");
+    } else if options.checksum_verify {
+        verify_checksums(runner, config, session_name, repo_root, options)?;
+    }
+    record_state(config, session_name, repo_root, options);
+
+    let stats = parse_rsync_stats(&rsync_result.stdout);
+    info!("Push complete. {}.", stats.summary());
+    if options.stats_json {
+        println!("{}", stats_json(&stats, duration));
+    }
+    Ok(())
+}
+
+/// Machine-readable transfer stats printed by `--stats-json`.
+#[derive(Debug, Serialize)]
+struct StatsJson {
+    files_transferred: u64,
+    bytes_sent: u64,
+    bytes_received: u64,
+    speedup: f64,
+    /// Wall-clock time of the rsync run.
+    duration_ms: u64,
+}
+
+fn stats_json(stats: &RsyncStats, duration: Duration) -> String {
+    let json = StatsJson {
+        files_transferred: stats.files_transferred,
+        bytes_sent: stats.bytes_sent,
+        bytes_received: stats.bytes_received,
+        speedup: stats.speedup,
+        duration_ms: duration.as_millis() as u64,
+    };
+    serde_json::to_string(&json).expect("StatsJson serialization cannot fail")
+}
+
+/// rsync exit code for "some files vanished before they could be transferred".
+const RSYNC_VANISHED: i32 = 24;
+
+/// rsync exit code for "partial transfer due to error".
+const RSYNC_PARTIAL: i32 = 23;
+
+/// Turns a failed rsync run into an error. Exit code 24 (files deleted while
+/// rsync was scanning, common while an agent is editing) is only logged when
+/// `ignore_vanished` is set; everything else was transferred. Exit code 23
+/// (some files failed, e.g. unreadable) is logged with rsync's errors when
+/// `ignore_partial` is set.
+fn check_rsync_status(config: &Config, output: &CommandOutput) -> Result<()> {
+    if output.status.success() {
+        return Ok(());
+    }
+    if config.ignore_vanished && output.status.code() == Some(RSYNC_VANISHED) {
+        info!("Some files vanished during transfer; the next sync will pick up the change.");
+        return Ok(());
+    }
+    if config.ignore_partial && output.status.code() == Some(RSYNC_PARTIAL) {
+        warn!(
+            "Some files could not be transferred; continuing as ignore_partial is set: {}",
+            output.stderr.trim()
+        );
+        return Ok(());
+    }
+    Err(Error::CommandFailed {
+        command: "rsync".to_string(),
+        message: output.stderr.clone(),
+    })
+}
+
+/// Fails if the configured `exclude_from` file or a `--include-from` /
+/// `--exclude-from` file is missing. rsync would otherwise abort
+/// mid-invocation with a less helpful message.
+fn check_filter_files(config: &Config, repo_root: &Path, options: &SyncOptions) -> Result<()> {
+    if let Some(full) = config.exclude_from_path(repo_root) {
+        if !full.is_file() {
+            return Err(Error::PathNotFound {
+                flag: "exclude_from",
+                path: full,
+            });
+        }
+    }
+    for (flag, path) in [
+        ("--include-from", &options.include_from),
+        ("--exclude-from", &options.exclude_from),
+    ] {
+        if let Some(path) = path.as_ref().filter(|p| !p.is_file()) {
+            return Err(Error::PathNotFound {
+                flag,
+                path: path.clone(),
+            });
+        }
     }
+    Ok(())
+}
 
-    info!("Push complete.");
+/// Warns about local paths that differ only in case; they are still pushed.
+fn warn_case_collisions(
+    runner: &dyn CommandRunner,
+    config: &Config,
+    repo_root: &Path,
+    options: &SyncOptions,
+) -> Result<()> {
+    let output = runner
+        .run_local(
+            "bash",
+            &["-c", &list_files_command(&quote_local_dir(repo_root))],
+        )?
+        .check("local file list")?;
+    let filter = FilterSet::new(config, repo_root, options).path_filter();
+    let collisions = case_collisions(&parse_file_list(&output.stdout, &filter));
+    if collisions.is_empty() {
+        return Ok(());
+    }
+    warn!(
+        "{} path(s) differ only in case and will clash on a case-insensitive filesystem:",
+        collisions.len()
+    );
+    for group in &collisions {
+        warn!("  {}", group.join(", "));
+    }
     Ok(())
 }
 
+/// Compares `sha256sum` manifests of the local tree and the remote session
+/// directory, warning about every differing path.
+fn verify_checksums(
+    runner: &dyn CommandRunner,
+    config: &Config,
+    session_name: &str,
+    repo_root: &Path,
+    options: &SyncOptions,
+) -> Result<()> {
+    info!("Verifying checksums...");
+    let local_cmd = manifest_command(&quote_local_dir(repo_root));
+    let local_output = runner
+        .run_local("bash", &["-c", &local_cmd])?
+        .check("local checksum manifest")?;
+    let remote_output = runner
+        .run_ssh(
+            &config.remote,
+            &manifest_command(&ssh::remote_work_dir(session_name)),
+        )?
+        .check("remote checksum manifest")?;
+
+    let filter = FilterSet::new(config, repo_root, options).path_filter();
+    let mut diff = diff_manifests(
+        &parse_manifest(&local_output.stdout, &filter),
+        &parse_manifest(&remote_output.stdout, &filter),
+    );
+    if skips_delete(config, Direction::Push, options) {
+        // The push was told to leave remote-only files alone.
+        diff.extra_remote.clear();
+    }
+    if diff.is_empty() {
+        info!("Checksums match.");
+        return Ok(());
+    }
+    for path in &diff.mismatched {
+        warn!("  content differs: {path}");
+    }
+    for path in &diff.missing_remote {
+        warn!("  missing on remote: {path}");
+    }
+    for path in &diff.extra_remote {
+        warn!("  only on remote: {path}");
+    }
+    Err(Error::ChecksumMismatch {
+        session: session_name.to_string(),
+        count: diff.len(),
+    })
+}
+
 /// Pulls remote files to local.
 ///
 /// Before running rsync, verifies the remote session directory is a valid
@@ -44,35 +305,314 @@ pub fn sync_pull(
     config: &Config,
     session_name: &str,
     repo_root: &Path,
-    verbose: bool,
+    options: &SyncOptions,
 ) -> Result<()> {
+    check_filter_files(config, repo_root, options)?;
+
     // Safety gate: verify remote is a healthy git repo before pulling
-    info!("Verifying remote git repository...");
-    let fsck_result = runner.run_ssh(&config.remote, &ssh::git_fsck(session_name))?;
-    if !fsck_result.status.success() {
-        return Err(crate::error::Error::RemoteGitFsckFailed {
-            session: session_name.to_string(),
-            stderr: fsck_result.stderr,
-        });
+    if config.pull_fsck {
+        info!("Verifying remote git repository...");
+        let fsck_result = runner.run_ssh(&config.remote, &ssh::git_fsck(session_name))?;
+        if !fsck_result.status.success() {
+            return Err(Error::RemoteGitFsckFailed {
+                session: session_name.to_string(),
+                stderr: fsck_result.stderr,
+            });
+        }
+    } else {
+        debug!("Skipping remote git fsck (pull_fsck = false).");
+    }
+
+    if records_state(options) {
+        warn_local_changes(config, session_name, repo_root, options);
     }
 
     info!("Pulling from remote...");
-    let params = build_rsync_args(config, Direction::Pull, session_name, repo_root, verbose);
+    let params = build_rsync_args(config, Direction::Pull, session_name, repo_root, options);
+    let started = Instant::now();
+    let rsync_result = run_rsync(runner, &params, options)?;
+    let duration = started.elapsed();
+    check_rsync_status(config, &rsync_result)?;
+
+    if options.dry_run {
+        report_changes(&rsync_result.stdout);
+        return Ok(());
+    }
+    if options.delete_dry_run {
+        report_deletions(&rsync_result.stdout, "local");
+        return Ok(());
+    }
+
+    record_state(config, session_name, repo_root, options);
+
+    let stats = parse_rsync_stats(&rsync_result.stdout);
+    info!("Pull complete. {}.", stats.summary());
+    if options.itemize {
+        info!(
+            "Local changes: {}.",
+            parse_change_summary(&rsync_result.stdout).summary()
+        );
+    }
+    if options.stats_json {
+        println!("{}", stats_json(&stats, duration));
+    }
+    Ok(())
+}
+
+/// Two-way sync that never clobbers newer files: a push then a pull, both
+/// with rsync `--update` and without `--delete`.
+///
+/// This is not a merge. The newer mtime wins per file, a file edited on both
+/// sides keeps only the newer version, and deletions do not propagate.
+pub fn sync_bidirectional_safe(
+    runner: &dyn CommandRunner,
+    config: &Config,
+    session_name: &str,
+    repo_root: &Path,
+    options: &SyncOptions,
+) -> Result<()> {
+    warn!("Bidirectional-safe sync: newer files win by mtime and nothing is deleted. This is not a merge.");
+    let options = SyncOptions {
+        update: true,
+        ..options.clone()
+    };
+    sync_push(runner, config, session_name, repo_root, &options)?;
+    sync_pull(runner, config, session_name, repo_root, &options)
+}
+
+/// Reconciling two-way sync: syncs each differing path from the side that
+/// changed it since the previous reconciling sync, and leaves paths changed
+/// on both sides alone unless `prefer` picks a side (see [`crate::reconcile`]).
+///
+/// The differences come from a dry-run pull. The applied pull still runs the
+/// `git fsck` gate, since a remote that lost its files would otherwise read
+/// as a remote-side deletion of everything. Conflicts fail the sync with
+/// [`Error::SyncConflict`] after everything else has been synced.
+pub fn sync_reconcile(
+    runner: &dyn CommandRunner,
+    config: &Config,
+    session_name: &str,
+    repo_root: &Path,
+    options: &SyncOptions,
+    prefer: Option<Side>,
+) -> Result<()> {
+    check_filter_files(config, repo_root, options)?;
+    if !ssh::run_status_check(
+        runner,
+        &config.remote,
+        &ssh::check_work_dir_exists(session_name),
+    )? {
+        return Err(Error::Remote {
+            remote: config.remote.clone(),
+            message: format!("session '{session_name}' not found. No working directory exists."),
+        });
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let begin = runner
+        .run_ssh(&config.remote, &ssh::begin_reconcile(session_name, now))?
+        .check("sync baseline")?;
+    let baseline = parse_baseline(&begin.stdout);
+
+    info!("Comparing local and remote...");
+    let compare = SyncOptions {
+        dry_run: true,
+        ..options.clone()
+    };
+    let params = build_rsync_args(config, Direction::Pull, session_name, repo_root, &compare);
     let rsync_result = runner.run_rsync(&params)?;
-    if !rsync_result.status.success() {
-        return Err(crate::error::Error::CommandFailed {
-            command: "rsync".to_string(),
-            message: rsync_result.stderr,
+    check_rsync_status(config, &rsync_result)?;
+    let differences = parse_differences(&rsync_result.stdout);
+    if baseline.is_none() && !differences.is_empty() && prefer.is_none() {
+        warn!("No previous `relocal sync` of this session: every difference is a conflict.");
+    }
+    let plan = plan(
+        &differences,
+        baseline.as_ref(),
+        |path| {
+            baseline
+                .as_ref()
+                .is_some_and(|b| changed_locally(repo_root, path, b.local_secs))
+        },
+        prefer,
+    );
+    debug!(
+        "{} to push, {} to pull, {} conflicting",
+        plan.push.len(),
+        plan.pull.len(),
+        plan.conflicts.len()
+    );
+
+    // An empty path list would sync the whole tree.
+    if !plan.push.is_empty() {
+        let push = SyncOptions {
+            only_paths: plan.push,
+            ..options.clone()
+        };
+        sync_push(runner, config, session_name, repo_root, &push)?;
+    }
+    if !plan.pull.is_empty() {
+        let pull = SyncOptions {
+            only_paths: plan.pull,
+            ..options.clone()
+        };
+        sync_pull(runner, config, session_name, repo_root, &pull)?;
+    }
+    // Without a baseline, conflicts are paths nobody has decided on yet:
+    // recording one now would read them as deletions next time.
+    if baseline.is_some() || plan.conflicts.is_empty() {
+        let remote_only: Vec<String> = differences
+            .iter()
+            .filter(|d| {
+                matches!(d, Difference::RemoteOnly(_))
+                    && plan.conflicts.iter().any(|path| path == d.path())
+            })
+            .map(|d| d.path().to_string())
+            .collect();
+        runner
+            .run_ssh(
+                &config.remote,
+                &ssh::commit_reconcile(session_name, &remote_only),
+            )?
+            .check("sync baseline")?;
+    }
+
+    if plan.conflicts.is_empty() {
+        info!("Sync complete.");
+        return Ok(());
+    }
+    warn!("Conflicting paths, left untouched:");
+    for path in &plan.conflicts {
+        warn!("  {path}");
+    }
+    Err(Error::SyncConflict {
+        session: session_name.to_string(),
+        count: plan.conflicts.len(),
+    })
+}
+
+/// Mirrors session `from` into session `to` on the same remote.
+///
+/// Both working directories must exist, and `to` must not be locked by a
+/// running session (the copy uses `--delete`, and the session's next push
+/// would overwrite it anyway).
+pub fn sync_copy(runner: &dyn CommandRunner, config: &Config, from: &str, to: &str) -> Result<()> {
+    if from == to {
+        return Err(Error::CommandFailed {
+            command: "sync copy".to_string(),
+            message: "source and destination sessions are the same".to_string(),
+        });
+    }
+    for session in [from, to] {
+        if !ssh::run_status_check(runner, &config.remote, &ssh::check_work_dir_exists(session))? {
+            return Err(Error::Remote {
+                remote: config.remote.clone(),
+                message: format!("session '{session}' not found. No working directory exists."),
+            });
+        }
+    }
+    if ssh::run_status_check(runner, &config.remote, &ssh::check_lock_file_exists(to))? {
+        return Err(Error::SessionInUse {
+            session: to.to_string(),
         });
     }
 
-    info!("Pull complete.");
+    info!("Copying session '{from}' to '{to}' on {}...", config.remote);
+    runner
+        .run_ssh(&config.remote, &ssh::copy_work_dir(from, to))?
+        .check("remote rsync")?;
+    info!("Copy complete.");
     Ok(())
 }
 
+/// Whether a sync is one the user ran over the whole repo root, the only
+/// kind `.relocal/state.json` describes. Background pulls, partial
+/// (`only_paths`) syncs and pulls into a scratch `local_path` are not.
+fn records_state(options: &SyncOptions) -> bool {
+    !options.background && options.only_paths.is_empty() && options.local_path.is_none()
+}
+
+/// Records the synced tree in `.relocal/state.json` (see
+/// [`session::record_sync_state`]) if [`records_state`]. Best-effort: the
+/// sync itself succeeded.
+fn record_state(config: &Config, session_name: &str, repo_root: &Path, options: &SyncOptions) {
+    if !records_state(options) {
+        return;
+    }
+    let filter = FilterSet::new(config, repo_root, options).path_filter();
+    if let Err(e) = session::record_sync_state(repo_root, session_name, &filter) {
+        debug!("Not recording sync state: {e}");
+    }
+}
+
+/// Warns about local files changed since the last recorded sync, which a
+/// pull may overwrite or delete. Best-effort, like [`record_state`].
+fn warn_local_changes(
+    config: &Config,
+    session_name: &str,
+    repo_root: &Path,
+    options: &SyncOptions,
+) {
+    let filter = FilterSet::new(config, repo_root, options).path_filter();
+    let changed = match session::changed_since_sync(repo_root, session_name, &filter) {
+        Ok(Some(changed)) => changed,
+        Ok(None) => return,
+        Err(e) => {
+            debug!("Not checking for local changes: {e}");
+            return;
+        }
+    };
+    if changed.is_empty() {
+        return;
+    }
+    warn!(
+        "{} local file(s) changed since the last sync; the pull may overwrite them:",
+        changed.len()
+    );
+    for path in &changed {
+        warn!("  {path}");
+    }
+}
+
+/// Prints rsync's itemized dry-run output, without the `--stats` block.
+fn report_changes(rsync_stdout: &str) {
+    let lines: Vec<&str> = strip_stats(rsync_stdout)
+        .lines()
+        .filter(|l| !l.is_empty())
+        .collect();
+    if lines.is_empty() {
+        println!("Dry run: nothing would change.");
+        return;
+    }
+    println!("Dry run: {} change(s) would be made:", lines.len());
+    for line in lines {
+        println!("  {line}");
+    }
+}
+
+/// Prints the deletions found in dry-run rsync output. `side` names where the
+/// files would be removed ("remote" or "local").
+fn report_deletions(rsync_stdout: &str, side: &str) {
+    let deletions = parse_deletions(rsync_stdout);
+    if deletions.is_empty() {
+        println!("Dry run: no {side} files would be deleted.");
+        return;
+    }
+    println!(
+        "Dry run: {} {side} path(s) would be deleted:",
+        deletions.len()
+    );
+    for path in &deletions {
+        println!("  {path}");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ssh::{STATUS_CHECK_FALSE, STATUS_CHECK_TRUE};
     use crate::test_support::{Invocation, MockResponse, MockRunner};
     use std::path::PathBuf;
 
@@ -84,13 +624,73 @@ mod tests {
         PathBuf::from("/home/user/my-project")
     }
 
+    fn verbose() -> SyncOptions {
+        SyncOptions {
+            verbose: true,
+            ..Default::default()
+        }
+    }
+
+    fn dry_run() -> SyncOptions {
+        SyncOptions {
+            dry_run: true,
+            ..Default::default()
+        }
+    }
+
+    fn delete_dry_run() -> SyncOptions {
+        SyncOptions {
+            delete_dry_run: true,
+            ..Default::default()
+        }
+    }
+
+    fn checksum_verify() -> SyncOptions {
+        SyncOptions {
+            checksum_verify: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn stats_json_reports_parsed_transfer_stats() {
+        let output = "\
+Number of regular files transferred: 3
+Total transferred file size: 4,096 bytes
+Total bytes sent: 5,120
+Total bytes received: 64
+
+sent 5,120 bytes  received 64 bytes  10,368.00 bytes/sec
+total size is 40,960  speedup is 7.90
+";
+        let json = stats_json(&parse_rsync_stats(output), Duration::from_millis(1_500));
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "files_transferred": 3,
+                "bytes_sent": 5120,
+                "bytes_received": 64,
+                "speedup": 7.9,
+                "duration_ms": 1500,
+            })
+        );
+    }
+
     #[test]
     fn push_runs_rsync_with_push_direction() {
         let mock = MockRunner::new();
         // rsync
         mock.add_response(MockResponse::Ok(String::new()));
 
-        sync_push(&mock, &test_config(), "s1", &repo_root(), false).unwrap();
+        sync_push(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            &SyncOptions::default(),
+        )
+        .unwrap();
 
         let inv = mock.invocations();
         assert_eq!(inv.len(), 1);
@@ -116,7 +716,14 @@ mod tests {
         // rsync
         mock.add_response(MockResponse::Ok(String::new()));
 
-        sync_pull(&mock, &test_config(), "s1", &repo_root(), false).unwrap();
+        sync_pull(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            &SyncOptions::default(),
+        )
+        .unwrap();
 
         let inv = mock.invocations();
         assert_eq!(inv.len(), 2);
@@ -147,7 +754,13 @@ mod tests {
         // git fsck fails
         mock.add_response(MockResponse::Fail("fatal: not a git repository".into()));
 
-        let result = sync_pull(&mock, &test_config(), "s1", &repo_root(), false);
+        let result = sync_pull(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            &SyncOptions::default(),
+        );
         assert!(result.is_err());
         let err = result.unwrap_err().to_string();
         assert!(err.contains("git fsck"));
@@ -157,23 +770,80 @@ mod tests {
         assert_eq!(inv.len(), 1);
     }
 
+    #[test]
+    fn pull_skips_fsck_when_disabled() {
+        let config = Config::parse("remote = \"user@host\"\npull_fsck = false").unwrap();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // rsync
+
+        sync_pull(&mock, &config, "s1", &repo_root(), &SyncOptions::default()).unwrap();
+
+        let inv = mock.invocations();
+        assert_eq!(inv.len(), 1);
+        assert!(matches!(&inv[0], Invocation::Rsync { .. }));
+    }
+
+    #[test]
+    fn pull_fsck_failure_is_typed_with_stderr() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Fail("error: bad object HEAD".into()));
+
+        let err = sync_pull(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            &SyncOptions::default(),
+        )
+        .unwrap_err();
+
+        match err {
+            Error::RemoteGitFsckFailed { session, stderr } => {
+                assert_eq!(session, "s1");
+                assert_eq!(stderr, "error: bad object HEAD");
+            }
+            other => panic!("expected RemoteGitFsckFailed, got {other:?}"),
+        }
+    }
+
     #[test]
     fn push_verbose_passes_through() {
         let mock = MockRunner::new();
         // rsync
         mock.add_response(MockResponse::Ok(String::new()));
 
-        sync_push(&mock, &test_config(), "s1", &repo_root(), true).unwrap();
+        sync_push(&mock, &test_config(), "s1", &repo_root(), &verbose()).unwrap();
 
         let inv = mock.invocations();
         match &inv[0] {
-            Invocation::Rsync { args, .. } => {
+            Invocation::Rsync { args, streamed, .. } => {
                 assert!(args.contains(&"--progress".to_string()));
+                assert!(streamed);
             }
             _ => panic!("expected Rsync"),
         }
     }
 
+    #[test]
+    fn quiet_push_is_buffered() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new()));
+
+        sync_push(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            &SyncOptions::default(),
+        )
+        .unwrap();
+
+        match &mock.invocations()[0] {
+            Invocation::Rsync { streamed, .. } => assert!(!streamed),
+            other => panic!("expected Rsync, got {other:?}"),
+        }
+    }
+
     #[test]
     fn pull_verbose_passes_through() {
         let mock = MockRunner::new();
@@ -182,15 +852,1001 @@ mod tests {
         // rsync
         mock.add_response(MockResponse::Ok(String::new()));
 
-        sync_pull(&mock, &test_config(), "s1", &repo_root(), true).unwrap();
+        sync_pull(&mock, &test_config(), "s1", &repo_root(), &verbose()).unwrap();
 
         let inv = mock.invocations();
         // rsync is the second invocation (after fsck)
         match &inv[1] {
-            Invocation::Rsync { args, .. } => {
+            Invocation::Rsync { args, streamed, .. } => {
                 assert!(args.contains(&"--progress".to_string()));
+                assert!(streamed);
+            }
+            _ => panic!("expected Rsync"),
+        }
+    }
+
+    #[test]
+    fn push_delete_dry_run_passes_dry_run_flags() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok("*deleting   gone.txt\n".into()));
+
+        sync_push(&mock, &test_config(), "s1", &repo_root(), &delete_dry_run()).unwrap();
+
+        let inv = mock.invocations();
+        assert_eq!(inv.len(), 1);
+        match &inv[0] {
+            Invocation::Rsync { args, .. } => {
+                assert!(args.contains(&"--dry-run".to_string()));
+                assert!(args.contains(&"--itemize-changes".to_string()));
+            }
+            _ => panic!("expected Rsync"),
+        }
+    }
+
+    #[test]
+    fn pull_delete_dry_run_still_runs_fsck() {
+        let mock = MockRunner::new();
+        // git fsck
+        mock.add_response(MockResponse::Ok(String::new()));
+        // rsync dry run
+        mock.add_response(MockResponse::Ok(String::new()));
+
+        sync_pull(&mock, &test_config(), "s1", &repo_root(), &delete_dry_run()).unwrap();
+
+        let inv = mock.invocations();
+        assert_eq!(inv.len(), 2);
+        assert!(matches!(&inv[0], Invocation::Ssh { command, .. } if command.contains("git fsck")));
+        match &inv[1] {
+            Invocation::Rsync { args, .. } => assert!(args.contains(&"--dry-run".to_string())),
+            _ => panic!("expected Rsync"),
+        }
+    }
+
+    #[test]
+    fn delete_dry_run_rsync_failure_returns_error() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Fail("connection refused".into()));
+
+        let result = sync_push(&mock, &test_config(), "s1", &repo_root(), &delete_dry_run());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn push_checksum_verify_matching_manifests_succeeds() {
+        let mock = MockRunner::new();
+        // rsync
+        mock.add_response(MockResponse::Ok(String::new()));
+        // local manifest
+        mock.add_response(MockResponse::Ok("aaaa  src/main.rs\n".into()));
+        // remote manifest
+        mock.add_response(MockResponse::Ok("aaaa  src/main.rs\n".into()));
+
+        sync_push(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            &checksum_verify(),
+        )
+        .unwrap();
+
+        let inv = mock.invocations();
+        assert_eq!(inv.len(), 3);
+        match &inv[1] {
+            Invocation::Local { program, args } => {
+                assert_eq!(program, "bash");
+                assert!(args[1].contains("/home/user/my-project"));
+                assert!(args[1].contains("sha256sum"));
+            }
+            _ => panic!("expected Local, got {:?}", inv[1]),
+        }
+        match &inv[2] {
+            Invocation::Ssh { command, .. } => {
+                assert!(command.starts_with("cd ~/relocal/s1 && "));
+                assert!(command.contains("sha256sum"));
+            }
+            _ => panic!("expected Ssh, got {:?}", inv[2]),
+        }
+    }
+
+    #[test]
+    fn push_checksum_verify_skips_paths_excluded_by_filter_files() {
+        let repo = tempfile::tempdir().unwrap();
+        std::fs::write(repo.path().join(".relocalignore"), "secret.bin\n").unwrap();
+        let skip = repo.path().join("skip.txt");
+        std::fs::write(&skip, "# local only\nscratch/\n").unwrap();
+        let config =
+            Config::parse("remote = \"user@host\"\nexclude_from = \".relocalignore\"").unwrap();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new()));
+        mock.add_response(MockResponse::Ok(
+            "aaaa  src/main.rs\nbbbb  secret.bin\ncccc  scratch/notes.md\n".into(),
+        ));
+        mock.add_response(MockResponse::Ok("aaaa  src/main.rs\n".into()));
+
+        let options = SyncOptions {
+            checksum_verify: true,
+            exclude_from: Some(skip),
+            ..Default::default()
+        };
+        sync_push(&mock, &config, "s1", repo.path(), &options).unwrap();
+    }
+
+    #[test]
+    fn push_checksum_verify_ignores_remote_only_files_without_delete() {
+        let config = Config::parse("remote = \"user@host\"\npush_no_delete = true").unwrap();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new()));
+        mock.add_response(MockResponse::Ok("aaaa  a.txt\n".into()));
+        mock.add_response(MockResponse::Ok(
+            "aaaa  a.txt\nbbbb  remote-only.txt\n".into(),
+        ));
+
+        sync_push(&mock, &config, "s1", &repo_root(), &checksum_verify()).unwrap();
+    }
+
+    #[test]
+    fn push_checksum_verify_skipped_with_compare_dest() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new()));
+
+        let options = SyncOptions {
+            compare_dest: Some("/srv/ref".into()),
+            ..checksum_verify()
+        };
+        sync_push(&mock, &test_config(), "s1", &repo_root(), &options).unwrap();
+
+        assert_eq!(mock.invocations().len(), 1);
+    }
+
+    #[test]
+    fn push_checksum_verify_mismatch_returns_error() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new()));
+        mock.add_response(MockResponse::Ok("aaaa  a.txt\nbbbb  b.txt\n".into()));
+        mock.add_response(MockResponse::Ok("ffff  a.txt\n".into()));
+
+        let err = sync_push(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            &checksum_verify(),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("2 path(s) differ"), "got: {err}");
+    }
+
+    #[test]
+    fn push_checksum_verify_remote_manifest_failure_returns_error() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new()));
+        mock.add_response(MockResponse::Ok("aaaa  a.txt\n".into()));
+        mock.add_response(MockResponse::Fail("not a git repository".into()));
+
+        let err = sync_push(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            &checksum_verify(),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("remote checksum manifest"), "got: {err}");
+    }
+
+    #[test]
+    fn push_without_checksum_verify_skips_manifests() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new()));
+
+        sync_push(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            &SyncOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(mock.invocations().len(), 1);
+    }
+
+    #[test]
+    fn push_check_case_lists_local_files_before_rsync() {
+        let mock = MockRunner::new();
+        // local file list with a case-only collision (warned, not fatal)
+        mock.add_response(MockResponse::Ok(
+            "README.md\0readme.md\0src/lib.rs\0".into(),
+        ));
+        // rsync
+        mock.add_response(MockResponse::Ok(String::new()));
+
+        let options = SyncOptions {
+            check_case: true,
+            ..Default::default()
+        };
+        sync_push(&mock, &test_config(), "s1", &repo_root(), &options).unwrap();
+
+        let inv = mock.invocations();
+        assert_eq!(inv.len(), 2);
+        match &inv[0] {
+            Invocation::Local { program, args } => {
+                assert_eq!(program, "bash");
+                assert!(args[1].contains("git ls-files -z"), "{args:?}");
+            }
+            _ => panic!("expected Local"),
+        }
+        assert!(matches!(&inv[1], Invocation::Rsync { .. }));
+    }
+
+    #[test]
+    fn push_check_case_list_failure_returns_error() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Fail("not a git repository".into()));
+
+        let options = SyncOptions {
+            check_case: true,
+            ..Default::default()
+        };
+        let err = sync_push(&mock, &test_config(), "s1", &repo_root(), &options)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("local file list"), "got: {err}");
+        assert_eq!(mock.invocations().len(), 1);
+    }
+
+    #[test]
+    fn push_dry_run_passes_flags_and_skips_checksum_verify() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(">f+++++++++ new.txt\n".into()));
+
+        let options = SyncOptions {
+            dry_run: true,
+            checksum_verify: true,
+            ..Default::default()
+        };
+        sync_push(&mock, &test_config(), "s1", &repo_root(), &options).unwrap();
+
+        // Only rsync ran: no manifest commands against a remote we didn't touch.
+        let inv = mock.invocations();
+        assert_eq!(inv.len(), 1);
+        match &inv[0] {
+            Invocation::Rsync { args, .. } => {
+                assert!(args.contains(&"--dry-run".to_string()));
+                assert!(args.contains(&"--itemize-changes".to_string()));
             }
             _ => panic!("expected Rsync"),
         }
     }
+
+    #[test]
+    fn pull_dry_run_still_runs_fsck() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new()));
+        mock.add_response(MockResponse::Ok(String::new()));
+
+        sync_pull(&mock, &test_config(), "s1", &repo_root(), &dry_run()).unwrap();
+
+        let inv = mock.invocations();
+        assert_eq!(inv.len(), 2);
+        assert!(matches!(&inv[0], Invocation::Ssh { command, .. } if command.contains("git fsck")));
+    }
+
+    #[test]
+    fn dry_run_rsync_failure_returns_error() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Fail("connection refused".into()));
+
+        assert!(sync_push(&mock, &test_config(), "s1", &repo_root(), &dry_run()).is_err());
+    }
+
+    #[test]
+    fn stats_count_each_direction_and_failures() {
+        let mut stats = SyncStats::default();
+        stats.record(Direction::Push, &Ok(()));
+        assert!(stats.last_sync.is_some());
+        stats.record(Direction::Pull, &Ok(()));
+        let last = stats.last_sync;
+        stats.record(
+            Direction::Pull,
+            &Err(Error::CommandFailed {
+                command: "rsync".into(),
+                message: String::new(),
+            }),
+        );
+
+        assert_eq!((stats.pushes, stats.pulls, stats.errors), (1, 2, 1));
+        // A failure does not move the last successful sync time.
+        assert_eq!(stats.last_sync, last);
+        assert_eq!(stats.summary(), "1 push(es), 2 pull(s), 1 failed");
+    }
+
+    #[test]
+    fn pull_to_local_path_targets_that_directory() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // fsck
+        mock.add_response(MockResponse::Ok(String::new())); // rsync
+        let options = SyncOptions {
+            local_path: Some(PathBuf::from("/tmp/scratch")),
+            ..Default::default()
+        };
+
+        sync_pull(&mock, &test_config(), "s1", &repo_root(), &options).unwrap();
+
+        match &mock.invocations()[1] {
+            Invocation::Rsync {
+                args, local_path, ..
+            } => {
+                assert_eq!(local_path, &PathBuf::from("/tmp/scratch"));
+                assert_eq!(args.last().unwrap(), "/tmp/scratch/");
+            }
+            other => panic!("expected Rsync, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn itemized_pull_requests_itemize_changes() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // fsck
+        mock.add_response(MockResponse::Ok(">f.st...... src/main.rs\n".into())); // rsync
+        let options = SyncOptions {
+            itemize: true,
+            ..Default::default()
+        };
+
+        sync_pull(&mock, &test_config(), "s1", &repo_root(), &options).unwrap();
+
+        match &mock.invocations()[1] {
+            Invocation::Rsync { args, .. } => {
+                assert!(args.contains(&"--itemize-changes".to_string()));
+                assert!(!args.contains(&"--dry-run".to_string()));
+            }
+            other => panic!("expected Rsync, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn push_vanished_files_is_ok_by_default() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Exit(
+            24,
+            "file has vanished: \"a.tmp\"".into(),
+        ));
+
+        sync_push(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            &SyncOptions::default(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn pull_vanished_files_is_ok_by_default() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // fsck
+        mock.add_response(MockResponse::Exit(24, "file has vanished".into()));
+
+        sync_pull(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            &SyncOptions::default(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn push_vanished_files_fails_when_not_ignored() {
+        let config = Config::parse("remote = \"user@host\"\nignore_vanished = false").unwrap();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Exit(
+            24,
+            "file has vanished: \"a.tmp\"".into(),
+        ));
+
+        let err = sync_push(&mock, &config, "s1", &repo_root(), &SyncOptions::default())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("vanished"));
+    }
+
+    #[test]
+    fn ignore_vanished_does_not_mask_other_rsync_errors() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Exit(
+            23,
+            "some files could not be transferred".into(),
+        ));
+
+        assert!(sync_push(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            &SyncOptions::default()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn partial_transfer_is_ok_when_ignored() {
+        let config = Config::parse("remote = \"user@host\"\nignore_partial = true").unwrap();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Exit(23, "open \"secret\" failed".into()));
+        mock.add_response(MockResponse::Ok(String::new())); // fsck
+        mock.add_response(MockResponse::Exit(23, "open \"secret\" failed".into()));
+
+        sync_push(&mock, &config, "s1", &repo_root(), &SyncOptions::default()).unwrap();
+        sync_pull(&mock, &config, "s1", &repo_root(), &SyncOptions::default()).unwrap();
+    }
+
+    #[test]
+    fn ignore_partial_does_not_mask_hard_failures() {
+        let config = Config::parse("remote = \"user@host\"\nignore_partial = true").unwrap();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Exit(12, "protocol data stream error".into()));
+
+        let err = sync_push(&mock, &config, "s1", &repo_root(), &SyncOptions::default())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("protocol data stream"), "{err}");
+    }
+
+    #[test]
+    fn missing_exclude_from_fails_before_rsync() {
+        let dir = tempfile::tempdir().unwrap();
+        let config =
+            Config::parse("remote = \"user@host\"\nexclude_from = \"missing.txt\"").unwrap();
+        let mock = MockRunner::new();
+
+        let push = sync_push(&mock, &config, "s1", dir.path(), &SyncOptions::default());
+        let err = push.unwrap_err();
+        assert!(
+            matches!(err, Error::PathNotFound { flag: "exclude_from", ref path } if *path == dir.path().join("missing.txt")),
+            "{err}"
+        );
+        let pull = sync_pull(&mock, &config, "s1", dir.path(), &SyncOptions::default());
+        assert!(pull.unwrap_err().to_string().contains("missing.txt"));
+        assert!(mock.invocations().is_empty());
+    }
+
+    #[test]
+    fn existing_exclude_from_is_passed_to_rsync() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("ignore.txt"), "*.log\n").unwrap();
+        let config =
+            Config::parse("remote = \"user@host\"\nexclude_from = \"ignore.txt\"").unwrap();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new()));
+
+        sync_push(&mock, &config, "s1", dir.path(), &SyncOptions::default()).unwrap();
+
+        match &mock.invocations()[0] {
+            Invocation::Rsync { args, .. } => {
+                assert!(args
+                    .iter()
+                    .any(|a| a.starts_with("--exclude-from=") && a.ends_with("ignore.txt")));
+            }
+            other => panic!("expected Rsync, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn missing_filter_file_flags_fail_before_rsync() {
+        let dir = tempfile::tempdir().unwrap();
+        let mock = MockRunner::new();
+        for options in [
+            SyncOptions {
+                include_from: Some(dir.path().join("keep.txt")),
+                ..Default::default()
+            },
+            SyncOptions {
+                exclude_from: Some(dir.path().join("skip.txt")),
+                ..Default::default()
+            },
+        ] {
+            let flag = if options.include_from.is_some() {
+                "--include-from"
+            } else {
+                "--exclude-from"
+            };
+            let push = sync_push(&mock, &test_config(), "s1", dir.path(), &options);
+            let err = push.unwrap_err().to_string();
+            assert!(err.contains(flag), "{err}");
+            let pull = sync_pull(&mock, &test_config(), "s1", dir.path(), &options);
+            assert!(pull.unwrap_err().to_string().contains(flag));
+        }
+        assert!(mock.invocations().is_empty());
+    }
+
+    #[test]
+    fn existing_filter_file_flags_are_passed_to_rsync() {
+        let dir = tempfile::tempdir().unwrap();
+        let keep = dir.path().join("keep.txt");
+        let skip = dir.path().join("skip.txt");
+        std::fs::write(&keep, "build/keep.bin\n").unwrap();
+        std::fs::write(&skip, "*.tmp\n").unwrap();
+        let options = SyncOptions {
+            include_from: Some(keep.clone()),
+            exclude_from: Some(skip.clone()),
+            ..Default::default()
+        };
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new()));
+
+        sync_push(&mock, &test_config(), "s1", dir.path(), &options).unwrap();
+
+        match &mock.invocations()[0] {
+            Invocation::Rsync { args, .. } => {
+                assert!(args.contains(&format!("--include-from={}", keep.display())));
+                assert!(args.contains(&format!("--exclude-from={}", skip.display())));
+            }
+            other => panic!("expected Rsync, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn copy_checks_both_sessions_then_runs_remote_rsync() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // from exists
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // to exists
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into())); // to not locked
+        mock.add_response(MockResponse::Ok(String::new())); // rsync
+
+        sync_copy(&mock, &test_config(), "a", "b").unwrap();
+
+        let inv = mock.invocations();
+        assert_eq!(inv.len(), 4);
+        assert!(
+            matches!(&inv[0], Invocation::Ssh { command, .. } if command.contains("test -d ~/relocal/a"))
+        );
+        assert!(
+            matches!(&inv[1], Invocation::Ssh { command, .. } if command.contains("test -d ~/relocal/b"))
+        );
+        assert!(
+            matches!(&inv[2], Invocation::Ssh { command, .. } if command.contains(".locks/b.lock"))
+        );
+        match &inv[3] {
+            Invocation::Ssh { remote, command } => {
+                assert_eq!(remote, "user@host");
+                assert!(command.starts_with("rsync -a --delete"));
+                assert!(command.ends_with("~/relocal/a/ ~/relocal/b/"));
+            }
+            other => panic!("expected Ssh, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn copy_fails_when_source_missing() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into()));
+
+        let err = sync_copy(&mock, &test_config(), "a", "b").unwrap_err();
+        assert!(err.to_string().contains("'a' not found"));
+        assert_eq!(mock.invocations().len(), 1);
+    }
+
+    #[test]
+    fn copy_fails_when_destination_missing() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into()));
+
+        let err = sync_copy(&mock, &test_config(), "a", "b").unwrap_err();
+        assert!(err.to_string().contains("'b' not found"));
+        assert_eq!(mock.invocations().len(), 2);
+    }
+
+    #[test]
+    fn copy_refuses_locked_destination() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // locked
+
+        let err = sync_copy(&mock, &test_config(), "a", "b").unwrap_err();
+        assert!(matches!(err, Error::SessionInUse { ref session } if session == "b"));
+        assert_eq!(mock.invocations().len(), 3);
+    }
+
+    #[test]
+    fn copy_to_same_session_is_rejected() {
+        let mock = MockRunner::new();
+        assert!(sync_copy(&mock, &test_config(), "a", "a").is_err());
+        assert!(mock.invocations().is_empty());
+    }
+
+    #[test]
+    fn copy_remote_rsync_failure_returns_error() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into()));
+        mock.add_response(MockResponse::Fail("rsync: command not found".into()));
+
+        let err = sync_copy(&mock, &test_config(), "a", "b").unwrap_err();
+        assert!(err.to_string().contains("remote rsync"));
+    }
+
+    #[test]
+    fn bidirectional_safe_pushes_then_pulls_with_update() {
+        let mock = MockRunner::new();
+        // push rsync, pull git fsck, pull rsync
+        mock.add_response(MockResponse::Ok(String::new()));
+        mock.add_response(MockResponse::Ok(String::new()));
+        mock.add_response(MockResponse::Ok(String::new()));
+
+        sync_bidirectional_safe(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            &SyncOptions::default(),
+        )
+        .unwrap();
+
+        let inv = mock.invocations();
+        assert_eq!(inv.len(), 3);
+        let directions: Vec<Direction> = inv
+            .iter()
+            .filter_map(|i| match i {
+                Invocation::Rsync {
+                    args, direction, ..
+                } => {
+                    assert!(args.contains(&"--update".to_string()));
+                    assert!(!args.contains(&"--delete".to_string()));
+                    Some(*direction)
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(directions, vec![Direction::Push, Direction::Pull]);
+    }
+
+    #[test]
+    fn bidirectional_safe_skips_pull_when_push_fails() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Fail("connection refused".into()));
+
+        let result = sync_bidirectional_safe(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            &SyncOptions::default(),
+        );
+        assert!(result.is_err());
+        assert_eq!(mock.invocations().len(), 1);
+    }
+
+    /// Rsync invocations as `(direction, args)`.
+    fn rsyncs(mock: &MockRunner) -> Vec<(Direction, Vec<String>)> {
+        mock.invocations()
+            .into_iter()
+            .filter_map(|inv| match inv {
+                Invocation::Rsync {
+                    args, direction, ..
+                } => Some((direction, args)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn unix_now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    #[test]
+    fn reconcile_syncs_one_sided_changes_and_reports_conflicts() {
+        let repo = tempfile::tempdir().unwrap();
+        let old = SystemTime::now() - Duration::from_secs(3600);
+        for name in ["mine.txt", "theirs.txt", "both.txt"] {
+            std::fs::write(repo.path().join(name), name).unwrap();
+        }
+        std::fs::File::options()
+            .write(true)
+            .open(repo.path().join("theirs.txt"))
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        mock.add_response(MockResponse::Ok(format!(
+            "{}\n./theirs.txt\n./both.txt\n",
+            unix_now() - 60
+        )));
+        mock.add_response(MockResponse::Ok(
+            ">f.st...... mine.txt\n>f.st...... theirs.txt\n>f.st...... both.txt\n".into(),
+        ));
+        mock.add_response(MockResponse::Ok(String::new())); // push rsync
+        mock.add_response(MockResponse::Ok(String::new())); // git fsck
+        mock.add_response(MockResponse::Ok(String::new())); // pull rsync
+        mock.add_response(MockResponse::Ok(String::new())); // commit baseline
+
+        let err = sync_reconcile(
+            &mock,
+            &test_config(),
+            "s1",
+            repo.path(),
+            &SyncOptions::default(),
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::SyncConflict { count: 1, .. }), "{err}");
+
+        let rsyncs = rsyncs(&mock);
+        assert_eq!(rsyncs.len(), 3);
+        assert!(rsyncs[0].1.contains(&"--dry-run".to_string()));
+        assert_eq!(rsyncs[1].0, Direction::Push);
+        assert!(rsyncs[1].1.contains(&"--include=/mine.txt".to_string()));
+        assert_eq!(rsyncs[2].0, Direction::Pull);
+        assert!(rsyncs[2].1.contains(&"--include=/theirs.txt".to_string()));
+        for (_, args) in &rsyncs[1..] {
+            assert!(args.contains(&"--exclude=*".to_string()));
+            assert!(!args.contains(&"--include=/both.txt".to_string()));
+        }
+        let inv = mock.invocations();
+        assert!(
+            matches!(inv.last(), Some(Invocation::Ssh { command, .. }) if command.contains("last-sync"))
+        );
+    }
+
+    #[test]
+    fn reconcile_without_baseline_touches_nothing() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        mock.add_response(MockResponse::Ok(String::new())); // no baseline
+        mock.add_response(MockResponse::Ok(
+            ">f.st...... a.txt\n*deleting   b.txt\n".into(),
+        ));
+
+        let err = sync_reconcile(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            &SyncOptions::default(),
+            None,
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, Error::SyncConflict { count: 2, .. }), "{err}");
+        assert_eq!(rsyncs(&mock).len(), 1);
+        // No baseline is committed while the first sync leaves conflicts.
+        assert!(!mock.invocations().iter().any(
+            |inv| matches!(inv, Invocation::Ssh { command, .. } if command.contains("mv .relocal/sync-pending"))
+        ));
+    }
+
+    #[test]
+    fn reconcile_never_deletes_paths_that_were_never_on_both_sides() {
+        let repo = tempfile::tempdir().unwrap();
+        let old = SystemTime::now() - Duration::from_secs(3600);
+        let local = std::fs::File::create(repo.path().join("local.txt")).unwrap();
+        local.set_modified(old).unwrap();
+        let differences = ">f+++++++++ remote.txt\n*deleting   local.txt\n";
+
+        // First run: no baseline, both one-sided paths conflict.
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        mock.add_response(MockResponse::Ok(String::new())); // no baseline
+        mock.add_response(MockResponse::Ok(differences.into()));
+        let err = sync_reconcile(
+            &mock,
+            &test_config(),
+            "s1",
+            repo.path(),
+            &SyncOptions::default(),
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::SyncConflict { count: 2, .. }), "{err}");
+        assert_eq!(rsyncs(&mock).len(), 1);
+
+        // Second run: a baseline exists, but neither path was ever on both
+        // sides, and neither changed since. Both are copied, not deleted.
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        mock.add_response(MockResponse::Ok(format!(
+            "{}\n=./other.txt\n",
+            unix_now() - 60
+        )));
+        mock.add_response(MockResponse::Ok(differences.into()));
+        mock.add_response(MockResponse::Ok(String::new())); // push rsync
+        mock.add_response(MockResponse::Ok(String::new())); // git fsck
+        mock.add_response(MockResponse::Ok(String::new())); // pull rsync
+        mock.add_response(MockResponse::Ok(String::new())); // commit baseline
+        sync_reconcile(
+            &mock,
+            &test_config(),
+            "s1",
+            repo.path(),
+            &SyncOptions::default(),
+            None,
+        )
+        .unwrap();
+
+        let rsyncs = rsyncs(&mock);
+        assert_eq!(rsyncs.len(), 3);
+        assert_eq!(rsyncs[1].0, Direction::Push);
+        assert!(rsyncs[1].1.contains(&"--include=/local.txt".to_string()));
+        assert_eq!(rsyncs[2].0, Direction::Pull);
+        assert!(rsyncs[2].1.contains(&"--include=/remote.txt".to_string()));
+        assert!(repo.path().join("local.txt").exists());
+    }
+
+    #[test]
+    fn reconcile_prefer_remote_pulls_conflicts() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        mock.add_response(MockResponse::Ok(String::new())); // no baseline
+        mock.add_response(MockResponse::Ok(
+            ">f.st...... src/a.txt\n*deleting   b.txt\n".into(),
+        ));
+        mock.add_response(MockResponse::Ok(String::new())); // git fsck
+        mock.add_response(MockResponse::Ok(String::new())); // pull rsync
+        mock.add_response(MockResponse::Ok(String::new())); // commit baseline
+
+        sync_reconcile(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            &SyncOptions::default(),
+            Some(Side::Remote),
+        )
+        .unwrap();
+
+        let rsyncs = rsyncs(&mock);
+        assert_eq!(rsyncs.len(), 2);
+        let (direction, args) = &rsyncs[1];
+        assert_eq!(*direction, Direction::Pull);
+        for include in [
+            "--include=/src/",
+            "--include=/src/a.txt",
+            "--include=/b.txt",
+        ] {
+            assert!(args.contains(&include.to_string()), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn reconcile_missing_session_fails_before_baseline() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into()));
+
+        let err = sync_reconcile(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            &SyncOptions::default(),
+            None,
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("not found"), "{err}");
+        assert_eq!(mock.invocations().len(), 1);
+    }
+
+    fn git_repo() -> tempfile::TempDir {
+        let repo = tempfile::tempdir().unwrap();
+        let status = std::process::Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(repo.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+        std::fs::write(repo.path().join("main.rs"), "fn main() {}").unwrap();
+        repo
+    }
+
+    #[test]
+    fn push_records_sync_state() {
+        let repo = git_repo();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new()));
+
+        sync_push(
+            &mock,
+            &test_config(),
+            "s1",
+            repo.path(),
+            &SyncOptions::default(),
+        )
+        .unwrap();
+
+        let state = session::read_sync_state(repo.path(), "s1")
+            .unwrap()
+            .unwrap();
+        assert!(state.files.contains_key("main.rs"));
+        assert_eq!(session::read_sync_state(repo.path(), "s2").unwrap(), None);
+    }
+
+    #[test]
+    fn dry_runs_and_scratch_pulls_do_not_record_sync_state() {
+        let repo = git_repo();
+        let scratch = tempfile::tempdir().unwrap();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // dry-run push
+        mock.add_response(MockResponse::Ok(String::new())); // git fsck
+        mock.add_response(MockResponse::Ok(String::new())); // pull rsync
+
+        sync_push(&mock, &test_config(), "s1", repo.path(), &dry_run()).unwrap();
+        let scratch_pull = SyncOptions {
+            local_path: Some(scratch.path().to_path_buf()),
+            ..Default::default()
+        };
+        sync_pull(&mock, &test_config(), "s1", repo.path(), &scratch_pull).unwrap();
+
+        assert_eq!(session::read_sync_state(repo.path(), "s1").unwrap(), None);
+    }
+
+    #[test]
+    fn partial_syncs_do_not_record_sync_state() {
+        let repo = git_repo();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // push rsync
+        mock.add_response(MockResponse::Ok(String::new())); // git fsck
+        mock.add_response(MockResponse::Ok(String::new())); // pull rsync
+
+        let partial = SyncOptions {
+            only_paths: vec!["main.rs".into()],
+            ..Default::default()
+        };
+        sync_push(&mock, &test_config(), "s1", repo.path(), &partial).unwrap();
+        sync_pull(&mock, &test_config(), "s1", repo.path(), &partial).unwrap();
+
+        assert_eq!(session::read_sync_state(repo.path(), "s1").unwrap(), None);
+    }
+
+    #[test]
+    fn pull_still_runs_with_local_changes_since_last_sync() {
+        let repo = git_repo();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // push rsync
+        mock.add_response(MockResponse::Ok(String::new())); // git fsck
+        mock.add_response(MockResponse::Ok(String::new())); // pull rsync
+
+        let options = SyncOptions::default();
+        sync_push(&mock, &test_config(), "s1", repo.path(), &options).unwrap();
+        std::fs::write(repo.path().join("main.rs"), "fn main() { edited(); }").unwrap();
+        sync_pull(&mock, &test_config(), "s1", repo.path(), &options).unwrap();
+
+        // The warning doesn't block the pull, which records the new state.
+        assert!(matches!(
+            mock.invocations().last(),
+            Some(Invocation::Rsync { .. })
+        ));
+        let state = session::read_sync_state(repo.path(), "s1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(state.files["main.rs"].size, 23);
+    }
+
+    #[test]
+    fn background_pulls_do_not_record_sync_state() {
+        let repo = git_repo();
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // git fsck
+        mock.add_response(MockResponse::Ok(String::new())); // pull rsync
+
+        let background = SyncOptions {
+            background: true,
+            ..Default::default()
+        };
+        sync_pull(&mock, &test_config(), "s1", repo.path(), &background).unwrap();
+
+        assert_eq!(session::read_sync_state(repo.path(), "s1").unwrap(), None);
+    }
 }
@@ -0,0 +1,433 @@
+//! `relocal watch [session-name]` — push automatically on local file changes.
+//!
+//! Watches the repo root with a filesystem notifier and runs [`sync_push`]
+//! once a burst of changes has been quiet for [`DEBOUNCE`]. Changes rsync
+//! would never transfer do not trigger a push: the sync's filter rules (see
+//! [`FilterSet`]) are matched locally, and `.gitignore` rules are applied by
+//! asking `git check-ignore` about each batch. Paths included ahead of
+//! `.gitignore` (`always_include`, `--include-from`) bypass the gitignore
+//! check, as they do in rsync.
+//! Ctrl-C stops the watcher and prints how many pushes ran.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use notify::{EventKind, RecursiveMode, Watcher};
+use shell_quote::{Bash, QuoteRefExt};
+use tracing::{debug, info, warn};
+
+use crate::checksum::quote_local_dir;
+use crate::commands::sync::sync_push;
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::rsync::{FilterSet, PathFilter, SyncOptions};
+use crate::runner::CommandRunner;
+
+/// Quiet period after the last relevant change before a push starts.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Batches larger than this are pushed without consulting `git check-ignore`,
+/// keeping its command line bounded (e.g. after a branch switch).
+const MAX_IGNORE_CHECK: usize = 1000;
+
+/// Upper bound on how long the loop blocks, so Ctrl-C is noticed promptly.
+const TICK: Duration = Duration::from_millis(200);
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_sigint(_: libc::c_int) {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+/// Push counts reported when the watcher stops.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct WatchSummary {
+    pub pushes: usize,
+    pub failures: usize,
+}
+
+/// Watches `repo_root` and pushes to the session until Ctrl-C.
+///
+/// Push failures are logged and counted; the watcher keeps running.
+pub fn run(
+    runner: &dyn CommandRunner,
+    config: &Config,
+    session_name: &str,
+    repo_root: &Path,
+    options: &SyncOptions,
+) -> Result<WatchSummary> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).map_err(watch_error)?;
+    watcher
+        .watch(repo_root, RecursiveMode::Recursive)
+        .map_err(watch_error)?;
+
+    // SAFETY: the handler only stores to an atomic, which is async-signal-safe.
+    unsafe {
+        libc::signal(
+            libc::SIGINT,
+            on_sigint as extern "C" fn(libc::c_int) as libc::sighandler_t,
+        );
+    }
+
+    let filter = FilterSet::new(config, repo_root, options).path_filter();
+    info!("Watching {} (Ctrl-C to stop)...", repo_root.display());
+    let mut summary = WatchSummary::default();
+    let mut batch = Batch::default();
+
+    while !INTERRUPTED.load(Ordering::SeqCst) {
+        match rx.recv_timeout(TICK) {
+            Ok(Ok(event)) => {
+                if matches!(event.kind, EventKind::Access(_)) {
+                    continue;
+                }
+                let now = Instant::now();
+                for path in &event.paths {
+                    if let Some(rel) = candidate_path(repo_root, path, &filter) {
+                        batch.add(rel, now);
+                    }
+                }
+            }
+            Ok(Err(e)) => warn!("watch error: {e}"),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        if batch.is_due(Instant::now()) {
+            let changed = unignored(runner, &filter, repo_root, &batch.take())?;
+            flush(
+                runner,
+                config,
+                session_name,
+                repo_root,
+                options,
+                &changed,
+                &mut summary,
+            );
+        }
+    }
+
+    info!(
+        "Watch stopped: {} push(es), {} failed.",
+        summary.pushes, summary.failures
+    );
+    Ok(summary)
+}
+
+fn watch_error(e: notify::Error) -> Error {
+    Error::CommandFailed {
+        command: "watch".to_string(),
+        message: e.to_string(),
+    }
+}
+
+/// Changed paths accumulated during one burst.
+#[derive(Debug, Default)]
+struct Batch {
+    paths: BTreeSet<String>,
+    last_change: Option<Instant>,
+}
+
+impl Batch {
+    fn add(&mut self, path: String, now: Instant) {
+        self.paths.insert(path);
+        self.last_change = Some(now);
+    }
+
+    /// Whether the burst has been quiet for [`DEBOUNCE`].
+    fn is_due(&self, now: Instant) -> bool {
+        self.last_change
+            .is_some_and(|last| now.duration_since(last) >= DEBOUNCE)
+    }
+
+    fn take(&mut self) -> Vec<String> {
+        self.last_change = None;
+        std::mem::take(&mut self.paths).into_iter().collect()
+    }
+}
+
+/// Maps an event path to a repo-relative path, or `None` if rsync never
+/// transfers it regardless of `.gitignore`.
+fn candidate_path(repo_root: &Path, path: &Path, filter: &PathFilter) -> Option<String> {
+    let rel = path
+        .strip_prefix(repo_root)
+        .ok()?
+        .to_string_lossy()
+        .into_owned();
+    if rel.is_empty() || filter.is_excluded(&rel) {
+        return None;
+    }
+    Some(rel)
+}
+
+/// Drops paths `.gitignore` excludes from the batch. `.git/` itself and
+/// paths included ahead of `.gitignore` are kept without asking git.
+fn unignored(
+    runner: &dyn CommandRunner,
+    filter: &PathFilter,
+    repo_root: &Path,
+    paths: &[String],
+) -> Result<Vec<String>> {
+    let (mut kept, to_check): (Vec<String>, Vec<String>) = paths.iter().cloned().partition(|p| {
+        p == ".git" || p.starts_with(".git/") || filter.is_included_before_gitignore(p)
+    });
+    if to_check.is_empty() || to_check.len() > MAX_IGNORE_CHECK {
+        kept.extend(to_check);
+        return Ok(kept);
+    }
+
+    let quoted: Vec<String> = to_check.iter().map(|p| p.as_str().quoted(Bash)).collect();
+    let cmd = format!(
+        "cd {} && git check-ignore -- {}",
+        quote_local_dir(repo_root),
+        quoted.join(" ")
+    );
+    // Exits 1 when nothing is ignored and 128 outside a git repo; either way
+    // stdout lists exactly the ignored paths.
+    let output = runner.run_local("bash", &["-c", &cmd])?;
+    let ignored: BTreeSet<&str> = output.stdout.lines().collect();
+    kept.extend(
+        to_check
+            .into_iter()
+            .filter(|p| !ignored.contains(p.as_str())),
+    );
+    kept.sort();
+    Ok(kept)
+}
+
+/// Pushes if any path in the batch survived the gitignore check.
+fn flush(
+    runner: &dyn CommandRunner,
+    config: &Config,
+    session_name: &str,
+    repo_root: &Path,
+    options: &SyncOptions,
+    changed: &[String],
+    summary: &mut WatchSummary,
+) {
+    if changed.is_empty() {
+        debug!("Ignored-only changes, skipping push");
+        return;
+    }
+    info!("{} path(s) changed, pushing...", changed.len());
+    summary.pushes += 1;
+    if let Err(e) = sync_push(runner, config, session_name, repo_root, options) {
+        summary.failures += 1;
+        warn!("push failed: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    use crate::test_support::{Invocation, MockResponse, MockRunner};
+
+    fn test_config() -> Config {
+        Config::parse("remote = \"user@host\"\nexclude = [\"*.log\"]\nalways_include = [\".env\"]")
+            .unwrap()
+    }
+
+    fn root() -> PathBuf {
+        PathBuf::from("/tmp/repo")
+    }
+
+    fn filter() -> PathFilter {
+        FilterSet::new(&test_config(), &root(), &SyncOptions::default()).path_filter()
+    }
+
+    fn candidate(rel: &str) -> Option<String> {
+        candidate_path(&root(), &root().join(rel), &filter())
+    }
+
+    fn strings(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn candidate_keeps_regular_files() {
+        assert_eq!(candidate("src/main.rs").as_deref(), Some("src/main.rs"));
+    }
+
+    #[test]
+    fn candidate_drops_fixed_and_user_excludes() {
+        assert_eq!(candidate("relocal.toml"), None);
+        assert_eq!(candidate(".claude/settings.json"), None);
+        assert_eq!(candidate(".relocal/origin.json"), None);
+        assert_eq!(candidate("logs/debug.log"), None);
+    }
+
+    #[test]
+    fn candidate_applies_filter_files_in_rsync_order() {
+        let repo = tempfile::tempdir().unwrap();
+        std::fs::write(repo.path().join(".relocalignore"), "secret.bin\n").unwrap();
+        let config = Config::parse(
+            "remote = \"user@host\"\nexclude_from = \".relocalignore\"\nalways_include = [\".claude/\"]",
+        )
+        .unwrap();
+        let filter = FilterSet::new(&config, repo.path(), &SyncOptions::default()).path_filter();
+        let candidate = |rel: &str| candidate_path(repo.path(), &repo.path().join(rel), &filter);
+        assert_eq!(candidate("secret.bin"), None);
+        // Built-in excludes come before always_include.
+        assert_eq!(candidate(".claude/settings.json"), None);
+        assert_eq!(candidate("src/main.rs").as_deref(), Some("src/main.rs"));
+    }
+
+    #[test]
+    fn candidate_drops_paths_outside_repo_and_root_itself() {
+        let filter = filter();
+        assert_eq!(
+            candidate_path(&root(), Path::new("/etc/passwd"), &filter),
+            None
+        );
+        assert_eq!(candidate_path(&root(), &root(), &filter), None);
+    }
+
+    #[test]
+    fn batch_is_due_only_after_quiet_period() {
+        let start = Instant::now();
+        let mut batch = Batch::default();
+        assert!(!batch.is_due(start));
+
+        batch.add("a".into(), start);
+        assert!(!batch.is_due(start + DEBOUNCE / 2));
+        batch.add("b".into(), start + DEBOUNCE / 2);
+        assert!(!batch.is_due(start + DEBOUNCE));
+        assert!(batch.is_due(start + DEBOUNCE / 2 + DEBOUNCE));
+
+        assert_eq!(batch.take(), strings(&["a", "b"]));
+        assert!(!batch.is_due(start + DEBOUNCE * 10));
+    }
+
+    #[test]
+    fn batch_deduplicates_paths() {
+        let now = Instant::now();
+        let mut batch = Batch::default();
+        batch.add("a".into(), now);
+        batch.add("a".into(), now);
+        assert_eq!(batch.take(), strings(&["a"]));
+    }
+
+    #[test]
+    fn unignored_asks_git_and_drops_ignored_paths() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok("target/out\n".into()));
+
+        let kept = unignored(
+            &mock,
+            &filter(),
+            &root(),
+            &strings(&["src/lib.rs", "target/out"]),
+        )
+        .unwrap();
+        assert_eq!(kept, strings(&["src/lib.rs"]));
+
+        match &mock.invocations()[0] {
+            Invocation::Local { program, args } => {
+                assert_eq!(program, "bash");
+                assert!(args[1].starts_with("cd /tmp/repo && git check-ignore -- "));
+                assert!(args[1].contains("src/lib.rs"));
+            }
+            other => panic!("expected Local, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn unignored_treats_nonzero_exit_as_nothing_ignored() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Fail(String::new()));
+
+        let kept = unignored(&mock, &filter(), &root(), &strings(&["a.rs"])).unwrap();
+        assert_eq!(kept, strings(&["a.rs"]));
+    }
+
+    #[test]
+    fn unignored_skips_git_for_git_dir_and_always_include() {
+        let mock = MockRunner::new();
+
+        let kept = unignored(&mock, &filter(), &root(), &strings(&[".env", ".git/index"])).unwrap();
+        assert_eq!(kept, strings(&[".env", ".git/index"]));
+        assert!(mock.invocations().is_empty());
+    }
+
+    #[test]
+    fn unignored_skips_git_for_huge_batches() {
+        let mock = MockRunner::new();
+        let paths: Vec<String> = (0..=MAX_IGNORE_CHECK).map(|i| format!("f{i}")).collect();
+
+        let kept = unignored(&mock, &filter(), &root(), &paths).unwrap();
+        assert_eq!(kept.len(), paths.len());
+        assert!(mock.invocations().is_empty());
+    }
+
+    #[test]
+    fn flush_skips_push_when_everything_is_ignored() {
+        let mock = MockRunner::new();
+        let mut summary = WatchSummary::default();
+
+        flush(
+            &mock,
+            &test_config(),
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+            &[],
+            &mut summary,
+        );
+        assert_eq!(summary, WatchSummary::default());
+        assert!(mock.invocations().is_empty());
+    }
+
+    #[test]
+    fn flush_pushes_and_counts() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // rsync
+        let mut summary = WatchSummary::default();
+
+        flush(
+            &mock,
+            &test_config(),
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+            &strings(&["src/lib.rs"]),
+            &mut summary,
+        );
+        assert_eq!(
+            summary,
+            WatchSummary {
+                pushes: 1,
+                failures: 0
+            }
+        );
+        assert!(matches!(mock.invocations()[0], Invocation::Rsync { .. }));
+    }
+
+    #[test]
+    fn flush_counts_failed_push_and_keeps_going() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Fail("connection reset".into()));
+        let mut summary = WatchSummary::default();
+
+        flush(
+            &mock,
+            &test_config(),
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+            &strings(&["src/lib.rs"]),
+            &mut summary,
+        );
+        assert_eq!(
+            summary,
+            WatchSummary {
+                pushes: 1,
+                failures: 1
+            }
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! `relocal whoami` — shows the identity the configured remote resolves to.
+//!
+//! `remote` may be an SSH config alias, so the user and host actually reached
+//! can differ from what relocal.toml says. Running `whoami && hostname` on the
+//! remote confirms them before anything destructive.
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::runner::CommandRunner;
+use crate::ssh;
+
+/// Prints `you are <user>@<host> (configured remote: <remote>)` and returns
+/// the `<user>@<host>` part.
+pub fn run(runner: &dyn CommandRunner, config: &Config) -> Result<String> {
+    let output = runner
+        .run_ssh(&config.remote, &ssh::whoami())?
+        .check("whoami")?;
+    let identity = parse_identity(&output.stdout).ok_or_else(|| Error::Remote {
+        remote: config.remote.clone(),
+        message: format!("unexpected whoami output: {:?}", output.stdout),
+    })?;
+    println!("you are {identity} (configured remote: {})", config.remote);
+    Ok(identity)
+}
+
+/// Parses `<user>\n<host>\n` into `<user>@<host>`.
+fn parse_identity(stdout: &str) -> Option<String> {
+    let mut lines = stdout.lines().map(str::trim).filter(|l| !l.is_empty());
+    let user = lines.next()?;
+    let host = lines.next()?;
+    Some(format!("{user}@{host}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{Invocation, MockResponse, MockRunner};
+
+    fn test_config() -> Config {
+        Config::parse("remote = \"devbox\"").unwrap()
+    }
+
+    #[test]
+    fn parse_identity_joins_user_and_host() {
+        assert_eq!(
+            parse_identity("alice\nbuild-01\n").as_deref(),
+            Some("alice@build-01")
+        );
+        assert_eq!(parse_identity("alice\n"), None);
+        assert_eq!(parse_identity(""), None);
+    }
+
+    #[test]
+    fn reports_remote_identity() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok("alice\nbuild-01\n".into()));
+
+        let identity = run(&mock, &test_config()).unwrap();
+
+        assert_eq!(identity, "alice@build-01");
+        match &mock.invocations()[0] {
+            Invocation::Ssh { remote, command } => {
+                assert_eq!(remote, "devbox");
+                assert_eq!(command, "whoami && hostname");
+            }
+            other => panic!("expected ssh, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn failing_command_is_an_error() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Fail("connection refused".into()));
+        assert!(run(&mock, &test_config()).is_err());
+    }
+
+    #[test]
+    fn garbled_output_is_an_error() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok("alice".into()));
+        let err = run(&mock, &test_config()).unwrap_err();
+        assert!(err.to_string().contains("unexpected"), "{err}");
+    }
+}
@@ -6,40 +6,383 @@
 //!
 //! Unknown keys are silently ignored so that older binaries can read configs
 //! written for newer versions (forward compatibility).
+//!
+//! Instead of a plaintext `remote`, a layer may set `remote_encrypted`, which
+//! is decrypted at load time by piping it through `remote_decrypt_command`.
 
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use crate::error::{Error, Result};
-use serde::Deserialize;
+use crate::runner::{
+    CommandRunner, TimeoutPolicy, DEFAULT_CHECK_TIMEOUT, DEFAULT_INSTALL_TIMEOUT,
+    DEFAULT_SSH_RETRIES,
+};
+use serde::{Deserialize, Serialize};
+use shell_quote::{Bash, QuoteRefExt};
 
 /// Resolved configuration with all required fields present.
 ///
 /// This is the type that the rest of the codebase uses. Produced by
 /// [`PartialConfig::resolve`] after merging user and project layers.
-#[derive(Debug, Clone)]
+///
+/// Serializes to `relocal.toml` syntax with fields at their default value
+/// omitted, so a minimal config stays minimal (see `relocal init`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Config {
     pub remote: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub exclude: Vec<String>,
+    /// rsync exclude-pattern file, relative to the repo root, as written.
+    /// `~` and `$VAR` are expanded by
+    /// [`exclude_from_path`](Config::exclude_from_path).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude_from: Option<String>,
+    /// Patterns synced even if `.gitignore` or `exclude` would skip them.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub always_include: Vec<String>,
+    /// Extra packages `relocal remote install` installs with the
+    /// `package_manager`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub apt_packages: Vec<String>,
+    /// System package manager `relocal remote install` uses.
+    #[serde(skip_serializing_if = "PackageManager::is_default")]
+    pub package_manager: PackageManager,
+    /// Remotes that destructive commands may target; empty allows any.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_remotes: Vec<String>,
+    /// rsync `--bwlimit` value (e.g. `"2m"`); `None` leaves bandwidth unlimited.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bwlimit: Option<String>,
+    /// Remote shell rsync uses instead of `ssh` (rsync `-e`). Other ssh
+    /// invocations are unaffected.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rsync_rsh: Option<String>,
+    /// Keep the source's permission bits as-is (`-a`). When `false`, synced
+    /// files get [`DEFAULT_CHMOD`](crate::rsync::DEFAULT_CHMOD) unless
+    /// `chmod` is set.
+    #[serde(skip_serializing_if = "is_default_preserve_perms")]
+    pub preserve_perms: bool,
+    /// rsync `--chmod` string applied on top of `-a`; takes precedence over
+    /// `preserve_perms`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chmod: Option<String>,
+    /// Extra rsync flags added in verbose mode (`-v`), e.g.
+    /// `--itemize-changes`. Each must start with `--`.
+    #[serde(skip_serializing_if = "is_default_verbose_rsync_flags")]
+    pub verbose_rsync_flags: Vec<String>,
+    /// Treat rsync's "some files vanished" exit (code 24) as success.
+    #[serde(skip_serializing_if = "is_default_ignore_vanished")]
+    pub ignore_vanished: bool,
+    /// Treat rsync's partial-transfer exit (code 23, e.g. an unreadable
+    /// file) as success, logging what failed.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub ignore_partial: bool,
+    /// Refuse to pull unless the remote working copy passes `git fsck`.
+    /// Disable for non-git projects; the local pull-target check remains.
+    #[serde(skip_serializing_if = "is_default_pull_fsck")]
+    pub pull_fsck: bool,
+    /// Retries for ssh commands that fail to connect.
+    #[serde(skip_serializing_if = "is_default_ssh_retries")]
+    pub ssh_retries: u32,
+    /// Push without `--delete`, keeping remote-only files.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub push_no_delete: bool,
+    /// Keep every sync on one filesystem (rsync `-x`).
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub one_file_system: bool,
+    /// Compare files by checksum instead of mtime and size (rsync `-c`).
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub checksum: bool,
+    /// Refuse unknown or changed host keys instead of prompting (ssh
+    /// `StrictHostKeyChecking=yes`, `BatchMode=yes`).
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub strict_host_key: bool,
+    /// Seconds an idle SSH ControlMaster lingers after its last command
+    /// (ssh `ControlPersist`).
+    #[serde(skip_serializing_if = "is_default_control_persist")]
+    pub control_persist: u64,
+    /// Force (`true`) or suppress (`false`) `ssh -t` for interactive
+    /// sessions; `None` detects whether stdin is a terminal.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub force_tty: Option<bool>,
+    /// Launch `claude` with `--dangerously-skip-permissions` (`codex` with
+    /// `--yolo`). Disable for a normal permission-prompting session.
+    #[serde(skip_serializing_if = "is_default_skip_permissions")]
+    pub skip_permissions: bool,
+    /// Whether some config layer set `skip_permissions` rather than leaving
+    /// it at the default. Not a config key.
+    #[serde(skip)]
+    pub skip_permissions_explicit: bool,
+    /// Claude Code binary on the remote: a name looked up on the login
+    /// `PATH`, or a path (`~/` is expanded by the remote shell).
+    #[serde(skip_serializing_if = "is_default_claude_bin")]
+    pub claude_bin: String,
+    /// Claude Code version `relocal remote install` installs (npm version or
+    /// tag) instead of the latest.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claude_version: Option<String>,
+    /// Node.js version the remote must have before `relocal remote install`
+    /// installs the npm tools: `20` matches any 20.x.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_version: Option<String>,
+    /// Seconds between the session daemon's background pulls.
+    #[serde(skip_serializing_if = "is_default_sync_interval")]
+    pub sync_interval: u64,
+    /// Seconds a remote check (e.g. `command -v`) may take; 0 waits forever.
+    #[serde(skip_serializing_if = "is_default_check_timeout")]
+    pub check_timeout: u64,
+    /// Seconds a `relocal remote install` step may take; 0 waits forever.
+    #[serde(skip_serializing_if = "is_default_install_timeout")]
+    pub install_timeout: u64,
+    /// Seconds an rsync run may take; 0 (the default) waits forever.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub rsync_timeout: u64,
+    /// Project config given with `--config`, passed on to the session
+    /// daemon. Not a config key: set by the CLI after loading.
+    #[serde(skip)]
+    pub config_path: Option<PathBuf>,
+}
+
+const DEFAULT_VERBOSE_RSYNC_FLAGS: &[&str] = &["--progress"];
+
+fn is_default_verbose_rsync_flags(value: &[String]) -> bool {
+    value == DEFAULT_VERBOSE_RSYNC_FLAGS
+}
+
+const DEFAULT_IGNORE_VANISHED: bool = true;
+
+fn is_default_ignore_vanished(value: &bool) -> bool {
+    *value == DEFAULT_IGNORE_VANISHED
+}
+
+const DEFAULT_PULL_FSCK: bool = true;
+
+fn is_default_pull_fsck(value: &bool) -> bool {
+    *value == DEFAULT_PULL_FSCK
+}
+
+fn is_default_ssh_retries(value: &u32) -> bool {
+    *value == DEFAULT_SSH_RETRIES
+}
+
+const DEFAULT_CONTROL_PERSIST: u64 = 300;
+
+fn is_default_check_timeout(value: &u64) -> bool {
+    *value == DEFAULT_CHECK_TIMEOUT
+}
+
+fn is_default_install_timeout(value: &u64) -> bool {
+    *value == DEFAULT_INSTALL_TIMEOUT
 }
 
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+fn is_default_control_persist(value: &u64) -> bool {
+    *value == DEFAULT_CONTROL_PERSIST
+}
+
+const DEFAULT_PRESERVE_PERMS: bool = true;
+
+fn is_default_preserve_perms(value: &bool) -> bool {
+    *value == DEFAULT_PRESERVE_PERMS
+}
+
+const DEFAULT_SKIP_PERMISSIONS: bool = true;
+
+fn is_default_skip_permissions(value: &bool) -> bool {
+    *value == DEFAULT_SKIP_PERMISSIONS
+}
+
+const DEFAULT_CLAUDE_BIN: &str = "claude";
+
+fn is_default_claude_bin(value: &str) -> bool {
+    value == DEFAULT_CLAUDE_BIN
+}
+
+const DEFAULT_SYNC_INTERVAL: u64 = 3;
+
+/// Upper bound for `sync_interval`, in seconds.
+const MAX_SYNC_INTERVAL: u64 = 3600;
+
+fn is_default_sync_interval(value: &u64) -> bool {
+    *value == DEFAULT_SYNC_INTERVAL
+}
+
+/// Command that decrypts `remote_encrypted` when `remote_decrypt_command` is unset.
+pub const DEFAULT_DECRYPT_COMMAND: &str = "gpg --quiet --batch --decrypt";
+
 impl Config {
     /// Parse a TOML string that must contain `remote`. Convenience for
     /// call sites that have a single authoritative config source.
     pub fn parse(input: &str) -> Result<Self> {
         PartialConfig::parse(input, "relocal.toml")?.resolve()
     }
+
+    /// Refuses a destructive command against a remote missing from a
+    /// non-empty `allowed_remotes`, unless `force` is set.
+    pub fn check_remote_allowed(&self, force: bool) -> Result<()> {
+        if force || self.allowed_remotes.is_empty() || self.allowed_remotes.contains(&self.remote) {
+            return Ok(());
+        }
+        Err(Error::RemoteNotAllowed {
+            remote: self.remote.clone(),
+        })
+    }
+
+    /// The runner timeouts set by `check_timeout`, `install_timeout` and
+    /// `rsync_timeout`, where 0 means none.
+    pub fn timeout_policy(&self) -> TimeoutPolicy {
+        let secs = |value: u64| (value > 0).then(|| Duration::from_secs(value));
+        TimeoutPolicy {
+            check: secs(self.check_timeout),
+            install: secs(self.install_timeout),
+            rsync: secs(self.rsync_timeout),
+        }
+    }
+
+    /// The `exclude_from` file with `~` and `$VAR` expanded, joined onto
+    /// `repo_root` unless absolute.
+    pub fn exclude_from_path(&self, repo_root: &Path) -> Option<PathBuf> {
+        self.exclude_from.as_ref().map(|path| {
+            // `resolve` already rejected paths that don't expand.
+            let expanded =
+                expand_path(path, |name| std::env::var(name).ok()).unwrap_or_else(|_| path.clone());
+            repo_root.join(expanded)
+        })
+    }
+
+    /// Problems that loading doesn't catch but that would break a later
+    /// command: an implausible remote, empty patterns, a missing
+    /// `exclude_from` file (resolved against `repo_root`). Checks nothing
+    /// remote. Empty when the config looks usable.
+    pub fn problems(&self, repo_root: &Path) -> Vec<String> {
+        let mut problems = Vec::new();
+        if !is_plausible_remote(&self.remote) {
+            problems.push(format!(
+                "`remote` {:?} is not `user@host` or `host`",
+                self.remote
+            ));
+        }
+        for remote in &self.allowed_remotes {
+            if !is_plausible_remote(remote) {
+                problems.push(format!(
+                    "`allowed_remotes` entry {remote:?} is not `user@host` or `host`"
+                ));
+            }
+        }
+        for (key, patterns) in [
+            ("exclude", &self.exclude),
+            ("always_include", &self.always_include),
+        ] {
+            if patterns.iter().any(|p| p.trim().is_empty()) {
+                problems.push(format!("`{key}` contains an empty pattern"));
+            }
+        }
+        if let Some(full) = self.exclude_from_path(repo_root) {
+            if !full.is_file() {
+                problems.push(format!(
+                    "`exclude_from` file {} does not exist",
+                    full.display()
+                ));
+            }
+        }
+        problems
+    }
+}
+
+/// System package manager on the remote (`package_manager`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageManager {
+    #[default]
+    Apt,
+    Dnf,
+    Yum,
+    Pacman,
+}
+
+impl PackageManager {
+    fn is_default(&self) -> bool {
+        *self == PackageManager::default()
+    }
 }
 
 /// A config layer where every field is optional.
 ///
 /// Used for deserialization of both user and project config files before
-/// merging.
-#[derive(Debug, Clone, Default, Deserialize)]
+/// merging, and serialized back out by `relocal init` (unset fields are
+/// omitted).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
 pub struct PartialConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub remote: Option<String>,
+    /// Encrypted `remote`, decrypted by [`decrypt_remote`](Self::decrypt_remote).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_encrypted: Option<String>,
+    /// Shell command reading `remote_encrypted` on stdin and printing the remote.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_decrypt_command: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub exclude: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude_from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub always_include: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub apt_packages: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package_manager: Option<PackageManager>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_remotes: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bwlimit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rsync_rsh: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preserve_perms: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chmod: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verbose_rsync_flags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_vanished: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_partial: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pull_fsck: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ssh_retries: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub push_no_delete: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub one_file_system: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strict_host_key: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub control_persist: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub force_tty: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_permissions: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claude_bin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claude_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sync_interval: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub check_timeout: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub install_timeout: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rsync_timeout: Option<u64>,
 }
 
 impl PartialConfig {
@@ -50,26 +393,301 @@ impl PartialConfig {
         })
     }
 
+    /// Reads a single config file without merging it with any other layer.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path).map_err(|e| Error::ConfigParse {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })?;
+        Self::parse(&contents, &path.display().to_string())
+    }
+
     /// Overlay `over` on top of `self`. For each field, `over` wins if present.
+    ///
+    /// `remote` and `remote_encrypted` count as one field: a layer setting
+    /// either replaces both, so a project's encrypted remote is not shadowed
+    /// by a plaintext remote from the user config.
     pub fn merge(self, over: PartialConfig) -> PartialConfig {
+        let (remote, remote_encrypted) = if over.remote.is_some() || over.remote_encrypted.is_some()
+        {
+            (over.remote, over.remote_encrypted)
+        } else {
+            (self.remote, self.remote_encrypted)
+        };
         PartialConfig {
-            remote: over.remote.or(self.remote),
+            remote,
+            remote_encrypted,
+            remote_decrypt_command: over.remote_decrypt_command.or(self.remote_decrypt_command),
             exclude: over.exclude.or(self.exclude),
+            exclude_from: over.exclude_from.or(self.exclude_from),
+            always_include: over.always_include.or(self.always_include),
             apt_packages: over.apt_packages.or(self.apt_packages),
+            package_manager: over.package_manager.or(self.package_manager),
+            allowed_remotes: over.allowed_remotes.or(self.allowed_remotes),
+            bwlimit: over.bwlimit.or(self.bwlimit),
+            rsync_rsh: over.rsync_rsh.or(self.rsync_rsh),
+            preserve_perms: over.preserve_perms.or(self.preserve_perms),
+            chmod: over.chmod.or(self.chmod),
+            verbose_rsync_flags: over.verbose_rsync_flags.or(self.verbose_rsync_flags),
+            ignore_vanished: over.ignore_vanished.or(self.ignore_vanished),
+            ignore_partial: over.ignore_partial.or(self.ignore_partial),
+            pull_fsck: over.pull_fsck.or(self.pull_fsck),
+            ssh_retries: over.ssh_retries.or(self.ssh_retries),
+            push_no_delete: over.push_no_delete.or(self.push_no_delete),
+            one_file_system: over.one_file_system.or(self.one_file_system),
+            checksum: over.checksum.or(self.checksum),
+            strict_host_key: over.strict_host_key.or(self.strict_host_key),
+            control_persist: over.control_persist.or(self.control_persist),
+            force_tty: over.force_tty.or(self.force_tty),
+            skip_permissions: over.skip_permissions.or(self.skip_permissions),
+            claude_bin: over.claude_bin.or(self.claude_bin),
+            claude_version: over.claude_version.or(self.claude_version),
+            node_version: over.node_version.or(self.node_version),
+            sync_interval: over.sync_interval.or(self.sync_interval),
+            check_timeout: over.check_timeout.or(self.check_timeout),
+            install_timeout: over.install_timeout.or(self.install_timeout),
+            rsync_timeout: over.rsync_timeout.or(self.rsync_timeout),
+        }
+    }
+
+    /// Sets `remote` by decrypting `remote_encrypted` through
+    /// `remote_decrypt_command` (default [`DEFAULT_DECRYPT_COMMAND`]).
+    ///
+    /// A plaintext `remote` takes precedence, and nothing is run when no
+    /// encrypted value is set.
+    pub fn decrypt_remote(mut self, runner: &dyn CommandRunner) -> Result<Self> {
+        if self.remote.is_some() {
+            return Ok(self);
+        }
+        let Some(ciphertext) = self.remote_encrypted.take() else {
+            return Ok(self);
+        };
+        let command = self
+            .remote_decrypt_command
+            .as_deref()
+            .unwrap_or(DEFAULT_DECRYPT_COMMAND);
+        let quoted: String = ciphertext.quoted(Bash);
+        let output = runner
+            .run_local(
+                "bash",
+                &["-c", &format!("printf '%s\\n' {quoted} | {command}")],
+            )?
+            .check("decrypt remote_encrypted")?;
+        let remote = output.stdout.trim();
+        if remote.is_empty() {
+            return Err(Error::ConfigParse {
+                path: "config".to_string(),
+                reason: format!("`remote_encrypted` decrypted to an empty value (via `{command}`)"),
+            });
+        }
+        self.remote = Some(remote.to_string());
+        Ok(self)
+    }
+
+    /// Checks every value [`resolve`](Self::resolve) checks, accepting an
+    /// encrypted remote without decrypting it.
+    pub fn validate(&self) -> Result<()> {
+        let mut config = self.clone();
+        if config.remote.is_none() && config.remote_encrypted.is_some() {
+            // Stands in for the undecrypted remote; never written anywhere.
+            config.remote = Some("encrypted".to_string());
         }
+        config.resolve().map(|_| ())
     }
 
-    /// Convert to a resolved [`Config`], failing if `remote` is missing.
+    /// Convert to a resolved [`Config`], failing if `remote` is missing or a
+    /// field has an invalid value.
+    ///
+    /// An undecrypted `remote_encrypted` counts as missing; call
+    /// [`decrypt_remote`](Self::decrypt_remote) first.
     pub fn resolve(self) -> Result<Config> {
         let remote = self.remote.ok_or_else(|| Error::ConfigParse {
             path: "config".to_string(),
             reason: "missing field `remote` (not set in ~/.relocal/config.toml or relocal.toml)"
                 .to_string(),
         })?;
+        // Only obviously unusable values; a bare host (ssh config alias) is fine.
+        if remote.is_empty() || remote.starts_with('-') || remote.chars().any(char::is_whitespace) {
+            return Err(Error::ConfigParse {
+                path: "config".to_string(),
+                reason: format!(
+                    "invalid `remote` {remote:?}: expected user@host or an ssh config Host alias"
+                ),
+            });
+        }
+        if let Some(bwlimit) = &self.bwlimit {
+            validate_bwlimit(bwlimit)?;
+        }
+        let sync_interval = self.sync_interval.unwrap_or(DEFAULT_SYNC_INTERVAL);
+        if !(1..=MAX_SYNC_INTERVAL).contains(&sync_interval) {
+            return Err(Error::ConfigParse {
+                path: "config".to_string(),
+                reason: format!(
+                    "invalid `sync_interval` {sync_interval}: expected 1 to {MAX_SYNC_INTERVAL} seconds"
+                ),
+            });
+        }
+        let verbose_rsync_flags = self.verbose_rsync_flags.unwrap_or_else(|| {
+            DEFAULT_VERBOSE_RSYNC_FLAGS
+                .iter()
+                .map(|f| f.to_string())
+                .collect()
+        });
+        if let Some(flag) = verbose_rsync_flags.iter().find(|f| !f.starts_with("--")) {
+            return Err(Error::ConfigParse {
+                path: "config".to_string(),
+                reason: format!(
+                    "invalid `verbose_rsync_flags` entry {flag:?}: expected a long option starting with `--`"
+                ),
+            });
+        }
+        let control_persist = self.control_persist.unwrap_or(DEFAULT_CONTROL_PERSIST);
+        if control_persist == 0 {
+            return Err(Error::ConfigParse {
+                path: "config".to_string(),
+                reason: "invalid `control_persist` 0: expected at least 1 second".to_string(),
+            });
+        }
+        let claude_bin = self
+            .claude_bin
+            .unwrap_or_else(|| DEFAULT_CLAUDE_BIN.to_string());
+        if claude_bin.trim().is_empty() {
+            return Err(Error::ConfigParse {
+                path: "config".to_string(),
+                reason: "invalid `claude_bin`: expected a command name or path".to_string(),
+            });
+        }
+        for (key, version) in [
+            ("claude_version", &self.claude_version),
+            ("node_version", &self.node_version),
+        ] {
+            if let Some(version) = version {
+                validate_version(key, version)?;
+            }
+        }
+        // Only checked here; the raw value is kept so writing the config
+        // back (e.g. `init`) doesn't bake in this machine's paths.
+        if let Some(path) = &self.exclude_from {
+            expand_path(path, |name| std::env::var(name).ok()).map_err(|reason| {
+                Error::ConfigParse {
+                    path: "config".to_string(),
+                    reason: format!("invalid `exclude_from` {path:?}: {reason}"),
+                }
+            })?;
+        }
         Ok(Config {
             remote,
             exclude: self.exclude.unwrap_or_default(),
+            exclude_from: self.exclude_from,
+            always_include: self.always_include.unwrap_or_default(),
             apt_packages: self.apt_packages.unwrap_or_default(),
+            package_manager: self.package_manager.unwrap_or_default(),
+            allowed_remotes: self.allowed_remotes.unwrap_or_default(),
+            bwlimit: self.bwlimit,
+            rsync_rsh: self.rsync_rsh,
+            preserve_perms: self.preserve_perms.unwrap_or(DEFAULT_PRESERVE_PERMS),
+            chmod: self.chmod,
+            verbose_rsync_flags,
+            ignore_vanished: self.ignore_vanished.unwrap_or(DEFAULT_IGNORE_VANISHED),
+            ignore_partial: self.ignore_partial.unwrap_or(false),
+            pull_fsck: self.pull_fsck.unwrap_or(DEFAULT_PULL_FSCK),
+            ssh_retries: self.ssh_retries.unwrap_or(DEFAULT_SSH_RETRIES),
+            push_no_delete: self.push_no_delete.unwrap_or(false),
+            one_file_system: self.one_file_system.unwrap_or(false),
+            checksum: self.checksum.unwrap_or(false),
+            strict_host_key: self.strict_host_key.unwrap_or(false),
+            control_persist,
+            force_tty: self.force_tty,
+            skip_permissions: self.skip_permissions.unwrap_or(DEFAULT_SKIP_PERMISSIONS),
+            skip_permissions_explicit: self.skip_permissions.is_some(),
+            claude_bin,
+            claude_version: self.claude_version,
+            node_version: self.node_version,
+            sync_interval,
+            check_timeout: self.check_timeout.unwrap_or(DEFAULT_CHECK_TIMEOUT),
+            install_timeout: self.install_timeout.unwrap_or(DEFAULT_INSTALL_TIMEOUT),
+            rsync_timeout: self.rsync_timeout.unwrap_or(0),
+            config_path: None,
+        })
+    }
+}
+
+/// Expands a leading `~` (from `HOME`) and `$NAME` / `${NAME}` references in a
+/// config path, looking variables up with `lookup`. An unset variable is an
+/// error rather than being left in place; a `$` not followed by a name is
+/// kept literally.
+fn expand_path(
+    path: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> std::result::Result<String, String> {
+    let var = |name: &str| lookup(name).ok_or_else(|| format!("${name} is not set"));
+    let mut out = String::new();
+    let mut rest = path;
+    if rest == "~" || rest.starts_with("~/") {
+        out.push_str(&var("HOME")?);
+        rest = &rest[1..];
+    }
+    while let Some(i) = rest.find('$') {
+        out.push_str(&rest[..i]);
+        let after = &rest[i + 1..];
+        let (name, tail) = if let Some(braced) = after.strip_prefix('{') {
+            let end = braced
+                .find('}')
+                .ok_or_else(|| "unterminated `${`".to_string())?;
+            (&braced[..end], &braced[end + 1..])
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], &after[end..])
+        };
+        if name.is_empty() {
+            out.push('$');
+            rest = after;
+        } else {
+            out.push_str(&var(name)?);
+            rest = tail;
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Checks that `bwlimit` looks like an rsync rate: a number with an optional
+/// unit suffix (`500`, `2m`, `1.5M`, `2MiB`). rsync does the precise parsing; this only
+/// catches typos early instead of failing on the first sync.
+fn validate_bwlimit(bwlimit: &str) -> Result<()> {
+    let number = bwlimit.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let suffix = &bwlimit[number.len()..];
+    let valid = !number.is_empty()
+        && number.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && suffix.len() <= 3;
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::ConfigParse {
+            path: "config".to_string(),
+            reason: format!(
+                "invalid `bwlimit` {bwlimit:?}: expected a rate like \"500\" or \"2m\""
+            ),
+        })
+    }
+}
+
+/// Checks that a pinned version (`claude_version`, `node_version`) is a
+/// plain version or tag like `1.0.30` or `latest`. It ends up in remote shell
+/// commands, so anything else is rejected rather than quoted.
+fn validate_version(key: &str, version: &str) -> Result<()> {
+    let valid = !version.is_empty()
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+' | '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::ConfigParse {
+            path: "config".to_string(),
+            reason: format!("invalid `{key}` {version:?}: expected a version like \"1.0.30\""),
         })
     }
 }
@@ -90,24 +708,96 @@ fn user_config_path(home: &Path) -> PathBuf {
     home.join(".relocal").join("config.toml")
 }
 
+/// Whether `remote` looks like `user@host` or a bare `host` (e.g. an ssh
+/// config alias).
+fn is_plausible_remote(remote: &str) -> bool {
+    let host = match remote.split_once('@') {
+        Some((user, host)) => {
+            if user.is_empty() || user.starts_with('-') {
+                return false;
+            }
+            host
+        }
+        None => remote,
+    };
+    !host.is_empty()
+        && !host.starts_with('-')
+        && !host.contains(['@', '/'])
+        && !remote.chars().any(char::is_whitespace)
+}
+
+/// Checks that a remote given on the command line looks like `user@host` or
+/// an ssh config alias, so a typo fails before any SSH connection is
+/// attempted.
+pub fn validate_remote(remote: &str) -> Result<()> {
+    if is_plausible_remote(remote) {
+        Ok(())
+    } else {
+        Err(Error::ConfigParse {
+            path: "--remote".to_string(),
+            reason: format!("invalid remote {remote:?}: expected user@host or host"),
+        })
+    }
+}
+
+/// Path of the optional per-session config, `<repo>/relocal.<session>.toml`.
+pub fn session_config_path(repo_root: &Path, session: &str) -> PathBuf {
+    repo_root.join(format!("relocal.{session}.toml"))
+}
+
 /// Load and merge config from user and project layers.
 ///
-/// The project config overrides the user config per-field. The merged result
-/// must have `remote`.
-pub fn load_merged_config(home: &Path, repo_root: &Path) -> Result<Config> {
+/// The project config overrides the user config per-field, and `overrides`
+/// (from command-line flags) override both. The merged result must have
+/// `remote` or a `remote_encrypted` that `runner` can decrypt.
+pub fn load_merged_config(
+    runner: &dyn CommandRunner,
+    home: &Path,
+    repo_root: &Path,
+    overrides: PartialConfig,
+) -> Result<Config> {
+    load_merged_config_from(
+        runner,
+        home,
+        &repo_root.join("relocal.toml"),
+        None,
+        overrides,
+    )
+}
+
+/// Like [`load_merged_config`], but reads the project layer from
+/// `project_config` (given with `--config`) instead of `<repo>/relocal.toml`.
+/// The file is optional here; `main` checks that an explicit path exists.
+///
+/// `session_config` (see [`session_config_path`]), if given and present, is
+/// layered between the project config and `overrides`.
+pub fn load_merged_config_from(
+    runner: &dyn CommandRunner,
+    home: &Path,
+    project_config: &Path,
+    session_config: Option<&Path>,
+    overrides: PartialConfig,
+) -> Result<Config> {
     let mut base = PartialConfig::default();
     if let Some(user) = load_optional_config(&user_config_path(home))? {
         base = base.merge(user);
     }
-    if let Some(project) = load_optional_config(&repo_root.join("relocal.toml"))? {
+    if let Some(project) = load_optional_config(project_config)? {
         base = base.merge(project);
     }
-    base.resolve()
+    if let Some(session) = session_config {
+        if let Some(session) = load_optional_config(session)? {
+            base = base.merge(session);
+        }
+    }
+    base.merge(overrides).decrypt_remote(runner)?.resolve()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::runner::Operation;
+    use crate::test_support::{Invocation, MockResponse, MockRunner};
     use std::fs;
     use tempfile::TempDir;
 
@@ -117,6 +807,178 @@ mod tests {
         assert_eq!(config.remote, "user@host");
         assert!(config.exclude.is_empty());
         assert!(config.apt_packages.is_empty());
+        assert!(config.bwlimit.is_none());
+        assert!(config.ignore_vanished);
+        assert!(config.pull_fsck);
+        assert!(config.force_tty.is_none());
+        assert!(config.skip_permissions);
+        assert!(!config.strict_host_key);
+        assert!(config.preserve_perms);
+        assert!(config.chmod.is_none());
+    }
+
+    #[test]
+    fn preserve_perms_and_chmod_parsed() {
+        let config =
+            Config::parse("remote = \"u@h\"\npreserve_perms = false\nchmod = \"Du=rwx,Fu=rw\"")
+                .unwrap();
+        assert!(!config.preserve_perms);
+        assert_eq!(config.chmod.as_deref(), Some("Du=rwx,Fu=rw"));
+    }
+
+    #[test]
+    fn any_remote_allowed_without_allowlist() {
+        let config = Config::parse("remote = \"u@prod\"").unwrap();
+        assert!(config.allowed_remotes.is_empty());
+        config.check_remote_allowed(false).unwrap();
+    }
+
+    #[test]
+    fn remote_outside_allowlist_refused_unless_forced() {
+        let config =
+            Config::parse("remote = \"u@prod\"\nallowed_remotes = [\"u@dev\", \"u@staging\"]")
+                .unwrap();
+        let err = config.check_remote_allowed(false).unwrap_err();
+        assert!(matches!(err, Error::RemoteNotAllowed { ref remote } if remote == "u@prod"));
+        assert!(err.to_string().contains("--force"));
+        config.check_remote_allowed(true).unwrap();
+    }
+
+    #[test]
+    fn allowlisted_remote_allowed() {
+        let config = Config::parse("remote = \"u@dev\"\nallowed_remotes = [\"u@dev\"]").unwrap();
+        config.check_remote_allowed(false).unwrap();
+    }
+
+    #[test]
+    fn strict_host_key_parsed() {
+        let config = Config::parse("remote = \"u@h\"\nstrict_host_key = true").unwrap();
+        assert!(config.strict_host_key);
+    }
+
+    #[test]
+    fn control_persist_parsed_and_defaults_to_300() {
+        let config = Config::parse("remote = \"u@h\"\ncontrol_persist = 30").unwrap();
+        assert_eq!(config.control_persist, 30);
+        assert_eq!(
+            Config::parse("remote = \"u@h\"").unwrap().control_persist,
+            300
+        );
+    }
+
+    #[test]
+    fn control_persist_zero_rejected() {
+        let err = Config::parse("remote = \"u@h\"\ncontrol_persist = 0").unwrap_err();
+        assert!(err.to_string().contains("control_persist"), "{err}");
+    }
+
+    #[test]
+    fn timeouts_default_per_operation() {
+        let config = Config::parse("remote = \"u@h\"").unwrap();
+        assert_eq!(
+            (
+                config.check_timeout,
+                config.install_timeout,
+                config.rsync_timeout
+            ),
+            (10, 600, 0)
+        );
+        let policy = config.timeout_policy();
+        assert_eq!(policy, TimeoutPolicy::default());
+        assert_eq!(
+            policy.for_operation(Operation::Check),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            policy.for_operation(Operation::Install),
+            Some(Duration::from_secs(600))
+        );
+        assert_eq!(policy.for_operation(Operation::Other), None);
+        assert_eq!(policy.rsync, None);
+    }
+
+    #[test]
+    fn timeouts_configurable_and_zero_disables() {
+        let config = Config::parse(
+            "remote = \"u@h\"\ncheck_timeout = 0\ninstall_timeout = 1200\nrsync_timeout = 3600",
+        )
+        .unwrap();
+        let policy = config.timeout_policy();
+        assert_eq!(policy.for_operation(Operation::Check), None);
+        assert_eq!(
+            policy.for_operation(Operation::Install),
+            Some(Duration::from_secs(1200))
+        );
+        assert_eq!(policy.rsync, Some(Duration::from_secs(3600)));
+    }
+
+    #[test]
+    fn sync_interval_parsed_and_defaults_to_3() {
+        let config = Config::parse("remote = \"u@h\"\nsync_interval = 10").unwrap();
+        assert_eq!(config.sync_interval, 10);
+        assert_eq!(Config::parse("remote = \"u@h\"").unwrap().sync_interval, 3);
+    }
+
+    #[test]
+    fn claude_bin_defaults_to_claude_and_rejects_empty() {
+        assert_eq!(
+            Config::parse("remote = \"u@h\"").unwrap().claude_bin,
+            "claude"
+        );
+        let config =
+            Config::parse("remote = \"u@h\"\nclaude_bin = \"~/.local/bin/claude\"").unwrap();
+        assert_eq!(config.claude_bin, "~/.local/bin/claude");
+        let err = Config::parse("remote = \"u@h\"\nclaude_bin = \" \"").unwrap_err();
+        assert!(err.to_string().contains("claude_bin"), "{err}");
+    }
+
+    #[test]
+    fn package_manager_parsed_and_defaults_to_apt() {
+        let config = Config::parse("remote = \"u@h\"").unwrap();
+        assert_eq!(config.package_manager, PackageManager::Apt);
+        let config = Config::parse("remote = \"u@h\"\npackage_manager = \"pacman\"").unwrap();
+        assert_eq!(config.package_manager, PackageManager::Pacman);
+        let err = Config::parse("remote = \"u@h\"\npackage_manager = \"brew\"").unwrap_err();
+        assert!(err.to_string().contains("package_manager"), "{err}");
+    }
+
+    #[test]
+    fn pinned_versions_parsed_and_validated() {
+        let config =
+            Config::parse("remote = \"u@h\"\nclaude_version = \"1.0.30\"\nnode_version = \"20\"")
+                .unwrap();
+        assert_eq!(config.claude_version.as_deref(), Some("1.0.30"));
+        assert_eq!(config.node_version.as_deref(), Some("20"));
+        let err = Config::parse("remote = \"u@h\"\nclaude_version = \"1; rm -rf ~\"").unwrap_err();
+        assert!(err.to_string().contains("claude_version"), "{err}");
+        assert!(Config::parse("remote = \"u@h\"\nnode_version = \"\"").is_err());
+    }
+
+    #[test]
+    fn sync_interval_out_of_range_rejected() {
+        for value in ["0", "3601"] {
+            let input = format!("remote = \"u@h\"\nsync_interval = {value}");
+            let err = Config::parse(&input).unwrap_err();
+            assert!(err.to_string().contains("sync_interval"), "{err}");
+        }
+    }
+
+    #[test]
+    fn skip_permissions_can_be_disabled() {
+        let config = Config::parse("remote = \"u@h\"\nskip_permissions = false").unwrap();
+        assert!(!config.skip_permissions);
+        assert!(config.skip_permissions_explicit);
+        assert!(
+            !Config::parse("remote = \"u@h\"")
+                .unwrap()
+                .skip_permissions_explicit
+        );
+    }
+
+    #[test]
+    fn force_tty_parsed() {
+        let config = Config::parse("remote = \"u@h\"\nforce_tty = false").unwrap();
+        assert_eq!(config.force_tty, Some(false));
     }
 
     #[test]
@@ -170,16 +1032,20 @@ another = "value"
             remote: Some("base@host".into()),
             exclude: Some(vec!["base.txt".into()]),
             apt_packages: Some(vec!["base-pkg".into()]),
+            bwlimit: Some("1m".into()),
+            ..Default::default()
         };
         let over = PartialConfig {
             remote: Some("over@host".into()),
             exclude: Some(vec!["over.txt".into()]),
-            apt_packages: None,
+            bwlimit: Some("5m".into()),
+            ..Default::default()
         };
         let merged = base.merge(over);
         assert_eq!(merged.remote.as_deref(), Some("over@host"));
         assert_eq!(merged.exclude, Some(vec!["over.txt".into()]));
         assert_eq!(merged.apt_packages, Some(vec!["base-pkg".into()]));
+        assert_eq!(merged.bwlimit.as_deref(), Some("5m"));
     }
 
     #[test]
@@ -187,7 +1053,7 @@ another = "value"
         let base = PartialConfig {
             remote: Some("base@host".into()),
             exclude: Some(vec![".env".into()]),
-            apt_packages: None,
+            ..Default::default()
         };
         let over = PartialConfig::default();
         let merged = base.merge(over);
@@ -207,12 +1073,74 @@ another = "value"
     fn resolve_fills_defaults() {
         let partial = PartialConfig {
             remote: Some("u@h".into()),
-            exclude: None,
-            apt_packages: None,
+            ..Default::default()
         };
         let config = partial.resolve().unwrap();
         assert!(config.exclude.is_empty());
         assert!(config.apt_packages.is_empty());
+        assert!(config.bwlimit.is_none());
+        assert!(config.ignore_vanished);
+    }
+
+    #[test]
+    fn always_include_parsed_and_defaults_empty() {
+        let config = Config::parse("remote = \"u@h\"\nalways_include = [\".env\"]").unwrap();
+        assert_eq!(config.always_include, vec![".env"]);
+        assert!(Config::parse("remote = \"u@h\"")
+            .unwrap()
+            .always_include
+            .is_empty());
+    }
+
+    #[test]
+    fn exclude_from_parsed() {
+        let config = Config::parse("remote = \"u@h\"\nexclude_from = \".relocalignore\"").unwrap();
+        assert_eq!(config.exclude_from.as_deref(), Some(".relocalignore"));
+    }
+
+    #[test]
+    fn ignore_vanished_can_be_disabled() {
+        let config = Config::parse("remote = \"u@h\"\nignore_vanished = false").unwrap();
+        assert!(!config.ignore_vanished);
+    }
+
+    #[test]
+    fn ignore_vanished_project_overrides_user() {
+        let user = PartialConfig {
+            ignore_vanished: Some(false),
+            ..Default::default()
+        };
+        let project = PartialConfig {
+            remote: Some("u@h".into()),
+            ignore_vanished: Some(true),
+            ..Default::default()
+        };
+        assert!(user.merge(project).resolve().unwrap().ignore_vanished);
+    }
+
+    #[test]
+    fn bwlimit_parsed() {
+        let config = Config::parse("remote = \"u@h\"\nbwlimit = \"2m\"").unwrap();
+        assert_eq!(config.bwlimit.as_deref(), Some("2m"));
+    }
+
+    #[test]
+    fn bwlimit_accepts_plain_and_fractional_rates() {
+        for rate in ["500", "1.5M", "2k", "2MiB", "100KB"] {
+            assert!(validate_bwlimit(rate).is_ok(), "{rate} should be valid");
+        }
+    }
+
+    #[test]
+    fn bwlimit_rejects_garbage() {
+        for rate in ["", "fast", "2 m", "2mbit", "-1"] {
+            assert!(
+                validate_bwlimit(rate).is_err(),
+                "{rate:?} should be invalid"
+            );
+        }
+        let err = Config::parse("remote = \"u@h\"\nbwlimit = \"fast\"").unwrap_err();
+        assert!(err.to_string().contains("bwlimit"));
     }
 
     // --- load_optional_config tests ---
@@ -267,7 +1195,13 @@ another = "value"
         )
         .unwrap();
 
-        let config = load_merged_config(home.path(), repo.path()).unwrap();
+        let config = load_merged_config(
+            &MockRunner::new(),
+            home.path(),
+            repo.path(),
+            PartialConfig::default(),
+        )
+        .unwrap();
         assert_eq!(config.remote, "user@project");
         // Project didn't specify exclude, so user's value is used
         assert_eq!(config.exclude, vec![".env"]);
@@ -282,7 +1216,13 @@ another = "value"
         fs::create_dir(&user_dir).unwrap();
         fs::write(user_dir.join("config.toml"), "remote = \"u@h\"").unwrap();
 
-        let config = load_merged_config(home.path(), repo.path()).unwrap();
+        let config = load_merged_config(
+            &MockRunner::new(),
+            home.path(),
+            repo.path(),
+            PartialConfig::default(),
+        )
+        .unwrap();
         assert_eq!(config.remote, "u@h");
     }
 
@@ -293,16 +1233,431 @@ another = "value"
 
         fs::write(repo.path().join("relocal.toml"), "remote = \"u@h\"").unwrap();
 
-        let config = load_merged_config(home.path(), repo.path()).unwrap();
+        let config = load_merged_config(
+            &MockRunner::new(),
+            home.path(),
+            repo.path(),
+            PartialConfig::default(),
+        )
+        .unwrap();
         assert_eq!(config.remote, "u@h");
     }
 
+    #[test]
+    fn merged_from_explicit_project_config() {
+        let home = TempDir::new().unwrap();
+        let elsewhere = TempDir::new().unwrap();
+        let path = elsewhere.path().join("api.toml");
+        fs::write(&path, "remote = \"u@explicit\"").unwrap();
+
+        let config = load_merged_config_from(
+            &MockRunner::new(),
+            home.path(),
+            &path,
+            None,
+            PartialConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(config.remote, "u@explicit");
+    }
+
+    #[test]
+    fn session_config_layers_over_project() {
+        let home = TempDir::new().unwrap();
+        let repo = TempDir::new().unwrap();
+        fs::write(
+            repo.path().join("relocal.toml"),
+            "remote = \"u@proj\"\nexclude = [\".env\"]\nsync_interval = 10",
+        )
+        .unwrap();
+        let session_path = session_config_path(repo.path(), "s1");
+        assert_eq!(session_path, repo.path().join("relocal.s1.toml"));
+        fs::write(&session_path, "exclude = [\"data/\"]").unwrap();
+
+        let config = load_merged_config_from(
+            &MockRunner::new(),
+            home.path(),
+            &repo.path().join("relocal.toml"),
+            Some(&session_path),
+            PartialConfig::default(),
+        )
+        .unwrap();
+        // Lists are replaced, like every other layer; unset fields fall through.
+        assert_eq!(config.exclude, vec!["data/"]);
+        assert_eq!(config.remote, "u@proj");
+        assert_eq!(config.sync_interval, 10);
+    }
+
+    #[test]
+    fn flags_override_session_config() {
+        let home = TempDir::new().unwrap();
+        let repo = TempDir::new().unwrap();
+        fs::write(repo.path().join("relocal.toml"), "remote = \"u@proj\"").unwrap();
+        let session_path = session_config_path(repo.path(), "s1");
+        fs::write(&session_path, "remote = \"u@session\"").unwrap();
+
+        let with_session = |overrides| {
+            load_merged_config_from(
+                &MockRunner::new(),
+                home.path(),
+                &repo.path().join("relocal.toml"),
+                Some(&session_path),
+                overrides,
+            )
+            .unwrap()
+        };
+        assert_eq!(with_session(PartialConfig::default()).remote, "u@session");
+        let overrides = PartialConfig {
+            remote: Some("u@flag".into()),
+            ..Default::default()
+        };
+        assert_eq!(with_session(overrides).remote, "u@flag");
+    }
+
+    #[test]
+    fn missing_session_config_is_skipped() {
+        let home = TempDir::new().unwrap();
+        let repo = TempDir::new().unwrap();
+        fs::write(repo.path().join("relocal.toml"), "remote = \"u@proj\"").unwrap();
+
+        let config = load_merged_config_from(
+            &MockRunner::new(),
+            home.path(),
+            &repo.path().join("relocal.toml"),
+            Some(&session_config_path(repo.path(), "s1")),
+            PartialConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(config.remote, "u@proj");
+    }
+
     #[test]
     fn merged_neither_has_remote() {
         let home = TempDir::new().unwrap();
         let repo = TempDir::new().unwrap();
 
-        let err = load_merged_config(home.path(), repo.path()).unwrap_err();
+        let err = load_merged_config(
+            &MockRunner::new(),
+            home.path(),
+            repo.path(),
+            PartialConfig::default(),
+        )
+        .unwrap_err();
         assert!(err.to_string().contains("remote"));
     }
+
+    #[test]
+    fn merged_decrypts_project_remote_over_user_plaintext() {
+        let home = TempDir::new().unwrap();
+        let repo = TempDir::new().unwrap();
+
+        let user_dir = home.path().join(".relocal");
+        fs::create_dir(&user_dir).unwrap();
+        fs::write(user_dir.join("config.toml"), "remote = \"user@default\"").unwrap();
+        fs::write(
+            repo.path().join("relocal.toml"),
+            "remote_encrypted = \"CIPHER\"",
+        )
+        .unwrap();
+
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok("user@secret\n".into()));
+        let config =
+            load_merged_config(&mock, home.path(), repo.path(), PartialConfig::default()).unwrap();
+        assert_eq!(config.remote, "user@secret");
+    }
+
+    #[test]
+    fn merged_overrides_win_over_both_files() {
+        let home = TempDir::new().unwrap();
+        let repo = TempDir::new().unwrap();
+        fs::write(
+            repo.path().join("relocal.toml"),
+            "remote_encrypted = \"CIPHER\"\nexclude = [\".env\"]",
+        )
+        .unwrap();
+
+        let overrides = PartialConfig {
+            remote: Some("me@vm".into()),
+            ..Default::default()
+        };
+        let mock = MockRunner::new();
+        let config = load_merged_config(&mock, home.path(), repo.path(), overrides).unwrap();
+        assert_eq!(config.remote, "me@vm");
+        assert_eq!(config.exclude, vec![".env"]);
+        // The override replaces the encrypted remote, so nothing is decrypted.
+        assert!(mock.invocations().is_empty());
+    }
+
+    #[test]
+    fn merged_override_supplies_missing_remote() {
+        let home = TempDir::new().unwrap();
+        let repo = TempDir::new().unwrap();
+        let overrides = PartialConfig {
+            remote: Some("me@vm".into()),
+            ..Default::default()
+        };
+        let config =
+            load_merged_config(&MockRunner::new(), home.path(), repo.path(), overrides).unwrap();
+        assert_eq!(config.remote, "me@vm");
+    }
+
+    #[test]
+    fn validate_remote_accepts_user_at_host_and_alias() {
+        for remote in ["user@host", "me@10.0.0.5", "ci@vm.example.com", "devbox"] {
+            validate_remote(remote).unwrap();
+        }
+    }
+
+    #[test]
+    fn validate_remote_rejects_implausible() {
+        for remote in [
+            "",
+            "-oProxyCommand=x",
+            "@host",
+            "user@",
+            "-oProxyCommand=x@h",
+            "a@b@c",
+            "u@h ost",
+            "u@h/x",
+        ] {
+            let err = validate_remote(remote).unwrap_err();
+            assert!(err.to_string().contains("user@host"), "{err}");
+        }
+    }
+
+    fn fake_env(name: &str) -> Option<String> {
+        match name {
+            "HOME" => Some("/home/me".to_string()),
+            "PROJ" => Some("work".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn expand_path_tilde_and_vars() {
+        assert_eq!(expand_path("~", fake_env).unwrap(), "/home/me");
+        assert_eq!(
+            expand_path("~/.relocalignore", fake_env).unwrap(),
+            "/home/me/.relocalignore"
+        );
+        assert_eq!(
+            expand_path("$HOME/${PROJ}_ignore/$PROJ", fake_env).unwrap(),
+            "/home/me/work_ignore/work"
+        );
+        assert_eq!(
+            expand_path(".relocalignore", fake_env).unwrap(),
+            ".relocalignore"
+        );
+    }
+
+    #[test]
+    fn expand_path_keeps_tilde_elsewhere_and_lone_dollar() {
+        assert_eq!(expand_path("a/~b", fake_env).unwrap(), "a/~b");
+        assert_eq!(expand_path("~other/x", fake_env).unwrap(), "~other/x");
+        assert_eq!(expand_path("cost$", fake_env).unwrap(), "cost$");
+        assert_eq!(expand_path("a$/b", fake_env).unwrap(), "a$/b");
+    }
+
+    #[test]
+    fn expand_path_unset_variable_is_error() {
+        let err = expand_path("$NOPE/x", fake_env).unwrap_err();
+        assert!(err.contains("$NOPE"), "{err}");
+        assert!(expand_path("${PROJ", fake_env).is_err());
+    }
+
+    #[test]
+    fn exclude_from_kept_raw_and_expanded_on_use() {
+        let config = Config::parse("remote = \"u@h\"\nexclude_from = \"~/ignore.txt\"").unwrap();
+        assert_eq!(config.exclude_from.as_deref(), Some("~/ignore.txt"));
+        let home = std::env::var("HOME").unwrap();
+        assert_eq!(
+            config.exclude_from_path(Path::new("/repo")),
+            Some(Path::new(&home).join("ignore.txt"))
+        );
+        let relative = Config::parse("remote = \"u@h\"\nexclude_from = \"skip.txt\"").unwrap();
+        assert_eq!(
+            relative.exclude_from_path(Path::new("/repo")),
+            Some(PathBuf::from("/repo/skip.txt"))
+        );
+    }
+
+    #[test]
+    fn exclude_from_unset_variable_fails_resolve() {
+        let err =
+            Config::parse("remote = \"u@h\"\nexclude_from = \"$RELOCAL_TEST_SURELY_UNSET/x\"")
+                .unwrap_err();
+        assert!(err.to_string().contains("exclude_from"), "{err}");
+    }
+
+    #[test]
+    fn pull_fsck_can_be_disabled() {
+        let config = Config::parse("remote = \"u@h\"\npull_fsck = false").unwrap();
+        assert!(!config.pull_fsck);
+    }
+
+    #[test]
+    fn verbose_rsync_flags_default_to_progress() {
+        let config = Config::parse("remote = \"u@h\"").unwrap();
+        assert_eq!(config.verbose_rsync_flags, vec!["--progress"]);
+    }
+
+    #[test]
+    fn verbose_rsync_flags_parsed() {
+        let config = Config::parse(
+            "remote = \"u@h\"\nverbose_rsync_flags = [\"--itemize-changes\", \"--progress\"]",
+        )
+        .unwrap();
+        assert_eq!(
+            config.verbose_rsync_flags,
+            vec!["--itemize-changes", "--progress"]
+        );
+    }
+
+    #[test]
+    fn verbose_rsync_flags_must_be_long_options() {
+        let err = Config::parse("remote = \"u@h\"\nverbose_rsync_flags = [\"-i\"]").unwrap_err();
+        assert!(err.to_string().contains("verbose_rsync_flags"), "{err}");
+    }
+
+    #[test]
+    fn bare_host_alias_accepted() {
+        let config = Config::parse("remote = \"devbox\"").unwrap();
+        assert_eq!(config.remote, "devbox");
+    }
+
+    #[test]
+    fn obviously_invalid_remote_rejected() {
+        for remote in ["", "dev box", "-oProxyCommand=x"] {
+            let err = Config::parse(&format!("remote = {remote:?}")).unwrap_err();
+            assert!(err.to_string().contains("`remote`"), "{err}");
+        }
+    }
+
+    #[test]
+    fn problems_empty_for_valid_config() {
+        let repo = TempDir::new().unwrap();
+        fs::write(repo.path().join(".relocalignore"), "*.log\n").unwrap();
+        let config = Config::parse(
+            "remote = \"devbox\"\nexclude = [\".env\"]\nexclude_from = \".relocalignore\"",
+        )
+        .unwrap();
+        assert!(config.problems(repo.path()).is_empty());
+    }
+
+    #[test]
+    fn problems_lists_every_issue() {
+        let repo = TempDir::new().unwrap();
+        let config = Config::parse(
+            r#"
+remote = "u@h/x"
+allowed_remotes = ["u@h", "a@b@c"]
+exclude = [".env", " "]
+always_include = [""]
+exclude_from = "missing.txt"
+"#,
+        )
+        .unwrap();
+        let problems = config.problems(repo.path());
+        assert_eq!(problems.len(), 5, "{problems:#?}");
+        assert!(problems[0].contains("`remote`"));
+        assert!(problems[1].contains("a@b@c"));
+        assert!(problems[2].contains("`exclude`"));
+        assert!(problems[3].contains("`always_include`"));
+        assert!(problems[4].contains("missing.txt"));
+    }
+
+    // --- decrypt_remote tests ---
+
+    fn encrypted(command: Option<&str>) -> PartialConfig {
+        PartialConfig {
+            remote_encrypted: Some("-----BEGIN PGP MESSAGE-----\nabc\n".into()),
+            remote_decrypt_command: command.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn decrypt_remote_uses_command_output() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok("user@host\n".into()));
+
+        let partial = encrypted(Some("age -d -i key.txt"))
+            .decrypt_remote(&mock)
+            .unwrap();
+        assert_eq!(partial.remote.as_deref(), Some("user@host"));
+        assert!(partial.remote_encrypted.is_none());
+
+        let invocations = mock.invocations();
+        assert_eq!(invocations.len(), 1);
+        let Invocation::Local { program, args } = &invocations[0] else {
+            panic!("expected local invocation, got {:?}", invocations[0]);
+        };
+        assert_eq!(program, "bash");
+        assert!(args[1].contains("BEGIN PGP MESSAGE"), "{}", args[1]);
+        assert!(args[1].ends_with("| age -d -i key.txt"), "{}", args[1]);
+    }
+
+    #[test]
+    fn decrypt_remote_defaults_to_gpg() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok("user@host\n".into()));
+
+        encrypted(None).decrypt_remote(&mock).unwrap();
+        let Invocation::Local { args, .. } = &mock.invocations()[0] else {
+            panic!("expected local invocation");
+        };
+        assert!(args[1].ends_with(DEFAULT_DECRYPT_COMMAND), "{}", args[1]);
+    }
+
+    #[test]
+    fn decrypt_remote_plaintext_wins() {
+        let mock = MockRunner::new();
+        let partial = PartialConfig {
+            remote: Some("plain@host".into()),
+            ..encrypted(None)
+        }
+        .decrypt_remote(&mock)
+        .unwrap();
+        assert_eq!(partial.remote.as_deref(), Some("plain@host"));
+        assert!(mock.invocations().is_empty());
+    }
+
+    #[test]
+    fn decrypt_remote_without_ciphertext_runs_nothing() {
+        let mock = MockRunner::new();
+        let partial = PartialConfig::default().decrypt_remote(&mock).unwrap();
+        assert!(partial.remote.is_none());
+        assert!(mock.invocations().is_empty());
+    }
+
+    #[test]
+    fn decrypt_remote_failure_is_error() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Fail(
+            "gpg: decryption failed: No secret key".into(),
+        ));
+        let err = encrypted(None).decrypt_remote(&mock).unwrap_err();
+        assert!(err.to_string().contains("No secret key"), "{err}");
+    }
+
+    #[test]
+    fn decrypt_remote_empty_output_is_error() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok("\n".into()));
+        let err = encrypted(None).decrypt_remote(&mock).unwrap_err();
+        assert!(err.to_string().contains("empty"), "{err}");
+    }
+
+    #[test]
+    fn merge_remote_encrypted_replaces_plaintext_remote() {
+        let base = PartialConfig {
+            remote: Some("base@host".into()),
+            ..Default::default()
+        };
+        let merged = base.merge(encrypted(Some("cat")));
+        assert!(merged.remote.is_none());
+        assert!(merged.remote_encrypted.is_some());
+        assert_eq!(merged.remote_decrypt_command.as_deref(), Some("cat"));
+    }
 }
@@ -14,25 +14,82 @@ use std::time::Duration;
 use nix::poll::{poll, PollFd, PollFlags, PollTimeout};
 use tracing::{debug, info, warn};
 
-use crate::commands::sync::{sync_pull, sync_push};
+use crate::commands::sync::{sync_pull, sync_push, SyncStats};
 use crate::config::Config;
 use crate::error::{Error, Result};
-use crate::runner::ProcessRunner;
+use crate::remote_meta;
+use crate::rsync::{Direction, SyncOptions};
+use crate::runner::{CommandRunner, ProcessRunner};
 use crate::ssh::{self, SshControlMaster};
 
-const SYNC_INTERVAL: Duration = Duration::from_secs(3);
-
 /// How long the daemon waits for the first client before giving up. If the
 /// spawning process dies between READY and connect, this prevents the daemon
 /// from running forever with zero clients.
 const INITIAL_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// Minimum time between refreshes of the remote lock file's mtime. The
+/// refresh rides on the background pull, so the effective period is the
+/// larger of this and `sync_interval`.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Number of missed heartbeats after which a lock is considered abandoned.
+const HEARTBEAT_MISSES: u64 = 3;
+
+/// Seconds between this daemon's lock refreshes, recorded in the lock file so
+/// other machines judge its liveness by the owner's period, not their own.
+fn heartbeat_secs(config: &Config) -> u64 {
+    HEARTBEAT_INTERVAL.as_secs().max(config.sync_interval)
+}
+
+/// Reads [`ssh::lock_file_age`] output and returns the lock's age if it is
+/// fresh enough to belong to a live session.
+///
+/// Locks from older versions record no heartbeat period; for those, this
+/// machine's own period is the best guess.
+fn live_lock_age(stdout: &str, config: &Config) -> Option<u64> {
+    let mut lines = stdout.lines().map(str::trim);
+    let age_secs: u64 = lines.next()?.parse().ok()?;
+    let owner_heartbeat = lines
+        .next()
+        .and_then(|line| line.parse::<u64>().ok())
+        .unwrap_or_else(|| heartbeat_secs(config));
+    (age_secs < HEARTBEAT_MISSES * owner_heartbeat).then_some(age_secs)
+}
+
+/// Session-start choices the spawning client passes to the daemon as
+/// `_daemon` flags. They are fixed for the daemon's lifetime, so a client
+/// joining a running session cannot change them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DaemonOptions {
+    /// Remove the remote working copy at shutdown, after a successful final
+    /// pull (`--ephemeral`).
+    pub ephemeral: bool,
+    /// Skip the initial push if the remote working copy already exists
+    /// (`--no-initial-push`).
+    pub no_initial_push: bool,
+}
+
+impl DaemonOptions {
+    /// The `_daemon` flags that reproduce these options.
+    pub fn args(&self) -> Vec<&'static str> {
+        let mut args = Vec::new();
+        if self.ephemeral {
+            args.push("--ephemeral");
+        }
+        if self.no_initial_push {
+            args.push("--no-initial-push");
+        }
+        args
+    }
+}
+
 /// Runs the session daemon. Called via the hidden `_daemon` subcommand.
 ///
 /// Performs setup (ControlMaster, remote dir, lock, push), signals readiness
 /// on stdout, then enters the poll loop until the last client disconnects.
 /// After the loop exits: final sync pull, lock removal, socket cleanup,
-/// ControlMaster teardown.
+/// ControlMaster teardown. See [`DaemonOptions`] for how `options` change
+/// setup and teardown.
 ///
 /// The startup flock is NOT acquired here — it is acquired at shutdown only.
 /// Acquiring it at startup would deadlock: the spawning client holds the
@@ -44,17 +101,25 @@ pub fn run_daemon(
     session_name: &str,
     repo_root: &Path,
     verbose: bool,
+    options: DaemonOptions,
 ) -> Result<()> {
     info!("Connecting to {}...", config.remote);
     debug!("Establishing SSH ControlMaster...");
-    let control_master = SshControlMaster::start_shared(&config.remote, session_name)?;
+    let control_master = SshControlMaster::start_shared(
+        &config.remote,
+        session_name,
+        config.strict_host_key,
+        config.control_persist,
+    )?;
     debug!(
         "ControlMaster established at {}",
         control_master.socket_path().display()
     );
-    let runner = ProcessRunner::with_control_path(control_master.socket_path());
+    let runner = ProcessRunner::with_control_path(control_master.socket_path())
+        .with_config(config)
+        .with_strict_host_key(config.strict_host_key);
 
-    daemon_setup(&runner, config, session_name, repo_root, verbose)?;
+    let pushed = daemon_setup(&runner, config, session_name, repo_root, verbose, options)?;
 
     let socket_path = ssh::daemon_socket_path(session_name, &config.remote);
     let _ = std::fs::remove_file(&socket_path);
@@ -85,7 +150,7 @@ pub fn run_daemon(
 
     let control_path_msg = format!("{}\n", control_master.socket_path().display());
 
-    let exit_result = poll_loop(
+    let loop_result = poll_loop(
         &listener,
         &runner,
         config,
@@ -94,6 +159,14 @@ pub fn run_daemon(
         verbose,
         &control_path_msg,
     );
+    let (mut stats, exit_result) = match loop_result {
+        Ok(stats) => (stats, Ok(())),
+        Err(e) => (SyncStats::default(), Err(e)),
+    };
+    // daemon_setup fails unless its initial push (if any) succeeded.
+    if pushed {
+        stats.record(Direction::Push, &Ok(()));
+    }
 
     // Stop accepting new connections.
     drop(listener);
@@ -123,11 +196,26 @@ pub fn run_daemon(
     // _shutdown_flock is held (not dropped) until run_daemon returns.
 
     info!("Pulling final changes from remote...");
-    if let Err(e) = sync_pull(&runner, config, session_name, repo_root, verbose) {
+    let sync_options = SyncOptions {
+        verbose,
+        itemize: true,
+        ..Default::default()
+    };
+    let final_pull = sync_pull(&runner, config, session_name, repo_root, &sync_options);
+    if let Err(e) = &final_pull {
         warn!("Final sync pull failed: {e}");
     }
-    if let Err(e) = cleanup(&runner, config, session_name) {
-        warn!("Lock file cleanup failed: {e}");
+    stats.record(Direction::Pull, &final_pull);
+    info!("Session sync summary: {}", stats.summary());
+    let remove_work_dir = options.ephemeral && final_pull.is_ok();
+    if options.ephemeral && !remove_work_dir {
+        warn!(
+            "Keeping {} of ephemeral session: the final pull failed. Run `relocal sync pull {session_name}`, then `relocal destroy {session_name}`.",
+            ssh::remote_work_dir(session_name)
+        );
+    }
+    if let Err(e) = cleanup(&runner, config, session_name, remove_work_dir) {
+        warn!("Session cleanup failed: {e}");
     }
     drop(control_master);
 
@@ -137,7 +225,12 @@ pub fn run_daemon(
     exit_result
 }
 
-/// Daemon-specific setup: stale session check, remote dir, lock, initial push.
+/// Daemon-specific setup: remote compatibility check, stale session check,
+/// remote dir, origin record, lock, initial push.
+///
+/// With `options.no_initial_push`, the push is skipped if the remote working
+/// copy already exists; a missing one is still pushed, since pulling from an
+/// empty directory would fail. Returns whether the push ran.
 ///
 /// Does NOT check tool installation — the daemon is tool-agnostic. Tool
 /// checks are the client's responsibility.
@@ -147,7 +240,11 @@ pub fn daemon_setup(
     session_name: &str,
     repo_root: &Path,
     verbose: bool,
-) -> Result<()> {
+    options: DaemonOptions,
+) -> Result<bool> {
+    info!("Checking remote compatibility...");
+    remote_meta::ensure_compatible(runner, &config.remote)?;
+
     info!("Checking for stale session...");
     let lock_exists = ssh::run_status_check(
         runner,
@@ -155,36 +252,74 @@ pub fn daemon_setup(
         &ssh::check_lock_file_exists(session_name),
     )?;
     if lock_exists {
+        // A live daemon keeps the lock's mtime fresh; a crashed one doesn't.
+        let output = runner.run_ssh(&config.remote, &ssh::lock_file_age(session_name))?;
+        let live_age = output
+            .status
+            .success()
+            .then(|| live_lock_age(&output.stdout, config))
+            .flatten();
+        if let Some(age_secs) = live_age {
+            return Err(Error::SessionAlreadyRunning {
+                session: session_name.to_string(),
+                age_secs,
+            });
+        }
         return Err(Error::StaleSession {
             session: session_name.to_string(),
         });
     }
     debug!("No stale session found");
 
-    info!("Creating remote working directory...");
-    runner
-        .run_ssh(&config.remote, &ssh::mkdir_work_dir(session_name))?
-        .check("mkdir")?;
-    debug!("Remote directory created");
+    let push = !options.no_initial_push
+        || !ssh::run_status_check(
+            runner,
+            &config.remote,
+            &ssh::check_work_dir_exists(session_name),
+        )?;
+    if options.no_initial_push && push {
+        warn!("--no-initial-push ignored: the remote working copy does not exist yet");
+    }
 
+    info!("Setting up remote session...");
+    let origin = remote_meta::origin_json(&config.remote, repo_root);
     runner
-        .run_ssh(&config.remote, &ssh::create_lock_file(session_name))?
-        .check("create lock file")?;
-    debug!("Lock file created");
-
+        .run_ssh(
+            &config.remote,
+            &ssh::setup_session(session_name, &origin, heartbeat_secs(config)),
+        )?
+        .check("session setup")?;
+    debug!("Remote directory created, origin recorded, lock file created");
+
+    if !push {
+        info!("Skipping initial push (--no-initial-push).");
+        return Ok(false);
+    }
     debug!("Starting initial rsync push...");
-    sync_push(runner, config, session_name, repo_root, verbose)?;
+    let options = SyncOptions {
+        verbose,
+        ..Default::default()
+    };
+    sync_push(runner, config, session_name, repo_root, &options)?;
     debug!("Initial rsync push complete");
 
-    Ok(())
+    Ok(true)
 }
 
-/// Post-session cleanup: remove lock file (best-effort).
+/// Post-session cleanup: remove the working copy when `remove_work_dir` is
+/// set (ephemeral sessions), then the lock file (best-effort).
 fn cleanup(
     runner: &dyn crate::runner::CommandRunner,
     config: &Config,
     session_name: &str,
+    remove_work_dir: bool,
 ) -> Result<()> {
+    if remove_work_dir {
+        info!("Removing remote working directory (ephemeral session)...");
+        runner
+            .run_ssh(&config.remote, &ssh::rm_work_dir(session_name))?
+            .check("rm work dir")?;
+    }
     info!("Removing lock file...");
     runner
         .run_ssh(&config.remote, &ssh::remove_lock_file(session_name))?
@@ -192,7 +327,10 @@ fn cleanup(
     Ok(())
 }
 
-/// Main event loop: accept clients, detect disconnects, sync on timeout.
+/// Main event loop: accept clients, detect disconnects, and pull after every
+/// `sync_interval` seconds without client activity. Returns the background
+/// pulls it performed once the last client has disconnected and no detached
+/// session is running.
 fn poll_loop(
     listener: &UnixListener,
     runner: &ProcessRunner,
@@ -201,10 +339,18 @@ fn poll_loop(
     repo_root: &Path,
     verbose: bool,
     control_path_msg: &str,
-) -> Result<()> {
+) -> Result<SyncStats> {
+    let options = SyncOptions {
+        verbose,
+        background: true,
+        ..Default::default()
+    };
+    let mut stats = SyncStats::default();
     let mut clients: Vec<UnixStream> = Vec::new();
     let mut ever_had_client = false;
+    let mut detached = false;
     let started = std::time::Instant::now();
+    let mut last_heartbeat = started;
 
     loop {
         // If no client has ever connected and the timeout has elapsed, the
@@ -215,7 +361,7 @@ fn poll_loop(
                 "No client connected within {:?}, shutting down",
                 INITIAL_CONNECT_TIMEOUT
             );
-            return Ok(());
+            return Ok(stats);
         }
 
         // Collect raw fds for polling. We can't hold BorrowedFds across
@@ -236,14 +382,10 @@ fn poll_loop(
             ));
         }
 
-        let timeout_ms: u16 = SYNC_INTERVAL
-            .as_millis()
-            .try_into()
-            .expect("SYNC_INTERVAL must fit in u16 milliseconds");
-        let n = poll(&mut poll_fds, PollTimeout::from(timeout_ms)).map_err(|e| {
-            Error::DaemonSpawnFailed {
-                message: format!("poll error: {e}"),
-            }
+        let timeout = PollTimeout::try_from(Duration::from_secs(config.sync_interval))
+            .expect("sync_interval is validated to fit a poll timeout");
+        let n = poll(&mut poll_fds, timeout).map_err(|e| Error::DaemonSpawnFailed {
+            message: format!("poll error: {e}"),
         })?;
 
         // Snapshot revents before dropping poll_fds (which hold BorrowedFds).
@@ -254,10 +396,31 @@ fn poll_loop(
 
         if n == 0 {
             // Timeout — run sync.
-            if !clients.is_empty() {
-                if let Err(e) = sync_pull(runner, config, session_name, repo_root, verbose) {
+            if detached
+                && clients.is_empty()
+                && !detached_session_running(runner, config, session_name, detached)
+            {
+                info!("Detached session ended");
+                return Ok(stats);
+            }
+            if !clients.is_empty() || detached {
+                let result = sync_pull(runner, config, session_name, repo_root, &options);
+                if let Err(e) = &result {
                     warn!("background sync failed: {e}");
                 }
+                stats.record(Direction::Pull, &result);
+            }
+            if last_heartbeat.elapsed() >= HEARTBEAT_INTERVAL {
+                // Best-effort, but missed heartbeats let a second machine
+                // take this session for a crashed one.
+                match runner.run_ssh(&config.remote, &ssh::touch_lock_file(session_name)) {
+                    Ok(output) if !output.status.success() => {
+                        warn!("lock heartbeat failed: {}", output.stderr.trim());
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("lock heartbeat failed: {e}"),
+                }
+                last_heartbeat = std::time::Instant::now();
             }
             continue;
         }
@@ -315,11 +478,40 @@ fn poll_loop(
             }
         }
         if clients.is_empty() {
-            return Ok(());
+            // A detached session keeps running without clients; cleanup
+            // waits until it ends.
+            if !detached_session_running(runner, config, session_name, detached) {
+                return Ok(stats);
+            }
+            if !detached {
+                info!("Detached session still running, deferring cleanup until it ends");
+                detached = true;
+            }
         }
     }
 }
 
+/// Whether the session's detached tmux session (`relocal claude --detach`)
+/// is still running, or `last_known` if the probe fails. Once a detached
+/// session has been seen, a network blip therefore doesn't tear the session
+/// down under a live agent; the next background tick probes again.
+fn detached_session_running(
+    runner: &dyn CommandRunner,
+    config: &Config,
+    session_name: &str,
+    last_known: bool,
+) -> bool {
+    ssh::run_status_check(
+        runner,
+        &config.remote,
+        &ssh::check_detached_session(session_name),
+    )
+    .unwrap_or_else(|e| {
+        debug!("detached session check failed: {e}");
+        last_known
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -336,44 +528,59 @@ mod tests {
         PathBuf::from("/home/user/my-project")
     }
 
+    fn no_initial_push() -> DaemonOptions {
+        DaemonOptions {
+            no_initial_push: true,
+            ..Default::default()
+        }
+    }
+
     #[test]
     fn daemon_setup_full_sequence() {
         let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // remote meta (absent)
         mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into())); // lock check
-        mock.add_response(MockResponse::Ok(String::new())); // mkdir
-        mock.add_response(MockResponse::Ok(String::new())); // lock create
+        mock.add_response(MockResponse::Ok(String::new())); // setup
         mock.add_response(MockResponse::Ok(String::new())); // rsync push
 
-        daemon_setup(&mock, &test_config(), "my-session", &repo_root(), false).unwrap();
+        daemon_setup(
+            &mock,
+            &test_config(),
+            "my-session",
+            &repo_root(),
+            false,
+            DaemonOptions::default(),
+        )
+        .unwrap();
 
         let inv = mock.invocations();
         assert_eq!(inv.len(), 4);
 
-        // lock check (wrapped)
+        // remote metadata read
         match &inv[0] {
-            Invocation::Ssh { command, .. } => {
-                assert!(command.contains("test -e"));
-                assert!(command.contains(".locks"));
-            }
-            _ => panic!("expected Ssh for lock check"),
+            Invocation::Ssh { command, .. } => assert!(command.contains(".relocal-meta.json")),
+            _ => panic!("expected Ssh for metadata read"),
         }
 
-        // mkdir work dir
+        // lock check (wrapped)
         match &inv[1] {
             Invocation::Ssh { command, .. } => {
-                assert!(command.contains("mkdir -p"));
-                assert!(command.contains("my-session"));
+                assert!(command.contains("test -e"));
+                assert!(command.contains(".locks"));
             }
-            _ => panic!("expected Ssh for mkdir"),
+            _ => panic!("expected Ssh for lock check"),
         }
 
-        // lock file creation
+        // mkdir, origin record and lock file creation in one command
         match &inv[2] {
             Invocation::Ssh { command, .. } => {
+                assert!(command.contains("mkdir -p ~/relocal/my-session &&"));
+                assert!(command.contains(".relocal/origin.json"));
+                assert!(command.contains("/home/user/my-project"));
                 assert!(command.contains("noclobber"));
                 assert!(command.contains(".locks"));
             }
-            _ => panic!("expected Ssh for lock creation"),
+            _ => panic!("expected Ssh for session setup"),
         }
 
         // rsync (push)
@@ -383,12 +590,20 @@ mod tests {
     #[test]
     fn daemon_setup_no_tool_check() {
         let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // remote meta (absent)
         mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into())); // lock check
-        mock.add_response(MockResponse::Ok(String::new())); // mkdir
-        mock.add_response(MockResponse::Ok(String::new())); // lock create
+        mock.add_response(MockResponse::Ok(String::new())); // setup
         mock.add_response(MockResponse::Ok(String::new())); // rsync push
 
-        daemon_setup(&mock, &test_config(), "s1", &repo_root(), false).unwrap();
+        daemon_setup(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            false,
+            DaemonOptions::default(),
+        )
+        .unwrap();
 
         // Should be 4 invocations — no tool check (that's the client's job).
         let inv = mock.invocations();
@@ -405,37 +620,290 @@ mod tests {
     #[test]
     fn daemon_setup_stale_session_detected() {
         let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // remote meta (absent)
         mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // lock exists
+        mock.add_response(MockResponse::Ok("86400\n".into())); // lock age
 
-        let result = daemon_setup(&mock, &test_config(), "stale-session", &repo_root(), false);
+        let result = daemon_setup(
+            &mock,
+            &test_config(),
+            "stale-session",
+            &repo_root(),
+            false,
+            DaemonOptions::default(),
+        );
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), Error::StaleSession { .. }));
-        assert_eq!(mock.invocations().len(), 1);
+        assert_eq!(mock.invocations().len(), 3);
+    }
+
+    #[test]
+    fn daemon_setup_fresh_lock_means_already_running() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // remote meta (absent)
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // lock exists
+        mock.add_response(MockResponse::Ok("12\n".into())); // lock age
+
+        let err = daemon_setup(
+            &mock,
+            &test_config(),
+            "busy",
+            &repo_root(),
+            false,
+            DaemonOptions::default(),
+        )
+        .unwrap_err();
+
+        assert!(
+            matches!(err, Error::SessionAlreadyRunning { ref session, age_secs: 12 } if session == "busy"),
+            "{err}"
+        );
+        assert_eq!(mock.invocations().len(), 3);
+    }
+
+    #[test]
+    fn daemon_setup_unreadable_lock_age_is_stale() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // remote meta (absent)
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // lock exists
+        mock.add_response(MockResponse::Fail("stat: cannot stat".into())); // lock age
+
+        let err = daemon_setup(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            false,
+            DaemonOptions::default(),
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, Error::StaleSession { .. }), "{err}");
+    }
+
+    #[test]
+    fn live_lock_age_uses_the_owners_heartbeat() {
+        let slow = Config::parse("remote = \"u@h\"\nsync_interval = 600").unwrap();
+        // The owner heartbeats every 600s: 1000s is only one missed beat,
+        // whatever this machine's sync_interval.
+        assert_eq!(live_lock_age("1000\n600\n", &test_config()), Some(1000));
+        // The owner heartbeats every 30s: 100s is three missed beats.
+        assert_eq!(live_lock_age("100\n30\n", &slow), None);
+        assert_eq!(live_lock_age("12\n30\n", &slow), Some(12));
+    }
+
+    #[test]
+    fn live_lock_age_without_recorded_heartbeat_uses_own() {
+        assert_eq!(live_lock_age("60\n", &test_config()), Some(60));
+        assert_eq!(live_lock_age("90\n", &test_config()), None);
+        let slow = Config::parse("remote = \"u@h\"\nsync_interval = 600").unwrap();
+        assert_eq!(live_lock_age("1000\n\n", &slow), Some(1000));
+        assert_eq!(live_lock_age("garbage\n", &test_config()), None);
+    }
+
+    #[test]
+    fn detached_session_defers_cleanup_while_running() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into()));
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into()));
+
+        assert!(detached_session_running(&mock, &test_config(), "s1", false));
+        assert!(!detached_session_running(&mock, &test_config(), "s1", true));
+        match &mock.invocations()[0] {
+            Invocation::Ssh { command, .. } => {
+                assert!(command.contains("tmux has-session -t =relocal-s1"));
+            }
+            _ => panic!("expected Ssh"),
+        }
+    }
+
+    #[test]
+    fn failed_detached_session_probe_keeps_last_known_state() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Err("connection reset".into()));
+        mock.add_response(MockResponse::Err("connection reset".into()));
+
+        assert!(detached_session_running(&mock, &test_config(), "s1", true));
+        assert!(!detached_session_running(
+            &mock,
+            &test_config(),
+            "s1",
+            false
+        ));
     }
 
     #[test]
     fn daemon_setup_fails_if_mkdir_fails() {
         let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // remote meta (absent)
         mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into())); // lock check
-        mock.add_response(MockResponse::Fail("permission denied".into())); // mkdir fails
+        mock.add_response(MockResponse::Fail("permission denied".into())); // setup fails
 
-        let result = daemon_setup(&mock, &test_config(), "s1", &repo_root(), false);
+        let result = daemon_setup(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            false,
+            DaemonOptions::default(),
+        );
         assert!(result.is_err());
-        assert_eq!(mock.invocations().len(), 2);
+        assert_eq!(mock.invocations().len(), 3);
     }
 
     #[test]
     fn daemon_setup_fails_if_lock_creation_fails() {
         let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // remote meta (absent)
         mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into())); // lock check
-        mock.add_response(MockResponse::Ok(String::new())); // mkdir
-        mock.add_response(MockResponse::Fail("noclobber: file exists".into())); // lock fails
+        mock.add_response(MockResponse::Fail("noclobber: file exists".into())); // setup fails
 
-        let result = daemon_setup(&mock, &test_config(), "s1", &repo_root(), false);
-        assert!(result.is_err());
+        let err = daemon_setup(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            false,
+            DaemonOptions::default(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("noclobber"), "{err}");
+        // No push after a failed setup.
         assert_eq!(mock.invocations().len(), 3);
     }
 
+    #[test]
+    fn daemon_setup_refuses_newer_remote_protocol() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(format!(
+            "{{\"installed_version\":\"99.0.0\",\"protocol_version\":{}}}",
+            remote_meta::PROTOCOL_VERSION + 1
+        )));
+
+        let result = daemon_setup(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            false,
+            DaemonOptions::default(),
+        );
+        assert!(matches!(result, Err(Error::IncompatibleRemote { .. })));
+        // Nothing touched on the remote after the metadata read.
+        assert_eq!(mock.invocations().len(), 1);
+    }
+
+    #[test]
+    fn daemon_setup_no_initial_push_skips_rsync_for_existing_dir() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // remote meta (absent)
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into())); // lock check
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_TRUE.into())); // work dir exists
+        mock.add_response(MockResponse::Ok(String::new())); // setup
+
+        let pushed = daemon_setup(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            false,
+            no_initial_push(),
+        )
+        .unwrap();
+
+        assert!(!pushed);
+        let inv = mock.invocations();
+        assert_eq!(inv.len(), 4);
+        assert!(!inv.iter().any(|i| matches!(i, Invocation::Rsync { .. })));
+        // The working directory, origin record and lock are still set up.
+        match &inv[3] {
+            Invocation::Ssh { command, .. } => assert!(command.contains("noclobber")),
+            _ => panic!("expected Ssh for lock creation"),
+        }
+    }
+
+    #[test]
+    fn daemon_setup_no_initial_push_still_pushes_missing_dir() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // remote meta (absent)
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into())); // lock check
+        mock.add_response(MockResponse::Ok(STATUS_CHECK_FALSE.into())); // no work dir
+        mock.add_response(MockResponse::Ok(String::new())); // setup
+        mock.add_response(MockResponse::Ok(String::new())); // rsync push
+
+        let pushed = daemon_setup(
+            &mock,
+            &test_config(),
+            "s1",
+            &repo_root(),
+            false,
+            no_initial_push(),
+        )
+        .unwrap();
+
+        assert!(pushed);
+        let inv = mock.invocations();
+        assert_eq!(inv.len(), 5);
+        assert!(matches!(&inv[4], Invocation::Rsync { .. }));
+    }
+
+    #[test]
+    fn daemon_options_args() {
+        assert!(DaemonOptions::default().args().is_empty());
+        let options = DaemonOptions {
+            ephemeral: true,
+            no_initial_push: true,
+        };
+        assert_eq!(options.args(), ["--ephemeral", "--no-initial-push"]);
+    }
+
+    #[test]
+    fn cleanup_removes_only_lock_by_default() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // rm lock
+
+        cleanup(&mock, &test_config(), "s1", false).unwrap();
+
+        let inv = mock.invocations();
+        assert_eq!(inv.len(), 1);
+        match &inv[0] {
+            Invocation::Ssh { command, .. } => {
+                assert!(command.contains(".locks/s1.lock"));
+                assert!(!command.contains("rm -rf"));
+            }
+            _ => panic!("expected Ssh for lock removal"),
+        }
+    }
+
+    #[test]
+    fn cleanup_removes_work_dir_for_ephemeral_session() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // rm work dir
+        mock.add_response(MockResponse::Ok(String::new())); // rm lock
+
+        cleanup(&mock, &test_config(), "s1", true).unwrap();
+
+        let inv = mock.invocations();
+        assert_eq!(inv.len(), 2);
+        match &inv[0] {
+            Invocation::Ssh { command, .. } => assert_eq!(command, "rm -rf ~/relocal/s1"),
+            _ => panic!("expected Ssh for work dir removal"),
+        }
+        match &inv[1] {
+            Invocation::Ssh { command, .. } => assert!(command.contains(".locks/s1.lock")),
+            _ => panic!("expected Ssh for lock removal"),
+        }
+    }
+
+    #[test]
+    fn cleanup_keeps_lock_when_work_dir_removal_fails() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Fail("permission denied".into())); // rm work dir
+
+        assert!(cleanup(&mock, &test_config(), "s1", true).is_err());
+        assert_eq!(mock.invocations().len(), 1);
+    }
+
     #[test]
     fn poll_loop_exits_when_last_client_disconnects() {
         let dir = tempfile::tempdir().unwrap();
@@ -453,7 +921,8 @@ mod tests {
         std::thread::sleep(Duration::from_millis(50));
 
         // The poll loop should accept the client, detect disconnect, and return.
-        let runner = ProcessRunner::default();
+        // `false` fails the detached-session probe fast, which counts as none.
+        let runner = ProcessRunner::with_ssh_program("false");
         let config = test_config();
         let result = poll_loop(
             &listener,
@@ -480,7 +949,7 @@ mod tests {
         let mut client = UnixStream::connect(&sock_path).unwrap();
 
         let handle = std::thread::spawn(move || {
-            let runner = ProcessRunner::default();
+            let runner = ProcessRunner::with_ssh_program("false");
             let config = Config::parse("remote = \"user@host\"").unwrap();
             poll_loop(
                 &listener,
@@ -522,7 +991,7 @@ mod tests {
         let sock_path_clone = sock_path.clone();
 
         let handle = std::thread::spawn(move || {
-            let runner = ProcessRunner::default();
+            let runner = ProcessRunner::with_ssh_program("false");
             let config = Config::parse("remote = \"user@host\"").unwrap();
             poll_loop(
                 &listener,
@@ -550,4 +1019,36 @@ mod tests {
         let result = handle.join().unwrap();
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn poll_loop_pulls_every_sync_interval() {
+        let dir = tempfile::tempdir().unwrap();
+        let sock_path = dir.path().join("test.sock");
+        let listener = UnixListener::bind(&sock_path).unwrap();
+        listener.set_nonblocking(true).unwrap();
+
+        let client = UnixStream::connect(&sock_path).unwrap();
+
+        let handle = std::thread::spawn(move || {
+            // `false` makes every ssh call fail fast, so each pull is an error.
+            let runner = ProcessRunner::with_ssh_program("false");
+            let config = Config::parse("remote = \"user@host\"\nsync_interval = 1").unwrap();
+            poll_loop(
+                &listener,
+                &runner,
+                &config,
+                "s1",
+                &PathBuf::from("/tmp/fake"),
+                false,
+                "/tmp/control\n",
+            )
+        });
+
+        std::thread::sleep(Duration::from_millis(1500));
+        drop(client);
+        let stats = handle.join().unwrap().unwrap();
+        assert!(stats.pulls >= 1, "expected a timed pull: {stats:?}");
+        assert_eq!(stats.errors, stats.pulls);
+        assert_eq!(stats.pushes, 0);
+    }
 }
@@ -11,8 +11,9 @@ use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::time::Duration;
 
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
+use crate::daemon::DaemonOptions;
 use crate::error::{Error, Result};
 use crate::ssh;
 
@@ -50,9 +51,19 @@ pub fn connect_or_spawn(
     session_name: &str,
     remote: &str,
     repo_root: &Path,
+    config_path: Option<&Path>,
     verbosity: u8,
+    options: DaemonOptions,
 ) -> Result<DaemonConnection> {
-    connect_or_spawn_with_exe(session_name, remote, repo_root, verbosity, None)
+    connect_or_spawn_with_exe(
+        session_name,
+        remote,
+        repo_root,
+        config_path,
+        verbosity,
+        options,
+        None,
+    )
 }
 
 /// Like [`connect_or_spawn`], but allows overriding the daemon binary path.
@@ -64,7 +75,9 @@ pub fn connect_or_spawn_with_exe(
     session_name: &str,
     remote: &str,
     repo_root: &Path,
+    config_path: Option<&Path>,
     verbosity: u8,
+    options: DaemonOptions,
     daemon_exe: Option<&Path>,
 ) -> Result<DaemonConnection> {
     let socket_path = ssh::daemon_socket_path(session_name, remote);
@@ -74,6 +87,7 @@ pub fn connect_or_spawn_with_exe(
     // Fast path: daemon is already running.
     if let Ok(conn) = try_connect(&socket_path) {
         info!("Connected to existing session daemon");
+        warn_options_ignored(options);
         return Ok(conn);
     }
 
@@ -102,6 +116,7 @@ pub fn connect_or_spawn_with_exe(
     // Double-check: another process may have started the daemon while we waited.
     if let Ok(conn) = try_connect(&socket_path) {
         info!("Connected to session daemon (started by another process)");
+        warn_options_ignored(options);
         // flock released on drop of flock_file.
         return Ok(conn);
     }
@@ -135,8 +150,16 @@ pub fn connect_or_spawn_with_exe(
     for _ in 0..verbosity {
         cmd.arg("-v");
     }
+    // Pass the client's effective remote so a `--remote` override (or a
+    // decrypted `remote_encrypted`) reaches the daemon, which re-reads config
+    // (from the same `--config` file, if one was given).
+    cmd.args(["--remote", remote]);
+    if let Some(path) = config_path {
+        cmd.arg("--config").arg(path);
+    }
+    cmd.args(["_daemon", session_name, repo_root_str]);
+    cmd.args(options.args());
     let mut child = cmd
-        .args(["_daemon", session_name, repo_root_str])
         .stdin(Stdio::null())
         .stdout(Stdio::piped())
         .stderr(Stdio::inherit())
@@ -197,6 +220,14 @@ pub fn connect_or_spawn_with_exe(
     Ok(conn)
 }
 
+/// [`DaemonOptions`] are fixed when the daemon starts, so joining a running
+/// session cannot change them.
+fn warn_options_ignored(options: DaemonOptions) {
+    for flag in options.args() {
+        warn!("{flag} ignored: joined a session that is already running");
+    }
+}
+
 /// Returns true if a daemon is actively listening for the given session.
 ///
 /// Probes the daemon socket with a connect+disconnect. The brief connection
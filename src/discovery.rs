@@ -2,7 +2,8 @@
 //!
 //! Unlike tools that walk up the directory tree (git, cargo), relocal intentionally
 //! only checks the given directory. This prevents accidentally syncing an
-//! unexpectedly large directory with `rsync --delete`.
+//! unexpectedly large directory with `rsync --delete`. The global `--config`
+//! and `--repo-root` flags bypass discovery entirely (see [`locate`]).
 
 use std::path::{Path, PathBuf};
 
@@ -39,6 +40,45 @@ pub fn find_repo_root(start: &Path) -> Result<PathBuf> {
     })
 }
 
+/// Resolves the repo root and an explicit project config path.
+///
+/// With `--repo-root`, that directory is the root. Otherwise, with
+/// `--config`, the config file's parent directory is. Only when neither flag
+/// is given does this fall back to [`find_repo_root`] on `cwd`. Both flags
+/// must name existing paths; they are made absolute so a spawned daemon can
+/// reuse them. Returns `(repo_root, explicit_config)`.
+pub fn locate(
+    cwd: &Path,
+    config: Option<&Path>,
+    repo_root: Option<&Path>,
+) -> Result<(PathBuf, Option<PathBuf>)> {
+    let config = match config {
+        Some(path) if cwd.join(path).is_file() => Some(cwd.join(path).canonicalize()?),
+        Some(path) => {
+            return Err(Error::PathNotFound {
+                flag: "--config",
+                path: path.to_path_buf(),
+            })
+        }
+        None => None,
+    };
+    let root = match (repo_root, &config) {
+        (Some(path), _) if cwd.join(path).is_dir() => cwd.join(path).canonicalize()?,
+        (Some(path), _) => {
+            return Err(Error::PathNotFound {
+                flag: "--repo-root",
+                path: path.to_path_buf(),
+            })
+        }
+        (None, Some(config)) => config
+            .parent()
+            .expect("canonical file path has a parent")
+            .to_path_buf(),
+        (None, None) => find_repo_root(cwd)?,
+    };
+    Ok((root, config))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -126,6 +166,71 @@ mod tests {
         assert!(matches!(err, Error::ConfigNotFound { .. }));
     }
 
+    // --- locate tests ---
+
+    #[test]
+    fn locate_without_flags_discovers_cwd() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("relocal.toml"), "remote = \"u@h\"").unwrap();
+        let (root, config) = locate(tmp.path(), None, None).unwrap();
+        assert_eq!(root, tmp.path());
+        assert!(config.is_none());
+    }
+
+    #[test]
+    fn locate_config_root_is_parent_and_skips_discovery() {
+        let cwd = TempDir::new().unwrap(); // no markers: discovery would fail
+        let elsewhere = TempDir::new().unwrap();
+        let path = elsewhere.path().join("api.toml");
+        fs::write(&path, "remote = \"u@h\"").unwrap();
+
+        let (root, config) = locate(cwd.path(), Some(&path), None).unwrap();
+        assert_eq!(root, elsewhere.path().canonicalize().unwrap());
+        assert_eq!(config, Some(path.canonicalize().unwrap()));
+    }
+
+    #[test]
+    fn locate_repo_root_overrides_config_parent() {
+        let cwd = TempDir::new().unwrap();
+        fs::write(cwd.path().join("api.toml"), "remote = \"u@h\"").unwrap();
+        let repo = TempDir::new().unwrap();
+
+        let (root, config) =
+            locate(cwd.path(), Some(Path::new("api.toml")), Some(repo.path())).unwrap();
+        assert_eq!(root, repo.path().canonicalize().unwrap());
+        assert_eq!(
+            config,
+            Some(cwd.path().join("api.toml").canonicalize().unwrap())
+        );
+    }
+
+    #[test]
+    fn locate_missing_config_is_error() {
+        let cwd = TempDir::new().unwrap();
+        let err = locate(cwd.path(), Some(Path::new("nope.toml")), None).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::PathNotFound {
+                flag: "--config",
+                ..
+            }
+        ));
+        assert!(err.to_string().contains("nope.toml"), "{err}");
+    }
+
+    #[test]
+    fn locate_missing_repo_root_is_error() {
+        let cwd = TempDir::new().unwrap();
+        let err = locate(cwd.path(), None, Some(Path::new("missing"))).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::PathNotFound {
+                flag: "--repo-root",
+                ..
+            }
+        ));
+    }
+
     // --- is_git_root tests ---
 
     #[test]
@@ -19,6 +19,9 @@ pub enum Error {
     )]
     ConfigNotFound { start_dir: PathBuf },
 
+    #[error("{flag} {path} does not exist")]
+    PathNotFound { flag: &'static str, path: PathBuf },
+
     #[error("failed to parse {path}: {reason}")]
     ConfigParse { path: String, reason: String },
 
@@ -34,12 +37,143 @@ pub enum Error {
     #[error("remote error ({remote}): {message}")]
     Remote { remote: String, message: String },
 
+    #[error("session {session} is already running elsewhere (its lock was refreshed {age_secs}s ago). Open a shell in it with `relocal ssh {session}`, or use a different session name.")]
+    SessionAlreadyRunning { session: String, age_secs: u64 },
+
+    #[error("session {session} is in use (its lock file exists). Exit the relocal claude/codex/ssh sessions using it, then retry.")]
+    SessionInUse { session: String },
+
     #[error("session {session} appears to be active (lock file exists). If the previous session crashed, run `relocal destroy {session}` to clean up.")]
     StaleSession { session: String },
 
     #[error("refusing to pull: remote session {session} failed git fsck (not a git repo or repository is corrupted).\nStderr: {stderr}")]
     RemoteGitFsckFailed { session: String, stderr: String },
 
+    #[error("checksum verification failed: {count} path(s) differ between local and remote session {session}. Re-run `relocal sync push` to retry.")]
+    ChecksumMismatch { session: String, count: usize },
+
+    #[error("{count} conflicting path(s) in session {session} were left untouched. Resolve them by hand, or re-run `relocal sync --prefer local|remote`.")]
+    SyncConflict { session: String, count: usize },
+
+    #[error("remote {remote} was set up by relocal {installed_version} (protocol {remote_protocol}), but this binary only supports protocol {local_protocol}. Please upgrade relocal.")]
+    IncompatibleRemote {
+        remote: String,
+        installed_version: String,
+        remote_protocol: u32,
+        local_protocol: u32,
+    },
+
+    #[error("host key verification failed for {remote}: its host key is unknown or has changed, which may mean a spoofed host. Verify the key's fingerprint out of band, then update ~/.ssh/known_hosts (e.g. `ssh-keygen -R <host>` and reconnect with plain `ssh`).")]
+    HostKeyMismatch { remote: String },
+
+    #[error("remote {remote} is not in `allowed_remotes`; refusing to run a destructive command against it. Check `--remote` and your config, or pass --force.")]
+    RemoteNotAllowed { remote: String },
+
     #[error("failed to start session daemon: {message}")]
     DaemonSpawnFailed { message: String },
 }
+
+impl Error {
+    /// Process exit status for this error, so scripts can tell failures
+    /// apart without parsing messages. Documented in SPEC.md (Exit Codes);
+    /// `1` is the catch-all and `2` is left to clap's usage errors.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Io(_) | Error::DaemonSpawnFailed { .. } => 1,
+            Error::StaleSession { .. } => 3,
+            Error::CommandFailed { .. } => 4,
+            Error::Remote { .. } => 5,
+            Error::ConfigParse { .. } => 6,
+            Error::InvalidSessionName { .. } => 7,
+            Error::PathNotFound { .. } => 8,
+            Error::HostKeyMismatch { .. } => 9,
+            Error::RemoteNotAllowed { .. } => 10,
+            Error::RemoteGitFsckFailed { .. } => 11,
+            Error::ChecksumMismatch { .. } => 12,
+            Error::IncompatibleRemote { .. } => 13,
+            Error::SessionAlreadyRunning { .. } => 14,
+            Error::SyncConflict { .. } => 15,
+            Error::SessionInUse { .. } => 16,
+            Error::ConfigNotFound { .. } => 17,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exit_codes_are_distinct_and_nonzero() {
+        let errors = [
+            Error::ConfigNotFound {
+                start_dir: PathBuf::new(),
+            },
+            Error::StaleSession {
+                session: String::new(),
+            },
+            Error::CommandFailed {
+                command: String::new(),
+                message: String::new(),
+            },
+            Error::Remote {
+                remote: String::new(),
+                message: String::new(),
+            },
+            Error::ConfigParse {
+                path: String::new(),
+                reason: String::new(),
+            },
+            Error::InvalidSessionName {
+                name: String::new(),
+                reason: String::new(),
+            },
+            Error::PathNotFound {
+                flag: "",
+                path: PathBuf::new(),
+            },
+            Error::HostKeyMismatch {
+                remote: String::new(),
+            },
+            Error::RemoteNotAllowed {
+                remote: String::new(),
+            },
+            Error::RemoteGitFsckFailed {
+                session: String::new(),
+                stderr: String::new(),
+            },
+            Error::ChecksumMismatch {
+                session: String::new(),
+                count: 0,
+            },
+            Error::IncompatibleRemote {
+                remote: String::new(),
+                installed_version: String::new(),
+                remote_protocol: 0,
+                local_protocol: 0,
+            },
+            Error::SessionAlreadyRunning {
+                session: String::new(),
+                age_secs: 0,
+            },
+            Error::SyncConflict {
+                session: String::new(),
+                count: 0,
+            },
+            Error::SessionInUse {
+                session: String::new(),
+            },
+        ];
+        let mut codes: Vec<i32> = errors.iter().map(Error::exit_code).collect();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), errors.len());
+        // 1 is the catch-all, 2 is clap's usage error.
+        assert!(codes.iter().all(|&c| c > 2));
+    }
+
+    #[test]
+    fn catch_all_is_one() {
+        assert_eq!(Error::Io(std::io::Error::other("x")).exit_code(), 1);
+    }
+}
@@ -1,11 +1,16 @@
 //! relocal library — exposes modules for integration tests.
 
+pub mod case_check;
+pub mod checksum;
 pub mod commands;
 pub mod config;
 pub mod daemon;
 pub mod daemon_client;
 pub mod discovery;
 pub mod error;
+pub mod reconcile;
+pub mod registry;
+pub mod remote_meta;
 pub mod rsync;
 pub mod runner;
 pub mod session;
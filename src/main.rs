@@ -1,12 +1,15 @@
 mod cli;
 
+use std::io::IsTerminal;
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
 
-use clap::Parser;
-use cli::{Cli, Command, RemoteCommand, SyncCommand};
-use relocal::{commands, config, daemon, discovery, runner, session, ssh};
-use tracing::error;
+use clap::{CommandFactory, Parser};
+use cli::{Cli, Command, ConfigCommand, RemoteCommand, SessionsCommand, SyncCommand};
+use relocal::commands::destroy::{DestroyOptions, DestroyOutcome};
+use relocal::rsync::SyncOptions;
+use relocal::{commands, config, daemon, discovery, registry, runner, session, ssh};
+use tracing::{debug, error, warn};
 use tracing_subscriber::FmtSubscriber;
 
 /// Returns the user's home directory, or exits with an error.
@@ -17,19 +20,98 @@ fn home_dir() -> PathBuf {
     })
 }
 
-/// Finds the repo root and loads merged config (user + project). Exits on failure.
-fn load_config() -> (PathBuf, config::Config) {
-    let root = discovery::find_repo_root(&std::env::current_dir().unwrap()).unwrap_or_else(|e| {
+/// Global flags that affect config loading.
+struct ConfigFlags {
+    /// Config layer given by flags (`--remote`).
+    overrides: config::PartialConfig,
+    config: Option<PathBuf>,
+    repo_root: Option<PathBuf>,
+}
+
+/// Collects the config-related global flags, validating `--remote`.
+fn config_flags(cli: &Cli) -> relocal::error::Result<ConfigFlags> {
+    if let Some(remote) = &cli.remote {
+        config::validate_remote(remote)?;
+    }
+    Ok(ConfigFlags {
+        overrides: config::PartialConfig {
+            remote: cli.remote.clone(),
+            ..Default::default()
+        },
+        config: cli.config.clone(),
+        repo_root: cli.root.clone(),
+    })
+}
+
+/// Loads merged config (user + project + flags), reading the project layer
+/// from `config` if given and from `<repo_root>/relocal.toml` otherwise.
+///
+/// With `session`, `<repo_root>/relocal.<session>.toml` is layered on top of
+/// the project config if it exists.
+fn load_merged(
+    repo_root: &Path,
+    config: Option<PathBuf>,
+    session: Option<&str>,
+    overrides: config::PartialConfig,
+) -> relocal::error::Result<config::Config> {
+    let runner = runner::ProcessRunner::default();
+    let project = config
+        .clone()
+        .unwrap_or_else(|| repo_root.join("relocal.toml"));
+    let session_config = session.map(|s| config::session_config_path(repo_root, s));
+    let mut cfg = config::load_merged_config_from(
+        &runner,
+        &home_dir(),
+        &project,
+        session_config.as_deref(),
+        overrides,
+    )?;
+    cfg.config_path = config;
+    Ok(cfg)
+}
+
+/// Finds the repo root (or takes it from `--config`/`--repo-root`) and loads
+/// merged config. Exits on failure.
+fn load_config(flags: &ConfigFlags) -> (PathBuf, config::Config) {
+    let (root, config) = discovery::locate(
+        &std::env::current_dir().unwrap(),
+        flags.config.as_deref(),
+        flags.repo_root.as_deref(),
+    )
+    .unwrap_or_else(|e| {
         error!("{e}");
-        std::process::exit(1);
+        std::process::exit(e.exit_code());
     });
-    let cfg = config::load_merged_config(&home_dir(), &root).unwrap_or_else(|e| {
+    let cfg = load_merged(&root, config, None, flags.overrides.clone()).unwrap_or_else(|e| {
         error!("{e}");
-        std::process::exit(1);
+        std::process::exit(e.exit_code());
     });
     (root, cfg)
 }
 
+/// Reloads `cfg` with the session's `relocal.<session>.toml` layered in, if
+/// that file exists. Exits on failure.
+fn with_session_config(
+    flags: &ConfigFlags,
+    root: &Path,
+    session: &str,
+    cfg: config::Config,
+) -> config::Config {
+    if !config::session_config_path(root, session).is_file() {
+        return cfg;
+    }
+    load_merged(
+        root,
+        cfg.config_path,
+        Some(session),
+        flags.overrides.clone(),
+    )
+    .unwrap_or_else(|e| {
+        error!("{e}");
+        std::process::exit(e.exit_code());
+    })
+}
+
 /// Resolves the session name: explicit name if given, otherwise hashed from
 /// the repo root path and git origin.
 fn resolve_session(name: Option<String>, repo_root: &Path) -> String {
@@ -37,17 +119,67 @@ fn resolve_session(name: Option<String>, repo_root: &Path) -> String {
         Some(n) => {
             session::validate_session_name(&n).unwrap_or_else(|e| {
                 error!("{e}");
-                std::process::exit(1);
+                std::process::exit(e.exit_code());
             });
             n
         }
         None => session::hashed_session_name(repo_root).unwrap_or_else(|e| {
             error!("{e}");
-            std::process::exit(1);
+            std::process::exit(e.exit_code());
         }),
     }
 }
 
+/// Creates a runner that multiplexes a standalone command's SSH and rsync
+/// calls over one ControlMaster owned by this process. Exits on failure.
+fn standalone_runner(config: &config::Config, label: &str) -> runner::ProcessRunner {
+    runner::ProcessRunner::with_control_master(
+        &config.remote,
+        label,
+        config.strict_host_key,
+        config.control_persist,
+    )
+    .unwrap_or_else(|e| {
+        error!("{e}");
+        std::process::exit(e.exit_code());
+    })
+    .with_config(config)
+}
+
+/// Creates a runner for commands that connect without a ControlMaster:
+/// interactive sessions and single queries.
+fn direct_runner(config: &config::Config) -> runner::ProcessRunner {
+    runner::ProcessRunner::default()
+        .with_config(config)
+        .with_strict_host_key(config.strict_host_key)
+}
+
+/// Reports `e` and exits with its code, tearing down `runner`'s
+/// ControlMaster first: `process::exit` skips destructors, which would leave
+/// the master process and its socket behind.
+fn exit_with_error(runner: runner::ProcessRunner, e: relocal::error::Error) -> ! {
+    error!("{e}");
+    drop(runner);
+    std::process::exit(e.exit_code());
+}
+
+/// Records a session launch in the local registry and prunes stale entries.
+/// A registry problem only warns: it must never keep a session from starting.
+fn record_session(cfg: &config::Config, session: &str, repo_root: &Path) {
+    let home = home_dir();
+    if let Err(e) = registry::record_session(&home, session, &cfg.remote, repo_root) {
+        warn!("could not update session registry: {e}");
+    }
+    match registry::prune_stale(&home, registry::now_secs()) {
+        Ok(pruned) => {
+            for entry in pruned {
+                debug!("Pruned stale session '{}' ({}).", entry.name, entry.remote);
+            }
+        }
+        Err(e) => warn!("could not prune session registry: {e}"),
+    }
+}
+
 /// Initializes the tracing subscriber for the daemon, writing to a log file.
 ///
 /// Returns the parsed config so `run_daemon` can reuse it without re-reading
@@ -62,11 +194,14 @@ fn init_daemon_tracing(
     level: tracing::Level,
     session_name: &str,
     repo_root: &str,
+    config: Option<PathBuf>,
+    overrides: config::PartialConfig,
 ) -> config::Config {
-    let cfg = config::load_merged_config(&home_dir(), Path::new(repo_root)).unwrap_or_else(|e| {
-        eprintln!("Error: {e}");
-        std::process::exit(1);
-    });
+    let cfg = load_merged(Path::new(repo_root), config, Some(session_name), overrides)
+        .unwrap_or_else(|e| {
+            eprintln!("Error: {e}");
+            std::process::exit(e.exit_code());
+        });
 
     let log_path = ssh::daemon_log_path(session_name, &cfg.remote);
     let log_file = std::fs::File::create(&log_path).unwrap_or_else(|e| {
@@ -92,145 +227,508 @@ fn main() {
     let daemon_config = if let Command::Daemon {
         ref session_name,
         ref repo_root,
+        ..
     } = cli.command
     {
+        // The spawning client passes its effective remote, already validated
+        // (or taken from config, which may use a host alias), and an absolute
+        // `--config` if it was given one.
+        let overrides = config::PartialConfig {
+            remote: cli.remote.clone(),
+            ..Default::default()
+        };
         Some(init_daemon_tracing(
             cli.log_level(),
             session_name,
             repo_root,
+            cli.config.clone(),
+            overrides,
         ))
     } else {
         FmtSubscriber::builder()
             .with_max_level(cli.log_level())
             .with_writer(std::io::stderr)
+            .with_ansi(cli::color_enabled(
+                std::env::var_os("NO_COLOR"),
+                std::io::stderr().is_terminal(),
+            ))
             .init();
         None
     };
+    let flags = config_flags(&cli).unwrap_or_else(|e| {
+        error!("{e}");
+        std::process::exit(e.exit_code());
+    });
 
     match cli.command {
-        Command::Init => {
-            if let Err(e) = commands::init::run(&std::env::current_dir().unwrap()) {
+        Command::Init { from_existing } => {
+            if let Err(e) =
+                commands::init::run(&std::env::current_dir().unwrap(), from_existing.as_deref())
+            {
                 error!("{e}");
-                std::process::exit(1);
+                std::process::exit(e.exit_code());
             }
         }
-        Command::Remote { command } => match command {
-            RemoteCommand::Install => {
-                let (_root, cfg) = load_config();
-                let runner = runner::ProcessRunner::default();
-                if let Err(e) = commands::install::run(&runner, &cfg) {
+        Command::Config { command } => match command {
+            ConfigCommand::Check => {
+                let (root, cfg) = load_config(&flags);
+                if let Err(e) = commands::config_check::run(&cfg, &root) {
                     error!("{e}");
-                    std::process::exit(1);
+                    std::process::exit(e.exit_code());
+                }
+            }
+        },
+        Command::Remote { command } => match command {
+            RemoteCommand::Install { only, skip } => {
+                let (_root, cfg) = load_config(&flags);
+                let runner = standalone_runner(&cfg, "install");
+                if let Err(e) = commands::install::run(
+                    &runner,
+                    &cfg,
+                    &commands::install::StepSelection { only, skip },
+                ) {
+                    exit_with_error(runner, e);
                 }
             }
-            RemoteCommand::Nuke => {
-                let (_root, cfg) = load_config();
-                let runner = runner::ProcessRunner::default();
-                if let Err(e) = commands::nuke::run(&runner, &cfg, true) {
+            RemoteCommand::Nuke { force } => {
+                let (_root, cfg) = load_config(&flags);
+                let runner = direct_runner(&cfg);
+                if let Err(e) = commands::nuke::run(&runner, &cfg, true, force) {
                     error!("{e}");
-                    std::process::exit(1);
+                    std::process::exit(e.exit_code());
                 }
             }
         },
         Command::Claude {
             session_name,
+            no_skip_permissions,
+            ephemeral,
+            no_initial_push,
+            detach,
             claude_args,
         } => {
-            let (root, cfg) = load_config();
+            let (root, cfg) = load_config(&flags);
             let session = resolve_session(session_name, &root);
-            if let Err(e) = commands::claude::run(&cfg, &session, &root, cli.verbose, &claude_args)
-            {
+            let mut cfg = with_session_config(&flags, &root, &session, cfg);
+            cfg.skip_permissions &= !no_skip_permissions;
+            record_session(&cfg, &session, &root);
+            if let Err(e) = commands::claude::warn_skip_permissions_once(&home_dir(), &cfg) {
+                warn!("could not record the skip-permissions warning: {e}");
+            }
+            let options = daemon::DaemonOptions {
+                ephemeral,
+                no_initial_push,
+            };
+            if let Err(e) = commands::claude::run(
+                &cfg,
+                &session,
+                &root,
+                cli.verbose,
+                options,
+                &claude_args,
+                detach,
+            ) {
                 error!("{e}");
-                std::process::exit(1);
+                std::process::exit(e.exit_code());
             }
         }
         Command::Codex {
             session_name,
+            no_skip_permissions,
+            ephemeral,
+            no_initial_push,
             codex_args,
         } => {
-            let (root, cfg) = load_config();
+            let (root, cfg) = load_config(&flags);
+            let session = resolve_session(session_name, &root);
+            let mut cfg = with_session_config(&flags, &root, &session, cfg);
+            cfg.skip_permissions &= !no_skip_permissions;
+            record_session(&cfg, &session, &root);
+            let options = daemon::DaemonOptions {
+                ephemeral,
+                no_initial_push,
+            };
+            if let Err(e) =
+                commands::codex::run(&cfg, &session, &root, cli.verbose, options, &codex_args)
+            {
+                error!("{e}");
+                std::process::exit(e.exit_code());
+            }
+        }
+        Command::Attach { session_name } => {
+            let (root, cfg) = load_config(&flags);
             let session = resolve_session(session_name, &root);
-            if let Err(e) = commands::codex::run(&cfg, &session, &root, cli.verbose, &codex_args) {
+            let cfg = with_session_config(&flags, &root, &session, cfg);
+            let runner = direct_runner(&cfg);
+            if let Err(e) = commands::attach::run(&runner, &cfg, &session) {
                 error!("{e}");
-                std::process::exit(1);
+                std::process::exit(e.exit_code());
             }
         }
         Command::Ssh { session_name } => {
-            let (root, cfg) = load_config();
-            let runner = runner::ProcessRunner::default();
+            let (root, cfg) = load_config(&flags);
             let session = resolve_session(session_name, &root);
+            let cfg = with_session_config(&flags, &root, &session, cfg);
+            let runner = direct_runner(&cfg);
             if let Err(e) = commands::ssh::run(&runner, &cfg, &session) {
                 error!("{e}");
-                std::process::exit(1);
+                std::process::exit(e.exit_code());
+            }
+        }
+        Command::Exec {
+            session_name,
+            command,
+        } => {
+            let (root, cfg) = load_config(&flags);
+            let session = resolve_session(session_name, &root);
+            let cfg = with_session_config(&flags, &root, &session, cfg);
+            let runner = standalone_runner(&cfg, &session);
+            match commands::exec::run(&runner, &cfg, &session, &command) {
+                Ok(code) => {
+                    // Tear down the ControlMaster before exiting.
+                    drop(runner);
+                    std::process::exit(code);
+                }
+                Err(e) => {
+                    exit_with_error(runner, e);
+                }
+            }
+        }
+        Command::Watch { session_name } => {
+            let (root, cfg) = load_config(&flags);
+            let session = resolve_session(session_name, &root);
+            let cfg = with_session_config(&flags, &root, &session, cfg);
+            let runner = standalone_runner(&cfg, &session);
+            let options = SyncOptions {
+                verbose,
+                ..Default::default()
+            };
+            if let Err(e) = commands::watch::run(&runner, &cfg, &session, &root, &options) {
+                exit_with_error(runner, e);
             }
         }
-        Command::Sync { command } => {
-            let (root, cfg) = load_config();
-            let runner = runner::ProcessRunner::default();
+        Command::Sync {
+            command,
+            prefer,
+            bidirectional_safe,
+            session_name,
+        } => {
+            let (root, cfg) = load_config(&flags);
+            let Some(command) = command else {
+                let session = resolve_session(session_name, &root);
+                let cfg = with_session_config(&flags, &root, &session, cfg);
+                let runner = standalone_runner(&cfg, &session);
+                let options = SyncOptions {
+                    verbose,
+                    ..Default::default()
+                };
+                let result = if bidirectional_safe {
+                    commands::sync::sync_bidirectional_safe(
+                        &runner, &cfg, &session, &root, &options,
+                    )
+                } else {
+                    commands::sync::sync_reconcile(&runner, &cfg, &session, &root, &options, prefer)
+                };
+                if let Err(e) = result {
+                    exit_with_error(runner, e);
+                }
+                return;
+            };
             match command {
-                SyncCommand::Push { session_name } => {
+                SyncCommand::Copy { from, to } => {
+                    let from = resolve_session(Some(from), &root);
+                    let to = resolve_session(Some(to), &root);
+                    let runner = standalone_runner(&cfg, &to);
+                    if let Err(e) = commands::sync::sync_copy(&runner, &cfg, &from, &to) {
+                        exit_with_error(runner, e);
+                    }
+                }
+                SyncCommand::Push {
+                    session_name,
+                    dry_run,
+                    delete_dry_run,
+                    checksum_verify,
+                    resume,
+                    no_delete,
+                    check_case,
+                    one_file_system,
+                    checksum,
+                    stats_json,
+                    include_from,
+                    exclude_from,
+                    compare_dest,
+                    show_filters,
+                } => {
                     let session = resolve_session(session_name, &root);
+                    let cfg = with_session_config(&flags, &root, &session, cfg);
+                    let options = SyncOptions {
+                        verbose,
+                        dry_run,
+                        delete_dry_run,
+                        checksum_verify,
+                        resume,
+                        no_delete,
+                        check_case,
+                        one_file_system,
+                        checksum,
+                        stats_json,
+                        include_from,
+                        exclude_from,
+                        compare_dest,
+                        ..Default::default()
+                    };
+                    if show_filters {
+                        commands::sync::show_filters(&cfg, &root, &options);
+                        return;
+                    }
+                    let runner = standalone_runner(&cfg, &session);
                     if let Err(e) =
-                        commands::sync::sync_push(&runner, &cfg, &session, &root, verbose)
+                        commands::sync::sync_push(&runner, &cfg, &session, &root, &options)
                     {
-                        error!("{e}");
-                        std::process::exit(1);
+                        exit_with_error(runner, e);
                     }
                 }
-                SyncCommand::Pull { session_name } => {
+                SyncCommand::Pull {
+                    session_name,
+                    dry_run,
+                    delete_dry_run,
+                    resume,
+                    local_path,
+                    force,
+                    one_file_system,
+                    checksum,
+                    stats_json,
+                    include_from,
+                    exclude_from,
+                    show_filters,
+                } => {
                     let session = resolve_session(session_name, &root);
+                    let cfg = with_session_config(&flags, &root, &session, cfg);
+                    let options = SyncOptions {
+                        verbose,
+                        dry_run,
+                        delete_dry_run,
+                        resume,
+                        local_path,
+                        force,
+                        one_file_system,
+                        checksum,
+                        stats_json,
+                        include_from,
+                        exclude_from,
+                        ..Default::default()
+                    };
+                    if show_filters {
+                        commands::sync::show_filters(&cfg, &root, &options);
+                        return;
+                    }
+                    let runner = standalone_runner(&cfg, &session);
                     if let Err(e) =
-                        commands::sync::sync_pull(&runner, &cfg, &session, &root, verbose)
+                        commands::sync::sync_pull(&runner, &cfg, &session, &root, &options)
                     {
-                        error!("{e}");
-                        std::process::exit(1);
+                        exit_with_error(runner, e);
                     }
                 }
             }
         }
-        Command::Status { session_name } => {
-            let (root, cfg) = load_config();
-            let runner = runner::ProcessRunner::default();
+        Command::Benchmark {
+            session_name,
+            count,
+        } => {
+            let (root, cfg) = load_config(&flags);
             let session = resolve_session(session_name, &root);
-            if let Err(e) = commands::status::run(&runner, &cfg, &session) {
-                error!("{e}");
-                std::process::exit(1);
+            let cfg = with_session_config(&flags, &root, &session, cfg);
+            let runner = standalone_runner(&cfg, &session);
+            if let Err(e) = commands::benchmark::run(&runner, &cfg, &session, &root, count) {
+                exit_with_error(runner, e);
+            }
+        }
+        Command::Status { session_name, json } => {
+            let (root, cfg) = load_config(&flags);
+            let session = resolve_session(session_name, &root);
+            let cfg = with_session_config(&flags, &root, &session, cfg);
+            let runner = standalone_runner(&cfg, &session);
+            if let Err(e) = commands::status::run(&runner, &cfg, &session, json) {
+                exit_with_error(runner, e);
             }
         }
         Command::Log { session_name } => {
-            let (root, cfg) = load_config();
+            let (root, cfg) = load_config(&flags);
             let session = resolve_session(session_name, &root);
+            let cfg = with_session_config(&flags, &root, &session, cfg);
             if let Err(e) = commands::log::run(&cfg, &session) {
                 error!("{e}");
-                std::process::exit(1);
+                std::process::exit(e.exit_code());
+            }
+        }
+        Command::Env { session_name } => {
+            let (root, cfg) = load_config(&flags);
+            let session = resolve_session(session_name, &root);
+            let cfg = with_session_config(&flags, &root, &session, cfg);
+            if let Err(e) = commands::env::run(&cfg, &session) {
+                error!("{e}");
+                std::process::exit(e.exit_code());
             }
         }
-        Command::List => {
-            let (_root, cfg) = load_config();
-            let runner = runner::ProcessRunner::default();
-            if let Err(e) = commands::list::run(&runner, &cfg) {
+        Command::Whoami => {
+            let (_root, cfg) = load_config(&flags);
+            let runner = direct_runner(&cfg);
+            if let Err(e) = commands::whoami::run(&runner, &cfg) {
+                error!("{e}");
+                std::process::exit(e.exit_code());
+            }
+        }
+        Command::List { detail, json } => {
+            let (_root, cfg) = load_config(&flags);
+            let runner = direct_runner(&cfg);
+            if let Err(e) = commands::list::run(&runner, &cfg, detail, json) {
+                error!("{e}");
+                std::process::exit(e.exit_code());
+            }
+        }
+        Command::Sessions { command } => {
+            let result = match command {
+                None => commands::sessions::list(&home_dir()),
+                Some(SessionsCommand::Forget { name }) => {
+                    commands::sessions::forget(&home_dir(), &name)
+                }
+            };
+            if let Err(e) = result {
                 error!("{e}");
-                std::process::exit(1);
+                std::process::exit(e.exit_code());
             }
         }
-        Command::Destroy { session_name } => {
-            let (root, cfg) = load_config();
-            let runner = runner::ProcessRunner::default();
+        Command::Destroy {
+            session_name,
+            force,
+        } => {
+            let (root, cfg) = load_config(&flags);
             let session = resolve_session(session_name, &root);
-            if let Err(e) = commands::destroy::run(&runner, &cfg, &session, true, true) {
+            let cfg = with_session_config(&flags, &root, &session, cfg);
+            let runner = standalone_runner(&cfg, &session);
+            match commands::destroy::run(
+                &runner,
+                &cfg,
+                &session,
+                DestroyOptions {
+                    confirm: true,
+                    check_daemon: true,
+                    force,
+                },
+            ) {
+                Ok(DestroyOutcome::Destroyed) => {
+                    if let Err(e) =
+                        registry::forget_remote_session(&home_dir(), &session, &cfg.remote)
+                    {
+                        warn!("could not update session registry: {e}");
+                    }
+                }
+                Ok(DestroyOutcome::Aborted) => {}
+                Err(e) => exit_with_error(runner, e),
+            }
+        }
+        Command::ImportSession {
+            remote_path,
+            session_name,
+        } => {
+            // The directory is empty, so there is no project config to find.
+            let cwd = std::env::current_dir().unwrap();
+            let root = flags
+                .repo_root
+                .as_deref()
+                .map_or(cwd.clone(), |p| cwd.join(p));
+            let cfg = load_merged(&root, None, None, flags.overrides.clone()).unwrap_or_else(|e| {
+                error!("{e}");
+                std::process::exit(e.exit_code());
+            });
+            let runner = standalone_runner(&cfg, "import-session");
+            match commands::import_session::run(
+                &runner,
+                &cfg,
+                &remote_path,
+                session_name.as_deref(),
+                &root,
+            ) {
+                Ok(session) => record_session(&cfg, &session, &root),
+                Err(e) => {
+                    exit_with_error(runner, e);
+                }
+            }
+        }
+        Command::Rename { old, new } => {
+            let (_root, cfg) = load_config(&flags);
+            let runner = standalone_runner(&cfg, "rename");
+            if let Err(e) = commands::rename::run(&runner, &cfg, &old, &new, true) {
+                exit_with_error(runner, e);
+            }
+            if let Err(e) = registry::rename_session(&home_dir(), &old, &new, &cfg.remote) {
+                warn!("could not update session registry: {e}");
+            }
+        }
+        Command::Prune {
+            older_than,
+            include_locked,
+            force,
+        } => {
+            let (_root, cfg) = load_config(&flags);
+            let runner = standalone_runner(&cfg, "prune");
+            match commands::prune::run(
+                &runner,
+                &cfg,
+                older_than,
+                include_locked,
+                DestroyOptions {
+                    confirm: true,
+                    check_daemon: true,
+                    force,
+                },
+            ) {
+                Ok(pruned) => {
+                    for session in pruned {
+                        if let Err(e) =
+                            registry::forget_remote_session(&home_dir(), &session, &cfg.remote)
+                        {
+                            warn!("could not update session registry: {e}");
+                        }
+                    }
+                }
+                Err(e) => {
+                    exit_with_error(runner, e);
+                }
+            }
+        }
+        Command::Doctor { fix } => {
+            let (_root, cfg) = load_config(&flags);
+            // No ControlMaster: a connection failure is a check result, not a
+            // reason to exit before printing the checklist.
+            let runner = direct_runner(&cfg);
+            if let Err(e) = commands::doctor::run(&runner, &cfg, fix) {
+                error!("{e}");
+                std::process::exit(e.exit_code());
+            }
+        }
+        Command::Completions { shell } => {
+            if let Err(e) = commands::completions::run(&mut Cli::command(), shell) {
                 error!("{e}");
-                std::process::exit(1);
+                std::process::exit(e.exit_code());
             }
         }
         Command::Daemon {
             session_name,
             repo_root,
+            ephemeral,
+            no_initial_push,
         } => {
             let cfg = daemon_config.expect("daemon config set above");
-            if let Err(e) = daemon::run_daemon(&cfg, &session_name, Path::new(&repo_root), verbose)
+            let options = daemon::DaemonOptions {
+                ephemeral,
+                no_initial_push,
+            };
+            if let Err(e) =
+                daemon::run_daemon(&cfg, &session_name, Path::new(&repo_root), verbose, options)
             {
                 error!("{e}");
-                std::process::exit(1);
+                std::process::exit(e.exit_code());
             }
         }
     }
@@ -0,0 +1,371 @@
+//! Change detection for the reconciling `relocal sync`.
+//!
+//! Instead of mirroring one side onto the other, a reconciling sync looks at
+//! every path that differs (from a dry-run pull, see [`parse_differences`])
+//! and asks which side changed it since the previous reconciling sync:
+//!
+//! - locally, a path changed if its mtime is at or after the local time the
+//!   previous sync started;
+//! - on the remote, a path changed if it is newer than the baseline file that
+//!   sync left in `.relocal/` (see [`crate::ssh::begin_reconcile`]).
+//!
+//! Each side is only compared against its own clock. rsync copies mtimes, so
+//! clock skew between the hosts can only make a file transferred by an
+//! earlier sync look changed, which turns a one-sided change into a conflict
+//! rather than losing an edit.
+//!
+//! A path changed on one side only is synced from that side. A path changed
+//! on both sides, changed on neither (differences left by a manual push or
+//! pull), or seen without a baseline is a conflict and is left alone unless a
+//! side is preferred. A deletion never wins over an edit: a path missing
+//! locally but changed on the remote is pulled back. A path missing on one
+//! side is only deleted on the other if the baseline lists it as present on
+//! both after the previous sync; otherwise it is new and gets copied.
+//!
+//! Like `rsync.rs`, this module only parses output and plans; the caller runs
+//! the commands.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use crate::rsync::is_itemize_code;
+
+/// A side of the sync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Local,
+    Remote,
+}
+
+/// Parses a `--prefer` value: `local` or `remote`.
+pub fn parse_side(input: &str) -> std::result::Result<Side, String> {
+    match input {
+        "local" => Ok(Side::Local),
+        "remote" => Ok(Side::Remote),
+        other => Err(format!("unknown side `{other}`: expected local or remote")),
+    }
+}
+
+/// A path that differs between the two sides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Difference {
+    /// Present on both sides with different contents.
+    Modified(String),
+    /// Present locally only.
+    LocalOnly(String),
+    /// Present on the remote only.
+    RemoteOnly(String),
+}
+
+impl Difference {
+    pub fn path(&self) -> &str {
+        match self {
+            Difference::Modified(path)
+            | Difference::LocalOnly(path)
+            | Difference::RemoteOnly(path) => path,
+        }
+    }
+}
+
+/// Extracts the differing files and symlinks from the `--itemize-changes`
+/// output of a dry-run pull.
+///
+/// Directories are skipped: their files are listed individually, and a
+/// deleted file's directory is left behind. Attribute-only changes are not
+/// differences.
+pub fn parse_differences(pull_output: &str) -> Vec<Difference> {
+    let mut differences = Vec::new();
+    for line in pull_output.lines() {
+        if let Some(path) = line.trim_start().strip_prefix("*deleting") {
+            let path = path.trim();
+            if !path.is_empty() && !path.ends_with('/') {
+                differences.push(Difference::LocalOnly(path.to_string()));
+            }
+            continue;
+        }
+        let Some((code, path)) = line.split_once(' ') else {
+            continue;
+        };
+        let mut chars = code.chars();
+        let (Some(kind), Some(file_type)) = (chars.next(), chars.next()) else {
+            continue;
+        };
+        if !is_itemize_code(code) || !matches!(file_type, 'f' | 'L') || path.is_empty() {
+            continue;
+        }
+        // Symlinks are listed as `link -> target`.
+        let path = match (file_type, path.split_once(" -> ")) {
+            ('L', Some((link, _))) => link.to_string(),
+            _ => path.to_string(),
+        };
+        if code[2..].chars().all(|c| c == '+') {
+            differences.push(Difference::RemoteOnly(path));
+        } else if kind == '>' || (kind == 'c' && file_type == 'L') {
+            differences.push(Difference::Modified(path));
+        }
+    }
+    differences
+}
+
+/// What the previous reconciling sync left behind.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Baseline {
+    /// Local time (seconds since the epoch) the previous sync started.
+    pub local_secs: u64,
+    /// Remote paths modified since the previous sync started.
+    pub remote_changed: BTreeSet<String>,
+    /// Paths present on both sides after the previous sync.
+    pub synced: BTreeSet<String>,
+}
+
+/// Parses the output of [`crate::ssh::begin_reconcile`]: `None` when no
+/// previous sync left a baseline.
+pub fn parse_baseline(output: &str) -> Option<Baseline> {
+    let mut lines = output.lines();
+    let local_secs = lines.next()?.trim().parse().ok()?;
+    let mut baseline = Baseline {
+        local_secs,
+        ..Baseline::default()
+    };
+    for line in lines {
+        if let Some(path) = line.strip_prefix("=./") {
+            baseline.synced.insert(path.to_string());
+        } else if let Some(path) = line.strip_prefix("./") {
+            baseline.remote_changed.insert(path.to_string());
+        }
+    }
+    Some(baseline)
+}
+
+/// Whether the local `path` was modified at or after `since` (seconds since
+/// the epoch). Missing or unreadable mtimes count as changed.
+pub fn changed_locally(repo_root: &Path, path: &str, since: u64) -> bool {
+    std::fs::symlink_metadata(repo_root.join(path))
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|mtime| mtime.duration_since(UNIX_EPOCH).ok())
+        .is_none_or(|mtime| mtime.as_secs() >= since)
+}
+
+/// How to reconcile the differences.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Plan {
+    /// Paths to push: sent to the remote, or deleted there if missing locally.
+    pub push: Vec<String>,
+    /// Paths to pull: fetched from the remote, or deleted locally if missing
+    /// there.
+    pub pull: Vec<String>,
+    /// Paths left untouched.
+    pub conflicts: Vec<String>,
+}
+
+/// Decides which way each difference goes.
+///
+/// `changed_locally` reports whether a path present locally changed since
+/// the baseline; it is only consulted when there is one. Conflicts go to the
+/// `prefer`red side when one is given.
+pub fn plan(
+    differences: &[Difference],
+    baseline: Option<&Baseline>,
+    changed_locally: impl Fn(&str) -> bool,
+    prefer: Option<Side>,
+) -> Plan {
+    let mut plan = Plan::default();
+    for difference in differences {
+        let path = difference.path();
+        let winner = baseline.and_then(|baseline| {
+            let remote = baseline.remote_changed.contains(path);
+            let synced = baseline.synced.contains(path);
+            match difference {
+                Difference::Modified(_) => match (changed_locally(path), remote) {
+                    (true, false) => Some(Side::Local),
+                    (false, true) => Some(Side::Remote),
+                    _ => None,
+                },
+                // Deleted on the remote unless new or edited locally since.
+                Difference::LocalOnly(_) if !synced || changed_locally(path) => Some(Side::Local),
+                Difference::LocalOnly(_) => Some(Side::Remote),
+                // Deleted locally unless new or edited on the remote since.
+                Difference::RemoteOnly(_) if !synced || remote => Some(Side::Remote),
+                Difference::RemoteOnly(_) => Some(Side::Local),
+            }
+        });
+        match winner.or(prefer) {
+            Some(Side::Local) => plan.push.push(path.to_string()),
+            Some(Side::Remote) => plan.pull.push(path.to_string()),
+            None => plan.conflicts.push(path.to_string()),
+        }
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn baseline(remote_changed: &[&str]) -> Baseline {
+        Baseline {
+            local_secs: 100,
+            remote_changed: remote_changed.iter().map(|p| p.to_string()).collect(),
+            synced: BTreeSet::new(),
+        }
+    }
+
+    fn modified(path: &str) -> Difference {
+        Difference::Modified(path.to_string())
+    }
+
+    #[test]
+    fn parse_side_values() {
+        assert_eq!(parse_side("local"), Ok(Side::Local));
+        assert_eq!(parse_side("remote"), Ok(Side::Remote));
+        assert!(parse_side("both").is_err());
+    }
+
+    #[test]
+    fn parse_differences_classifies_itemized_lines() {
+        let output = "\
+>f.st...... src/main.rs
+>f+++++++++ src/new.rs
+cd+++++++++ docs/
+>f+++++++++ docs/guide.md
+*deleting   local-only.txt
+*deleting   stale-dir/
+.f...p..... mode-only.sh
+cLc.T...... link -> elsewhere
+cL+++++++++ new-link -> target
+
+Number of files: 3
+";
+        assert_eq!(
+            parse_differences(output),
+            vec![
+                modified("src/main.rs"),
+                Difference::RemoteOnly("src/new.rs".into()),
+                Difference::RemoteOnly("docs/guide.md".into()),
+                Difference::LocalOnly("local-only.txt".into()),
+                modified("link"),
+                Difference::RemoteOnly("new-link".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_differences_reads_rsync_2_6_9_codes() {
+        let output = "\
+>f.st.... src/main.rs
+>f+++++++ src/new.rs
+*deleting   local-only.txt
+.f...p... mode-only.sh
+";
+        assert_eq!(
+            parse_differences(output),
+            vec![
+                modified("src/main.rs"),
+                Difference::RemoteOnly("src/new.rs".into()),
+                Difference::LocalOnly("local-only.txt".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_baseline_reads_time_and_paths() {
+        assert_eq!(parse_baseline(""), None);
+        assert_eq!(
+            parse_baseline("100\n./a.txt\n./src/b.rs\n=./a.txt\n=./c.txt\n"),
+            Some(Baseline {
+                local_secs: 100,
+                remote_changed: ["a.txt", "src/b.rs"].map(String::from).into(),
+                synced: ["a.txt", "c.txt"].map(String::from).into(),
+            })
+        );
+    }
+
+    #[test]
+    fn one_sided_changes_sync_from_that_side() {
+        let differences = [modified("mine"), modified("theirs")];
+        let plan = plan(
+            &differences,
+            Some(&baseline(&["theirs"])),
+            |p| p == "mine",
+            None,
+        );
+        assert_eq!(plan.push, vec!["mine"]);
+        assert_eq!(plan.pull, vec!["theirs"]);
+        assert!(plan.conflicts.is_empty());
+    }
+
+    #[test]
+    fn changes_on_both_or_neither_side_conflict() {
+        let differences = [modified("both"), modified("neither")];
+        let plan = plan(
+            &differences,
+            Some(&baseline(&["both"])),
+            |p| p == "both",
+            None,
+        );
+        assert_eq!(plan.conflicts, vec!["both", "neither"]);
+        assert!(plan.push.is_empty() && plan.pull.is_empty());
+    }
+
+    #[test]
+    fn deletions_propagate_unless_the_other_side_edited() {
+        let differences = [
+            Difference::LocalOnly("deleted-remotely".into()),
+            Difference::LocalOnly("new-locally".into()),
+            Difference::RemoteOnly("deleted-locally".into()),
+            Difference::RemoteOnly("edited-remotely".into()),
+        ];
+        let mut baseline = baseline(&["edited-remotely"]);
+        baseline.synced = differences.iter().map(|d| d.path().to_string()).collect();
+        let plan = plan(&differences, Some(&baseline), |p| p == "new-locally", None);
+        assert_eq!(plan.push, vec!["new-locally", "deleted-locally"]);
+        assert_eq!(plan.pull, vec!["deleted-remotely", "edited-remotely"]);
+    }
+
+    #[test]
+    fn one_sided_paths_never_synced_are_copied_not_deleted() {
+        let differences = [
+            Difference::LocalOnly("local-new".into()),
+            Difference::RemoteOnly("remote-new".into()),
+        ];
+        let plan = plan(&differences, Some(&baseline(&[])), |_| false, None);
+        assert_eq!(plan.push, vec!["local-new"]);
+        assert_eq!(plan.pull, vec!["remote-new"]);
+    }
+
+    #[test]
+    fn without_baseline_everything_conflicts() {
+        let differences = [modified("a"), Difference::LocalOnly("b".into())];
+        let plan = plan(&differences, None, |_| true, None);
+        assert_eq!(plan.conflicts, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn prefer_resolves_conflicts_only() {
+        let differences = [modified("both"), modified("theirs")];
+        let plan = plan(
+            &differences,
+            Some(&baseline(&["both", "theirs"])),
+            |p| p == "both",
+            Some(Side::Local),
+        );
+        assert_eq!(plan.push, vec!["both"]);
+        assert_eq!(plan.pull, vec!["theirs"]);
+        assert!(plan.conflicts.is_empty());
+    }
+
+    #[test]
+    fn changed_locally_compares_mtime() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("f"), "").unwrap();
+        let now = std::time::SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        assert!(changed_locally(dir.path(), "f", now - 60));
+        assert!(!changed_locally(dir.path(), "f", now + 60));
+        assert!(changed_locally(dir.path(), "missing", now + 60));
+    }
+}
@@ -0,0 +1,393 @@
+//! Local registry of the sessions this machine has started.
+//!
+//! Stored at `~/.relocal/sessions.toml`, next to the user config. `relocal
+//! claude`/`codex` record a session when launching it, `relocal destroy`
+//! removes it, and `relocal sessions` lists what is left. This is purely
+//! local bookkeeping: nothing here talks to the remote, so an entry may
+//! outlive a session destroyed from another machine until it is forgotten
+//! or pruned (see [`prune_stale`]).
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use tracing::warn;
+
+use crate::error::{Error, Result};
+use crate::ssh;
+
+/// One session recorded in the registry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegistryEntry {
+    pub name: String,
+    pub remote: String,
+    pub repo_root: PathBuf,
+    /// Unix timestamp (seconds) of the last launch.
+    pub last_used: u64,
+}
+
+/// The registry file's contents: one entry per `(name, remote)` pair.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Registry {
+    #[serde(default, rename = "session")]
+    pub sessions: Vec<RegistryEntry>,
+}
+
+/// Entries unused for longer than this (30 days) are pruned.
+pub const RETENTION_SECS: u64 = 30 * 86_400;
+
+/// Path to the registry file.
+pub fn registry_path(home: &Path) -> PathBuf {
+    home.join(".relocal").join("sessions.toml")
+}
+
+impl Registry {
+    /// Reads the registry, returning an empty one if the file does not exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.into()),
+        };
+        toml::from_str(&contents).map_err(|e| Error::ConfigParse {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })
+    }
+
+    /// Writes the registry, creating `~/.relocal/` if needed.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = toml::to_string(self).expect("Registry serialization cannot fail");
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Adds or refreshes the entry for `name` on `remote`.
+    pub fn record(&mut self, name: &str, remote: &str, repo_root: &Path, now: u64) {
+        let entry = RegistryEntry {
+            name: name.to_string(),
+            remote: remote.to_string(),
+            repo_root: repo_root.to_path_buf(),
+            last_used: now,
+        };
+        match self
+            .sessions
+            .iter_mut()
+            .find(|e| e.name == name && e.remote == remote)
+        {
+            Some(existing) => *existing = entry,
+            None => self.sessions.push(entry),
+        }
+    }
+
+    /// Removes every entry named `name`, on any remote. Returns how many were
+    /// removed.
+    pub fn forget(&mut self, name: &str) -> usize {
+        let before = self.sessions.len();
+        self.sessions.retain(|e| e.name != name);
+        before - self.sessions.len()
+    }
+
+    /// Removes the entry `name` on `remote`. Returns how many were removed.
+    pub fn forget_on(&mut self, name: &str, remote: &str) -> usize {
+        let before = self.sessions.len();
+        self.sessions
+            .retain(|e| !(e.name == name && e.remote == remote));
+        before - self.sessions.len()
+    }
+
+    /// Renames every entry `old` on `remote` to `new`. Returns how many were
+    /// renamed.
+    pub fn rename(&mut self, old: &str, new: &str, remote: &str) -> usize {
+        let mut renamed = 0;
+        for entry in &mut self.sessions {
+            if entry.name == old && entry.remote == remote {
+                entry.name = new.to_string();
+                renamed += 1;
+            }
+        }
+        renamed
+    }
+
+    /// Entries to prune at `now`: unused for more than `max_age` seconds, or
+    /// whose repo no longer exists. An entry whose daemon socket exists is
+    /// kept, since its daemon may still be running. `exists` reports whether
+    /// a local path exists.
+    pub fn stale(
+        &self,
+        now: u64,
+        max_age: u64,
+        exists: impl Fn(&Path) -> bool,
+    ) -> Vec<RegistryEntry> {
+        self.sessions
+            .iter()
+            .filter(|e| now.saturating_sub(e.last_used) > max_age || !exists(&e.repo_root))
+            .filter(|e| !exists(&ssh::daemon_socket_path(&e.name, &e.remote)))
+            .cloned()
+            .collect()
+    }
+
+    /// Entries ordered by most recently used first.
+    pub fn by_last_used(&self) -> Vec<&RegistryEntry> {
+        let mut entries: Vec<&RegistryEntry> = self.sessions.iter().collect();
+        entries.sort_by_key(|e| std::cmp::Reverse(e.last_used));
+        entries
+    }
+}
+
+/// Records a session launch in the registry under `home`.
+pub fn record_session(home: &Path, name: &str, remote: &str, repo_root: &Path) -> Result<()> {
+    let path = registry_path(home);
+    let mut registry = Registry::load(&path)?;
+    registry.record(name, remote, repo_root, now_secs());
+    registry.save(&path)
+}
+
+/// Removes stale entries (see [`Registry::stale`], with [`RETENTION_SECS`])
+/// from the registry under `home`, together with their daemon log and lock
+/// files. Purely local: the remote is never asked. Returns the pruned
+/// entries.
+pub fn prune_stale(home: &Path, now: u64) -> Result<Vec<RegistryEntry>> {
+    let path = registry_path(home);
+    let mut registry = Registry::load(&path)?;
+    let stale = registry.stale(now, RETENTION_SECS, Path::exists);
+    if stale.is_empty() {
+        return Ok(stale);
+    }
+    registry.sessions.retain(|e| !stale.contains(e));
+    registry.save(&path)?;
+    for entry in &stale {
+        for file in [
+            ssh::daemon_flock_path(&entry.name, &entry.remote),
+            ssh::daemon_log_path(&entry.name, &entry.remote),
+        ] {
+            match std::fs::remove_file(&file) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => warn!("failed to remove {}: {e}", file.display()),
+            }
+        }
+    }
+    Ok(stale)
+}
+
+/// Current Unix time in seconds.
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Removes `name` from the registry under `home`. Returns how many entries
+/// were removed.
+pub fn forget_session(home: &Path, name: &str) -> Result<usize> {
+    let path = registry_path(home);
+    let mut registry = Registry::load(&path)?;
+    let removed = registry.forget(name);
+    if removed > 0 {
+        registry.save(&path)?;
+    }
+    Ok(removed)
+}
+
+/// Removes session `name` on `remote` from the registry under `home`,
+/// leaving same-named sessions on other remotes alone.
+pub fn forget_remote_session(home: &Path, name: &str, remote: &str) -> Result<()> {
+    let path = registry_path(home);
+    let mut registry = Registry::load(&path)?;
+    if registry.forget_on(name, remote) > 0 {
+        registry.save(&path)?;
+    }
+    Ok(())
+}
+
+/// Renames session `old` on `remote` to `new` in the registry under `home`.
+pub fn rename_session(home: &Path, old: &str, new: &str, remote: &str) -> Result<()> {
+    let path = registry_path(home);
+    let mut registry = Registry::load(&path)?;
+    if registry.rename(old, new, remote) > 0 {
+        registry.save(&path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn load_missing_is_empty() {
+        let home = TempDir::new().unwrap();
+        let registry = Registry::load(&registry_path(home.path())).unwrap();
+        assert!(registry.sessions.is_empty());
+    }
+
+    #[test]
+    fn record_save_load_roundtrip() {
+        let home = TempDir::new().unwrap();
+        record_session(home.path(), "s1", "user@host", Path::new("/work/api")).unwrap();
+
+        let registry = Registry::load(&registry_path(home.path())).unwrap();
+        assert_eq!(registry.sessions.len(), 1);
+        let entry = &registry.sessions[0];
+        assert_eq!(entry.name, "s1");
+        assert_eq!(entry.remote, "user@host");
+        assert_eq!(entry.repo_root, Path::new("/work/api"));
+        assert!(entry.last_used > 0);
+    }
+
+    #[test]
+    fn record_refreshes_existing_entry() {
+        let mut registry = Registry::default();
+        registry.record("s1", "user@host", Path::new("/old"), 1);
+        registry.record("s1", "user@host", Path::new("/new"), 2);
+        registry.record("s1", "other@host", Path::new("/new"), 3);
+
+        assert_eq!(registry.sessions.len(), 2);
+        assert_eq!(registry.sessions[0].repo_root, Path::new("/new"));
+        assert_eq!(registry.sessions[0].last_used, 2);
+    }
+
+    #[test]
+    fn forget_removes_name_on_every_remote() {
+        let home = TempDir::new().unwrap();
+        record_session(home.path(), "s1", "a@host", Path::new("/r")).unwrap();
+        record_session(home.path(), "s1", "b@host", Path::new("/r")).unwrap();
+        record_session(home.path(), "s2", "a@host", Path::new("/r")).unwrap();
+
+        assert_eq!(forget_session(home.path(), "s1").unwrap(), 2);
+        assert_eq!(forget_session(home.path(), "s1").unwrap(), 0);
+
+        let registry = Registry::load(&registry_path(home.path())).unwrap();
+        let names: Vec<&str> = registry.sessions.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["s2"]);
+    }
+
+    #[test]
+    fn forget_remote_session_only_touches_the_given_remote() {
+        let home = TempDir::new().unwrap();
+        record_session(home.path(), "s1", "a@host", Path::new("/r")).unwrap();
+        record_session(home.path(), "s1", "b@host", Path::new("/r")).unwrap();
+
+        forget_remote_session(home.path(), "s1", "a@host").unwrap();
+
+        let registry = Registry::load(&registry_path(home.path())).unwrap();
+        assert_eq!(registry.sessions.len(), 1);
+        assert_eq!(registry.sessions[0].remote, "b@host");
+    }
+
+    #[test]
+    fn rename_only_touches_the_given_remote() {
+        let home = TempDir::new().unwrap();
+        record_session(home.path(), "s1", "a@host", Path::new("/r")).unwrap();
+        record_session(home.path(), "s1", "b@host", Path::new("/r")).unwrap();
+
+        rename_session(home.path(), "s1", "s2", "a@host").unwrap();
+
+        let registry = Registry::load(&registry_path(home.path())).unwrap();
+        let entries: Vec<(&str, &str)> = registry
+            .sessions
+            .iter()
+            .map(|e| (e.name.as_str(), e.remote.as_str()))
+            .collect();
+        assert_eq!(entries, vec![("s2", "a@host"), ("s1", "b@host")]);
+    }
+
+    #[test]
+    fn by_last_used_is_most_recent_first() {
+        let mut registry = Registry::default();
+        registry.record("old", "u@h", Path::new("/r"), 10);
+        registry.record("new", "u@h", Path::new("/r"), 20);
+        let names: Vec<&str> = registry
+            .by_last_used()
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["new", "old"]);
+    }
+
+    fn entry(name: &str, repo_root: &str, last_used: u64) -> RegistryEntry {
+        RegistryEntry {
+            name: name.to_string(),
+            remote: "u@h".to_string(),
+            repo_root: PathBuf::from(repo_root),
+            last_used,
+        }
+    }
+
+    #[test]
+    fn stale_selects_old_or_orphaned_entries() {
+        let day = 86_400;
+        let now = 100 * day;
+        let registry = Registry {
+            sessions: vec![
+                entry("fresh", "/repos/a", now - day),
+                entry("old", "/repos/a", now - 31 * day),
+                entry("borderline", "/repos/a", now - 30 * day),
+                entry("orphaned", "/repos/gone", now),
+            ],
+        };
+        let names: Vec<String> = registry
+            .stale(now, RETENTION_SECS, |p| p == Path::new("/repos/a"))
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        assert_eq!(names, vec!["old", "orphaned"]);
+    }
+
+    #[test]
+    fn stale_keeps_sessions_with_a_daemon_socket() {
+        let registry = Registry {
+            sessions: vec![entry("running", "/repos/gone", 0)],
+        };
+        let socket = ssh::daemon_socket_path("running", "u@h");
+        assert!(registry
+            .stale(u64::MAX, RETENTION_SECS, |p| p == socket)
+            .is_empty());
+        assert_eq!(registry.stale(u64::MAX, RETENTION_SECS, |_| false).len(), 1);
+    }
+
+    #[test]
+    fn prune_stale_rewrites_registry() {
+        let home = TempDir::new().unwrap();
+        let repo = TempDir::new().unwrap();
+        let path = registry_path(home.path());
+        let now = 1_000 * 86_400;
+        let mut registry = Registry::default();
+        registry.record("keep", "u@h", repo.path(), now);
+        registry.record("old", "u@h", repo.path(), now - RETENTION_SECS - 1);
+        registry.record("moved", "u@h", Path::new("/nonexistent/relocal-repo"), now);
+        registry.save(&path).unwrap();
+
+        let pruned: Vec<String> = prune_stale(home.path(), now)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        assert_eq!(pruned, vec!["old", "moved"]);
+        let names: Vec<String> = Registry::load(&path)
+            .unwrap()
+            .sessions
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        assert_eq!(names, vec!["keep"]);
+        assert!(prune_stale(home.path(), now).unwrap().is_empty());
+    }
+
+    #[test]
+    fn load_parse_error_names_file() {
+        let home = TempDir::new().unwrap();
+        let path = registry_path(home.path());
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "session = 3").unwrap();
+        let err = Registry::load(&path).unwrap_err();
+        assert!(err.to_string().contains("sessions.toml"), "{err}");
+    }
+}
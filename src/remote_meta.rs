@@ -0,0 +1,263 @@
+//! Remote metadata files: the remote-wide compatibility record
+//! (`~/relocal/.relocal-meta.json`) and per-session origin records
+//! (`~/relocal/<session>/.relocal/origin.json`).
+//!
+//! Several people on different relocal versions may share one remote.
+//! `relocal remote install` records which relocal version set the remote up
+//! and which protocol version its on-remote layout follows. Session setup
+//! reads the file back and refuses to run if the remote was prepared by a
+//! newer protocol than this binary understands. A missing file means the
+//! remote predates the metadata and is treated as compatible.
+//!
+//! Each session also records where it was started from, so `relocal list
+//! --detail` can show it even when run from a different checkout or config.
+//! The record lives under `.relocal/`, which rsync never transfers.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::runner::CommandRunner;
+use crate::ssh;
+
+/// Version of the on-remote layout this binary reads and writes. Bump when a
+/// change would break older binaries operating on the same remote.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Contents of the remote metadata file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteMeta {
+    /// relocal version (`CARGO_PKG_VERSION`) that last ran `remote install`.
+    pub installed_version: String,
+    pub protocol_version: u32,
+}
+
+impl RemoteMeta {
+    /// Metadata describing this binary.
+    pub fn current() -> Self {
+        Self {
+            installed_version: env!("CARGO_PKG_VERSION").to_string(),
+            protocol_version: PROTOCOL_VERSION,
+        }
+    }
+}
+
+/// Reads the remote metadata file. Returns `None` if it has never been written.
+pub fn read(runner: &dyn CommandRunner, remote: &str) -> Result<Option<RemoteMeta>> {
+    let output = runner
+        .run_ssh(remote, &ssh::read_meta_file())?
+        .check("read remote metadata")?;
+    let contents = output.stdout.trim();
+    if contents.is_empty() {
+        return Ok(None);
+    }
+    serde_json::from_str(contents)
+        .map(Some)
+        .map_err(|e| Error::Remote {
+            remote: remote.to_string(),
+            message: format!(
+                "malformed ~/relocal/.relocal-meta.json ({e}). Remove it and run `relocal remote install`."
+            ),
+        })
+}
+
+/// Writes [`RemoteMeta::current`] to the remote.
+pub fn write(runner: &dyn CommandRunner, remote: &str) -> Result<()> {
+    let json = serde_json::to_string(&RemoteMeta::current())
+        .expect("RemoteMeta serialization cannot fail");
+    runner
+        .run_ssh(remote, &ssh::write_meta_file(&json))?
+        .check("write remote metadata")?;
+    Ok(())
+}
+
+/// Decides whether this binary may operate on a remote with the given
+/// metadata. Only a newer protocol is refused; older remotes are upgraded in
+/// place by the next `relocal remote install`.
+pub fn check_compat(remote: &str, meta: Option<&RemoteMeta>) -> Result<()> {
+    match meta {
+        Some(meta) if meta.protocol_version > PROTOCOL_VERSION => Err(Error::IncompatibleRemote {
+            remote: remote.to_string(),
+            installed_version: meta.installed_version.clone(),
+            remote_protocol: meta.protocol_version,
+            local_protocol: PROTOCOL_VERSION,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Reads the remote metadata and applies [`check_compat`].
+pub fn ensure_compatible(runner: &dyn CommandRunner, remote: &str) -> Result<()> {
+    let meta = read(runner, remote)?;
+    check_compat(remote, meta.as_ref())
+}
+
+/// Where a session was started from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionOrigin {
+    /// The `remote` setting the session was started with.
+    pub remote: String,
+    /// Local repo root that was synced into the session.
+    pub local_path: String,
+}
+
+/// Records the origin of `session_name` on the remote.
+pub fn write_origin(
+    runner: &dyn CommandRunner,
+    remote: &str,
+    session_name: &str,
+    repo_root: &Path,
+) -> Result<()> {
+    runner
+        .run_ssh(
+            remote,
+            &ssh::write_origin_file(session_name, &origin_json(remote, repo_root)),
+        )?
+        .check("write session origin")?;
+    Ok(())
+}
+
+/// The origin record of a session on `remote` synced from `repo_root`.
+pub fn origin_json(remote: &str, repo_root: &Path) -> String {
+    let origin = SessionOrigin {
+        remote: remote.to_string(),
+        local_path: repo_root.to_string_lossy().into_owned(),
+    };
+    serde_json::to_string(&origin).expect("SessionOrigin serialization cannot fail")
+}
+
+/// Parses an origin record. Empty or malformed input yields `None`; a bad
+/// record should never make `list` fail.
+pub fn parse_origin(contents: &str) -> Option<SessionOrigin> {
+    serde_json::from_str(contents.trim()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{Invocation, MockResponse, MockRunner};
+
+    fn meta(protocol_version: u32) -> RemoteMeta {
+        RemoteMeta {
+            installed_version: "9.9.9".to_string(),
+            protocol_version,
+        }
+    }
+
+    #[test]
+    fn compat_missing_meta_is_allowed() {
+        assert!(check_compat("h", None).is_ok());
+    }
+
+    #[test]
+    fn compat_same_protocol_is_allowed() {
+        assert!(check_compat("h", Some(&meta(PROTOCOL_VERSION))).is_ok());
+    }
+
+    #[test]
+    fn compat_older_protocol_is_allowed() {
+        assert!(check_compat("h", Some(&meta(PROTOCOL_VERSION - 1))).is_ok());
+    }
+
+    #[test]
+    fn compat_newer_protocol_is_refused_with_upgrade_hint() {
+        let err = check_compat("h", Some(&meta(PROTOCOL_VERSION + 1))).unwrap_err();
+        assert!(matches!(err, Error::IncompatibleRemote { .. }));
+        let msg = err.to_string();
+        assert!(msg.contains("9.9.9"));
+        assert!(msg.contains("upgrade relocal"));
+    }
+
+    #[test]
+    fn meta_roundtrips_through_json() {
+        let json = serde_json::to_string(&RemoteMeta::current()).unwrap();
+        let parsed: RemoteMeta = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, RemoteMeta::current());
+    }
+
+    #[test]
+    fn read_absent_file_returns_none() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new()));
+        assert_eq!(read(&mock, "h").unwrap(), None);
+    }
+
+    #[test]
+    fn read_parses_existing_file() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(
+            "{\"installed_version\":\"0.2.0\",\"protocol_version\":3}\n".into(),
+        ));
+        let meta = read(&mock, "h").unwrap().unwrap();
+        assert_eq!(meta.installed_version, "0.2.0");
+        assert_eq!(meta.protocol_version, 3);
+    }
+
+    #[test]
+    fn read_malformed_file_returns_error() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok("not json".into()));
+        let err = read(&mock, "h").unwrap_err().to_string();
+        assert!(err.contains("malformed"));
+    }
+
+    #[test]
+    fn read_ssh_failure_returns_error() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Fail("permission denied".into()));
+        assert!(read(&mock, "h").is_err());
+    }
+
+    #[test]
+    fn write_sends_current_meta() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new()));
+        write(&mock, "h").unwrap();
+        match &mock.invocations()[0] {
+            Invocation::Ssh { command, .. } => {
+                assert!(command.contains(".relocal-meta.json"));
+                assert!(command.contains("protocol_version"));
+            }
+            other => panic!("expected Ssh, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn write_failure_returns_error() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Fail("disk full".into()));
+        assert!(write(&mock, "h").is_err());
+    }
+
+    #[test]
+    fn write_origin_records_remote_and_path() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new()));
+        write_origin(&mock, "user@host", "s1", Path::new("/home/u/proj")).unwrap();
+        match &mock.invocations()[0] {
+            Invocation::Ssh { remote, command } => {
+                assert_eq!(remote, "user@host");
+                assert!(command.contains("~/relocal/s1/.relocal/origin.json"));
+                assert!(command.contains("/home/u/proj"));
+            }
+            other => panic!("expected Ssh, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn write_origin_failure_returns_error() {
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Fail("read-only file system".into()));
+        assert!(write_origin(&mock, "h", "s1", Path::new("/p")).is_err());
+    }
+
+    #[test]
+    fn parse_origin_roundtrip_and_garbage() {
+        let origin = parse_origin(r#"{"remote":"h","local_path":"/p"}"#).unwrap();
+        assert_eq!(origin.remote, "h");
+        assert_eq!(origin.local_path, "/p");
+        assert_eq!(parse_origin(""), None);
+        assert_eq!(parse_origin("{broken"), None);
+    }
+}
@@ -5,8 +5,10 @@
 //! be thoroughly unit-tested. The caller passes the resulting [`RsyncParams`] to
 //! [`CommandRunner::run_rsync`].
 
+use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
 
+use crate::checksum::matches_prefix;
 use crate::config::Config;
 use crate::ssh::remote_work_dir;
 
@@ -17,6 +19,81 @@ pub enum Direction {
     Pull,
 }
 
+/// Per-invocation sync behavior selected on the command line.
+///
+/// Config-file settings live on [`Config`]; this carries the flags that vary
+/// between individual `relocal sync` runs (and the daemon's verbosity).
+#[derive(Debug, Clone, Default)]
+pub struct SyncOptions {
+    pub verbose: bool,
+    /// Run rsync with `--dry-run --itemize-changes` and report every change
+    /// it would make.
+    pub dry_run: bool,
+    /// Run rsync with `--dry-run --itemize-changes` and report only the
+    /// deletions it would perform.
+    pub delete_dry_run: bool,
+    /// After a push, compare checksum manifests of both sides
+    /// (see [`crate::checksum`]). Ignored by pull.
+    pub checksum_verify: bool,
+    /// Keep partially transferred files in [`PARTIAL_DIR`] so an interrupted
+    /// sync resumes instead of restarting them.
+    pub resume: bool,
+    /// Push without `--delete` (also enabled by the `push_no_delete` config).
+    /// Ignored by pull.
+    pub no_delete: bool,
+    /// Pull into this directory instead of the repo root. Ignored by push.
+    pub local_path: Option<PathBuf>,
+    /// Skip the repo-root check on the pull destination (see
+    /// [`RsyncParams::force`]). Only meant for scratch `local_path`s.
+    pub force: bool,
+    /// Skip files that are newer on the receiver (`--update`) and never
+    /// delete. Set by `relocal sync --bidirectional-safe`.
+    pub update: bool,
+    /// Before a push, warn about local paths that differ only in case (see
+    /// [`crate::case_check`]). Ignored by pull.
+    pub check_case: bool,
+    /// Don't cross filesystem boundaries (`-x`, also enabled by the
+    /// `one_file_system` config).
+    pub one_file_system: bool,
+    /// Compare files by checksum instead of mtime and size (`-c`, also
+    /// enabled by the `checksum` config).
+    pub checksum: bool,
+    /// rsync `--include-from` file (`sync --include-from`), ranked with
+    /// `always_include` ahead of `.gitignore`.
+    pub include_from: Option<PathBuf>,
+    /// rsync `--exclude-from` file (`sync --exclude-from`), applied after the
+    /// config's `exclude_from`.
+    pub exclude_from: Option<PathBuf>,
+    /// Print the transfer stats as JSON on stdout after the sync.
+    pub stats_json: bool,
+    /// Run rsync with `--itemize-changes` and log a
+    /// [`ChangeSummary`] of what changed. Used by the daemon's final pull.
+    pub itemize: bool,
+    /// Sync only these paths (relative to the repo root), deleting any that
+    /// are missing on the sender. Empty means the whole tree. Set by the
+    /// reconciling `relocal sync` (see [`crate::reconcile`]). A partial sync
+    /// skips recording `.relocal/state.json`.
+    pub only_paths: Vec<String>,
+    /// Remote reference tree for rsync `--compare-dest` (`sync push
+    /// --compare-dest`): files identical to it are neither transferred nor
+    /// created. A relative path is relative to the session's working
+    /// directory. Ignored by pull.
+    pub compare_dest: Option<String>,
+    /// A periodic pull by the daemon or sidecar rather than a sync the user
+    /// ran; skips recording `.relocal/state.json`.
+    pub background: bool,
+}
+
+/// Directory (relative to each transferred file's directory) where rsync
+/// keeps partial files when [`SyncOptions::resume`] is set. rsync removes it
+/// once empty after a successful transfer.
+pub const PARTIAL_DIR: &str = ".rsync-partial";
+
+/// `--chmod` applied when `preserve_perms = false` and no `chmod` is
+/// configured: owner and group read/write, others read, and execute kept for
+/// directories and for files that are executable by anyone.
+pub const DEFAULT_CHMOD: &str = "ug=rwX,o=rX";
+
 /// Structured rsync invocation carrying both the argument list and metadata
 /// needed for safety validation before execution.
 ///
@@ -31,6 +108,8 @@ pub struct RsyncParams {
     args: Vec<String>,
     direction: Direction,
     local_path: PathBuf,
+    force: bool,
+    custom_rsh: bool,
 }
 
 impl RsyncParams {
@@ -46,6 +125,17 @@ impl RsyncParams {
         &self.local_path
     }
 
+    /// When set, a pull skips the check that `local_path` is a repo root.
+    pub fn force(&self) -> bool {
+        self.force
+    }
+
+    /// Whether `args` already pick the remote shell (`rsync_rsh`), so the
+    /// runner must not inject its own `-e ssh ...`.
+    pub fn custom_rsh(&self) -> bool {
+        self.custom_rsh
+    }
+
     /// Test-only constructor for unit tests that need to exercise
     /// [`CommandRunner::run_rsync`](crate::runner::CommandRunner) directly.
     #[cfg(test)]
@@ -54,49 +144,101 @@ impl RsyncParams {
             args,
             direction,
             local_path,
+            force: false,
+            custom_rsh: false,
         }
     }
+
+    /// Test-only: marks the params as `--force`d.
+    #[cfg(test)]
+    pub fn forced(mut self) -> Self {
+        self.force = true;
+        self
+    }
 }
 
 /// Builds the complete rsync argument list for a sync operation.
 ///
 /// The `.claude/` directory is excluded entirely — the remote manages its own
-/// `.claude/` independently.
+/// `.claude/` independently. relocal's own `.relocal/` metadata directory is
+/// likewise always excluded so internal bookkeeping never crosses sides. The
+/// include/exclude rules come from [`FilterSet`].
 pub fn build_rsync_args(
     config: &Config,
     direction: Direction,
     session_name: &str,
     repo_root: &Path,
-    verbose: bool,
+    options: &SyncOptions,
 ) -> RsyncParams {
     let mut args = vec![
         // Base flags
         "-az".to_string(),
-        "--delete".to_string(),
-        // relocal.toml is intentionally local-only: never transfer it and never
-        // let --delete remove it on the destination. See the integration test
-        // `pull_keeps_gitignored_relocal_toml_across_repeated_pulls` for context.
-        "--exclude=/relocal.toml".to_string(),
-        "--filter=P /relocal.toml".to_string(),
-        // Respect .gitignore at every directory level
-        "--filter=:- .gitignore".to_string(),
     ];
+    if let Some(rsh) = &config.rsync_rsh {
+        args.push("-e".to_string());
+        args.push(rsh.clone());
+    }
+    // `-a` copies the source's permissions; `--chmod` is applied on top of
+    // them, and an explicit `chmod` wins over the `preserve_perms` default.
+    if let Some(chmod) = &config.chmod {
+        args.push(format!("--chmod={chmod}"));
+    } else if !config.preserve_perms {
+        args.push(format!("--chmod={DEFAULT_CHMOD}"));
+    }
+    if !skips_delete(config, direction, options) {
+        args.push("--delete".to_string());
+    }
+    args.extend(FilterSet::new(config, repo_root, options).args());
 
-    // User-configured exclusions
-    for pattern in &config.exclude {
-        args.push(format!("--exclude={pattern}"));
+    if let Some(bwlimit) = &config.bwlimit {
+        args.push(format!("--bwlimit={bwlimit}"));
     }
 
-    // Exclude .claude/ entirely — remote manages its own independently.
-    args.push("--exclude=.claude/".to_string());
+    if options.update {
+        args.push("--update".to_string());
+    }
+
+    if options.one_file_system || config.one_file_system {
+        args.push("--one-file-system".to_string());
+    }
 
-    // Verbose mode adds progress
-    if verbose {
-        args.push("--progress".to_string());
+    if options.checksum || config.checksum {
+        args.push("-c".to_string());
     }
 
-    // Source and destination (trailing slash ensures contents are synced)
-    let local_path = format!("{}/", repo_root.display());
+    if let (Direction::Push, Some(dir)) = (direction, &options.compare_dest) {
+        args.push(format!("--compare-dest={dir}"));
+    }
+
+    // Verbose mode adds the configured flags (`--progress` by default)
+    if options.verbose {
+        args.extend(config.verbose_rsync_flags.iter().cloned());
+    }
+
+    // Transfer summary, parsed by `parse_rsync_stats`.
+    args.push("--stats".to_string());
+
+    if options.resume {
+        args.push("--partial".to_string());
+        args.push(format!("--partial-dir={PARTIAL_DIR}"));
+    }
+
+    if options.dry_run || options.delete_dry_run {
+        args.push("--dry-run".to_string());
+    }
+    let itemize = options.dry_run || options.delete_dry_run || options.itemize;
+    // `verbose_rsync_flags` may already ask for it.
+    if itemize && !args.iter().any(|a| a == "--itemize-changes") {
+        args.push("--itemize-changes".to_string());
+    }
+
+    // Source and destination (trailing slash ensures contents are synced).
+    // `exclude_from` above stays relative to the repo root either way.
+    let local_dir = match (direction, &options.local_path) {
+        (Direction::Pull, Some(path)) => path.as_path(),
+        _ => repo_root,
+    };
+    let local_path = format!("{}/", local_dir.display());
     let remote_path = format!("{}:{}/", config.remote, remote_work_dir(session_name));
 
     match direction {
@@ -113,10 +255,474 @@ pub fn build_rsync_args(
     RsyncParams {
         args,
         direction,
-        local_path: repo_root.to_path_buf(),
+        local_path: local_dir.to_path_buf(),
+        force: options.force,
+        custom_rsh: config.rsync_rsh.is_some(),
+    }
+}
+
+/// Where a filter rule comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterSource {
+    /// Rules relocal always applies.
+    Builtin,
+    /// The reconciling `relocal sync` path list ([`SyncOptions::only_paths`]).
+    OnlyPaths,
+    /// The `always_include` config.
+    AlwaysInclude,
+    /// `sync --include-from`.
+    IncludeFromFlag,
+    /// `.gitignore` files at every directory level.
+    Gitignore,
+    /// The `exclude` config.
+    Exclude,
+    /// The `exclude_from` config.
+    ExcludeFrom,
+    /// `sync --exclude-from`.
+    ExcludeFromFlag,
+    /// `sync --resume`.
+    Resume,
+}
+
+impl FilterSource {
+    /// Label shown by `--show-filters`.
+    pub fn label(self) -> &'static str {
+        match self {
+            FilterSource::Builtin => "built-in",
+            FilterSource::OnlyPaths => "sync path list",
+            FilterSource::AlwaysInclude => "always_include",
+            FilterSource::IncludeFromFlag => "--include-from",
+            FilterSource::Gitignore => ".gitignore",
+            FilterSource::Exclude => "exclude",
+            FilterSource::ExcludeFrom => "exclude_from",
+            FilterSource::ExcludeFromFlag => "--exclude-from",
+            FilterSource::Resume => "--resume",
+        }
     }
 }
 
+/// One rsync filter argument and where it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterRule {
+    pub arg: String,
+    pub source: FilterSource,
+}
+
+/// Every include/exclude rule of a sync, in the order rsync sees them.
+///
+/// rsync applies the first rule that matches a path, so earlier rules win:
+/// the built-in `relocal.toml` protection and `.claude/` and `.relocal/`
+/// exclusions (which nothing can override), then a reconciling sync's path
+/// list, then `always_include` and `--include-from`, then `.gitignore`, and
+/// finally the configured and command-line exclusions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FilterSet {
+    rules: Vec<FilterRule>,
+}
+
+impl FilterSet {
+    /// Collects the rules for a sync of `repo_root`; `exclude_from` is
+    /// relative to it.
+    pub fn new(config: &Config, repo_root: &Path, options: &SyncOptions) -> Self {
+        let mut set = FilterSet::default();
+        // relocal.toml is intentionally local-only: never transfer it and never
+        // let --delete remove it on the destination. See the integration test
+        // `pull_keeps_gitignored_relocal_toml_across_repeated_pulls` for context.
+        set.push(FilterSource::Builtin, "--exclude=/relocal.toml");
+        set.push(FilterSource::Builtin, "--filter=P /relocal.toml");
+        // Exclude .claude/ entirely — remote manages its own independently.
+        set.push(FilterSource::Builtin, "--exclude=.claude/");
+        // Exclude relocal's own metadata unconditionally, regardless of user config.
+        set.push(FilterSource::Builtin, "--exclude=.relocal/");
+
+        // A path list must also beat always_include, or an always-included path
+        // left out of the list would still be synced.
+        if !options.only_paths.is_empty() {
+            for rule in only_paths_rules(&options.only_paths) {
+                set.push(FilterSource::OnlyPaths, rule);
+            }
+        }
+
+        // always_include overrides everything after it, but not the built-in
+        // rules above: rsync applies the first rule that matches.
+        for pattern in &config.always_include {
+            set.push(FilterSource::AlwaysInclude, format!("--include={pattern}"));
+        }
+        if let Some(path) = &options.include_from {
+            set.push(
+                FilterSource::IncludeFromFlag,
+                format!("--include-from={}", path.display()),
+            );
+        }
+
+        // Respect .gitignore at every directory level
+        set.push(FilterSource::Gitignore, "--filter=:- .gitignore");
+
+        for pattern in &config.exclude {
+            set.push(FilterSource::Exclude, format!("--exclude={pattern}"));
+        }
+        if let Some(path) = config.exclude_from_path(repo_root) {
+            set.push(
+                FilterSource::ExcludeFrom,
+                format!("--exclude-from={}", path.display()),
+            );
+        }
+        if let Some(path) = &options.exclude_from {
+            set.push(
+                FilterSource::ExcludeFromFlag,
+                format!("--exclude-from={}", path.display()),
+            );
+        }
+
+        if options.resume {
+            // rsync adds an implicit exclude for a relative partial-dir; make it
+            // explicit so leftovers from an interrupted run never cross sides.
+            set.push(FilterSource::Resume, format!("--exclude={PARTIAL_DIR}/"));
+        }
+        set
+    }
+
+    fn push(&mut self, source: FilterSource, arg: impl Into<String>) {
+        self.rules.push(FilterRule {
+            arg: arg.into(),
+            source,
+        });
+    }
+
+    pub fn rules(&self) -> &[FilterRule] {
+        &self.rules
+    }
+
+    /// The rsync arguments, in order.
+    pub fn args(&self) -> impl Iterator<Item = String> + '_ {
+        self.rules.iter().map(|rule| rule.arg.clone())
+    }
+
+    /// The same rules as a predicate over repo-relative file paths, for the
+    /// checks that enumerate files themselves (see [`PathFilter`]).
+    pub fn path_filter(&self) -> PathFilter {
+        let mut filter = PathFilter::default();
+        for rule in &self.rules {
+            if let Some(pattern) = rule.arg.strip_prefix("--include=") {
+                filter.rules.push((true, pattern.to_string()));
+            } else if let Some(pattern) = rule.arg.strip_prefix("--exclude=") {
+                filter.rules.push((false, pattern.to_string()));
+            } else if let Some(path) = rule.arg.strip_prefix("--include-from=") {
+                filter.read_rules_file(Path::new(path), true);
+            } else if let Some(path) = rule.arg.strip_prefix("--exclude-from=") {
+                filter.read_rules_file(Path::new(path), false);
+            } else if rule.source == FilterSource::Gitignore {
+                filter.gitignore_at = Some(filter.rules.len());
+            }
+        }
+        filter
+    }
+
+    /// One line per rule, e.g. `--exclude=.env  (exclude)`, for
+    /// `--show-filters`.
+    pub fn describe(&self) -> Vec<String> {
+        let width = self.rules.iter().map(|r| r.arg.len()).max().unwrap_or(0);
+        self.rules
+            .iter()
+            .map(|rule| format!("{:width$}  ({})", rule.arg, rule.source.label()))
+            .collect()
+    }
+}
+
+/// Whether rsync would transfer a file, decided from a [`FilterSet`].
+///
+/// `.gitignore` is not evaluated: callers enumerate files with
+/// `git ls-files --exclude-standard`, which already applies it. Like rsync,
+/// each directory on the way down and then the file itself is tested
+/// against the first rule that matches it; the file is excluded if any of
+/// them hits an exclude.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathFilter {
+    /// `(include, pattern)` in rsync order.
+    rules: Vec<(bool, String)>,
+    /// How many of `rules` rsync checks before `.gitignore`.
+    gitignore_at: Option<usize>,
+}
+
+impl PathFilter {
+    /// Adds the patterns of an `--include-from`/`--exclude-from` file.
+    /// Blank and comment lines are skipped, and `+ `/`- ` prefixes override
+    /// the file's kind as they do in rsync. An unreadable file adds nothing;
+    /// syncs check that filter files exist before running.
+    fn read_rules_file(&mut self, path: &Path, include: bool) {
+        let Ok(contents) = std::fs::read_to_string(path) else {
+            return;
+        };
+        for line in contents.lines() {
+            if line.trim().is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            let rule = match (line.strip_prefix("+ "), line.strip_prefix("- ")) {
+                (Some(pattern), _) => (true, pattern),
+                (_, Some(pattern)) => (false, pattern),
+                _ => (include, line),
+            };
+            self.rules.push((rule.0, rule.1.to_string()));
+        }
+    }
+
+    /// Whether the repo-relative file `path` is left out of the sync.
+    pub fn is_excluded(&self, path: &str) -> bool {
+        let components: Vec<&str> = path.split('/').collect();
+        (1..=components.len()).any(|end| {
+            self.rules
+                .iter()
+                .find(|(_, pattern)| matches_prefix(pattern, &components, end))
+                .is_some_and(|(include, _)| !include)
+        })
+    }
+
+    /// Whether a rule rsync checks before `.gitignore` (`always_include`,
+    /// `--include-from`) includes `path` or a directory above it, so
+    /// `.gitignore` cannot leave it out.
+    pub fn is_included_before_gitignore(&self, path: &str) -> bool {
+        let rules = &self.rules[..self.gitignore_at.unwrap_or(self.rules.len())];
+        let components: Vec<&str> = path.split('/').collect();
+        (1..=components.len()).any(|end| {
+            rules
+                .iter()
+                .find(|(_, pattern)| matches_prefix(pattern, &components, end))
+                .is_some_and(|(include, _)| *include)
+        })
+    }
+}
+
+/// Filter rules restricting a sync to `paths` (see [`SyncOptions::only_paths`]).
+///
+/// Each path and its parent directories are included by anchored rules and
+/// everything else is excluded. Excluded names are never deleted, so
+/// `--delete` only removes listed paths that are missing on the sender.
+/// Paths containing wildcard characters have them backslash-escaped.
+pub fn only_paths_rules(paths: &[String]) -> Vec<String> {
+    let mut included = BTreeSet::new();
+    for path in paths {
+        let mut end = 0;
+        while let Some(offset) = path[end..].find('/') {
+            end += offset + 1;
+            included.insert(escape_pattern(&path[..end]));
+        }
+        included.insert(escape_pattern(path));
+    }
+    let mut rules: Vec<String> = included
+        .into_iter()
+        .map(|pattern| format!("--include=/{pattern}"))
+        .collect();
+    rules.push("--exclude=*".to_string());
+    rules
+}
+
+/// Escapes rsync's wildcard characters so `path` matches only itself.
+/// rsync only honours backslash escapes in patterns that contain wildcards,
+/// so paths without any are left alone.
+fn escape_pattern(path: &str) -> String {
+    if !path.contains(['*', '?', '[']) {
+        return path.to_string();
+    }
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        if matches!(c, '*' | '?' | '[' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Whether this sync runs without `--delete`: pushes when requested by flag
+/// or config, and both directions in [`SyncOptions::update`] mode.
+pub fn skips_delete(config: &Config, direction: Direction, options: &SyncOptions) -> bool {
+    options.update || (direction == Direction::Push && (options.no_delete || config.push_no_delete))
+}
+
+/// Extracts the paths rsync reports it would delete from `--itemize-changes`
+/// output.
+///
+/// Itemized deletions look like `*deleting   path/to/file`; plain verbose
+/// output uses `deleting path/to/file`. Both forms are accepted. All other
+/// lines (transfers, stats, progress) are ignored.
+pub fn parse_deletions(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_start();
+            line.strip_prefix("*deleting")
+                .or_else(|| line.strip_prefix("deleting "))
+                .map(|path| path.trim().to_string())
+        })
+        .filter(|path| !path.is_empty())
+        .collect()
+}
+
+/// Counts of changed paths in `--itemize-changes` output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChangeSummary {
+    /// Existing files whose content was transferred.
+    pub updated: u64,
+    /// Newly created files, directories and links.
+    pub added: u64,
+    pub deleted: u64,
+}
+
+impl ChangeSummary {
+    /// One-line summary, e.g. `12 files updated, 3 added, 1 deleted`.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} files updated, {} added, {} deleted",
+            self.updated, self.added, self.deleted
+        )
+    }
+}
+
+/// Counts the changes in `--itemize-changes` output.
+///
+/// Deletions are found by [`parse_deletions`]. Other itemized lines start
+/// with a `YXcstpoguax` code (see [`is_itemize_code`]): all `+` attributes
+/// mean a new path, and a transferred (`<`/`>`) file is an update.
+/// Attribute-only changes and non-itemized lines are ignored.
+pub fn parse_change_summary(output: &str) -> ChangeSummary {
+    let mut summary = ChangeSummary {
+        deleted: parse_deletions(output).len() as u64,
+        ..Default::default()
+    };
+    for line in output.lines() {
+        let Some((code, _path)) = line.split_once(' ') else {
+            continue;
+        };
+        let mut chars = code.chars();
+        let (Some(kind), Some(file_type)) = (chars.next(), chars.next()) else {
+            continue;
+        };
+        if !is_itemize_code(code) || !"<>ch.".contains(kind) || !"fdLDS".contains(file_type) {
+            continue;
+        }
+        if code[2..].chars().all(|c| c == '+') {
+            summary.added += 1;
+        } else if matches!(kind, '<' | '>') && file_type == 'f' {
+            summary.updated += 1;
+        }
+    }
+    summary
+}
+
+/// Whether `code` has the length of an `--itemize-changes` code: 11
+/// characters (`YXcstpoguax`) from rsync 3.x, 9 (`YXcstpogz`) from 2.6.9,
+/// the rsync macOS ships.
+pub fn is_itemize_code(code: &str) -> bool {
+    matches!(code.len(), 9 | 11)
+}
+
+/// Transfer totals from rsync's `--stats` block.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RsyncStats {
+    pub files_transferred: u64,
+    /// Total size of the transferred files (before compression).
+    pub transferred_bytes: u64,
+    /// Bytes rsync sent over the wire, including protocol overhead.
+    pub bytes_sent: u64,
+    /// Bytes rsync received over the wire.
+    pub bytes_received: u64,
+    /// Total file size divided by bytes on the wire (rsync's "speedup").
+    pub speedup: f64,
+}
+
+impl RsyncStats {
+    /// One-line summary, e.g. `Synced 12 files, 3.4 MB`.
+    pub fn summary(&self) -> String {
+        let noun = if self.files_transferred == 1 {
+            "file"
+        } else {
+            "files"
+        };
+        format!(
+            "Synced {} {noun}, {}",
+            self.files_transferred,
+            format_size(self.transferred_bytes)
+        )
+    }
+}
+
+/// Extracts [`RsyncStats`] from rsync output. Missing fields are zero.
+///
+/// Accepts both the rsync 3.x wording (`Number of regular files transferred`)
+/// and the 2.6 wording (`Number of files transferred`), with or without
+/// thousands separators.
+pub fn parse_rsync_stats(output: &str) -> RsyncStats {
+    let mut stats = RsyncStats::default();
+    for line in output.lines() {
+        if let Some((_, speedup)) = line.split_once("speedup is ") {
+            stats.speedup = parse_stat_decimal(speedup);
+            continue;
+        }
+        let Some((key, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+        match key {
+            "Number of regular files transferred" | "Number of files transferred" => {
+                stats.files_transferred = parse_stat_number(value);
+            }
+            "Total transferred file size" => stats.transferred_bytes = parse_stat_number(value),
+            "Total bytes sent" => stats.bytes_sent = parse_stat_number(value),
+            "Total bytes received" => stats.bytes_received = parse_stat_number(value),
+            _ => {}
+        }
+    }
+    stats
+}
+
+/// Returns the part of rsync output before the `--stats` block, i.e. the
+/// itemized or progress lines.
+pub fn strip_stats(output: &str) -> &str {
+    match output.find("\nNumber of files:") {
+        Some(pos) => &output[..pos + 1],
+        None if output.starts_with("Number of files:") => "",
+        None => output,
+    }
+}
+
+/// Parses the leading integer of a stats value like ` 3,456,789 bytes`.
+fn parse_stat_number(value: &str) -> u64 {
+    value
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == ',')
+        .filter(char::is_ascii_digit)
+        .collect::<String>()
+        .parse()
+        .unwrap_or(0)
+}
+
+/// Parses the leading decimal of a stats value like `1,234.56 (DRY RUN)`.
+fn parse_stat_decimal(value: &str) -> f64 {
+    value
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == ',' || *c == '.')
+        .filter(|c| *c != ',')
+        .collect::<String>()
+        .parse()
+        .unwrap_or(0.0)
+}
+
+/// Formats a byte count with decimal units (`512 B`, `3.4 MB`).
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1000 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1000.0;
+    let mut unit = 0;
+    while size >= 1000.0 && unit < UNITS.len() - 1 {
+        size /= 1000.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,16 +736,35 @@ mod tests {
         PathBuf::from("/home/user/my-project")
     }
 
+    fn verbose() -> SyncOptions {
+        SyncOptions {
+            verbose: true,
+            ..Default::default()
+        }
+    }
+
     #[test]
     fn base_flags_present() {
-        let params = build_rsync_args(&minimal_config(), Direction::Push, "s1", &root(), false);
+        let params = build_rsync_args(
+            &minimal_config(),
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
         assert!(params.args().contains(&"-az".to_string()));
         assert!(params.args().contains(&"--delete".to_string()));
     }
 
     #[test]
     fn gitignore_filter_included() {
-        let params = build_rsync_args(&minimal_config(), Direction::Push, "s1", &root(), false);
+        let params = build_rsync_args(
+            &minimal_config(),
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
         assert!(params
             .args()
             .contains(&"--filter=:- .gitignore".to_string()));
@@ -147,7 +772,13 @@ mod tests {
 
     #[test]
     fn relocal_toml_is_excluded_and_protected() {
-        let params = build_rsync_args(&minimal_config(), Direction::Push, "s1", &root(), false);
+        let params = build_rsync_args(
+            &minimal_config(),
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
         assert!(params
             .args()
             .contains(&"--exclude=/relocal.toml".to_string()));
@@ -156,6 +787,118 @@ mod tests {
             .contains(&"--filter=P /relocal.toml".to_string()));
     }
 
+    #[test]
+    fn relocal_toml_is_protected_on_pull_ahead_of_every_include() {
+        let options = SyncOptions {
+            only_paths: vec!["relocal.toml".to_string()],
+            ..Default::default()
+        };
+        let config =
+            Config::parse("remote = \"user@host\"\nalways_include = [\"relocal.toml\"]").unwrap();
+        let params = build_rsync_args(&config, Direction::Pull, "s1", &root(), &options);
+        let args = params.args();
+        let pos = |arg: &str| args.iter().position(|a| a == arg).unwrap();
+        let first_include = args
+            .iter()
+            .position(|a| a.starts_with("--include"))
+            .unwrap();
+        assert!(args.contains(&"--delete".to_string()));
+        assert!(pos("--exclude=/relocal.toml") < first_include);
+        assert!(pos("--filter=P /relocal.toml") < first_include);
+    }
+
+    #[test]
+    fn filter_set_orders_sources_by_precedence() {
+        let config = Config::parse(
+            r#"
+remote = "user@host"
+always_include = [".env"]
+exclude = [".env", "target/"]
+exclude_from = ".relocalignore"
+"#,
+        )
+        .unwrap();
+        let options = SyncOptions {
+            include_from: Some(PathBuf::from("keep.txt")),
+            exclude_from: Some(PathBuf::from("/tmp/skip.txt")),
+            resume: true,
+            ..Default::default()
+        };
+        let set = FilterSet::new(&config, &root(), &options);
+        let rules: Vec<(&str, FilterSource)> = set
+            .rules()
+            .iter()
+            .map(|rule| (rule.arg.as_str(), rule.source))
+            .collect();
+        assert_eq!(
+            rules,
+            vec![
+                ("--exclude=/relocal.toml", FilterSource::Builtin),
+                ("--filter=P /relocal.toml", FilterSource::Builtin),
+                ("--exclude=.claude/", FilterSource::Builtin),
+                ("--exclude=.relocal/", FilterSource::Builtin),
+                ("--include=.env", FilterSource::AlwaysInclude),
+                ("--include-from=keep.txt", FilterSource::IncludeFromFlag),
+                ("--filter=:- .gitignore", FilterSource::Gitignore),
+                ("--exclude=.env", FilterSource::Exclude),
+                ("--exclude=target/", FilterSource::Exclude),
+                (
+                    "--exclude-from=/home/user/my-project/.relocalignore",
+                    FilterSource::ExcludeFrom
+                ),
+                (
+                    "--exclude-from=/tmp/skip.txt",
+                    FilterSource::ExcludeFromFlag
+                ),
+                ("--exclude=.rsync-partial/", FilterSource::Resume),
+            ]
+        );
+    }
+
+    #[test]
+    fn filter_set_path_list_precedes_always_include() {
+        let config = Config::parse("remote = \"user@host\"\nalways_include = [\".env\"]").unwrap();
+        let options = SyncOptions {
+            only_paths: vec!["a.txt".into()],
+            ..Default::default()
+        };
+        let set = FilterSet::new(&config, &root(), &options);
+        let sources: Vec<FilterSource> = set.rules().iter().map(|rule| rule.source).collect();
+        assert_eq!(
+            &sources[..7],
+            &[
+                FilterSource::Builtin,
+                FilterSource::Builtin,
+                FilterSource::Builtin,
+                FilterSource::Builtin,
+                FilterSource::OnlyPaths,
+                FilterSource::OnlyPaths,
+                FilterSource::AlwaysInclude,
+            ]
+        );
+    }
+
+    #[test]
+    fn filter_set_args_appear_in_rsync_args_in_order() {
+        let config = Config::parse("remote = \"user@host\"\nexclude = [\".env\"]").unwrap();
+        let options = SyncOptions::default();
+        let filters: Vec<String> = FilterSet::new(&config, &root(), &options).args().collect();
+        let params = build_rsync_args(&config, Direction::Push, "s1", &root(), &options);
+        let start = params.args().iter().position(|a| a == &filters[0]).unwrap();
+        assert_eq!(&params.args()[start..start + filters.len()], &filters[..]);
+    }
+
+    #[test]
+    fn filter_set_describe_labels_each_rule() {
+        let config = Config::parse("remote = \"user@host\"\nexclude = [\".env\"]").unwrap();
+        let lines = FilterSet::new(&config, &root(), &SyncOptions::default()).describe();
+        assert_eq!(lines[0], "--exclude=/relocal.toml   (built-in)");
+        assert_eq!(lines[2], "--exclude=.claude/        (built-in)");
+        assert_eq!(lines[4], "--filter=:- .gitignore    (.gitignore)");
+        assert_eq!(lines[5], "--exclude=.env            (exclude)");
+        assert_eq!(lines.len(), 6);
+    }
+
     #[test]
     fn custom_excludes() {
         let config = Config::parse(
@@ -165,34 +908,471 @@ exclude = [".env", "secrets/"]
 "#,
         )
         .unwrap();
-        let params = build_rsync_args(&config, Direction::Push, "s1", &root(), false);
+        let params = build_rsync_args(
+            &config,
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
         assert!(params.args().contains(&"--exclude=.env".to_string()));
         assert!(params.args().contains(&"--exclude=secrets/".to_string()));
     }
 
     #[test]
     fn claude_dir_excluded() {
-        let params = build_rsync_args(&minimal_config(), Direction::Push, "s1", &root(), false);
+        let params = build_rsync_args(
+            &minimal_config(),
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
         assert!(params.args().contains(&"--exclude=.claude/".to_string()));
     }
 
     #[test]
     fn claude_dir_excluded_on_pull() {
-        let params = build_rsync_args(&minimal_config(), Direction::Pull, "s1", &root(), false);
+        let params = build_rsync_args(
+            &minimal_config(),
+            Direction::Pull,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
         assert!(params.args().contains(&"--exclude=.claude/".to_string()));
     }
 
+    #[test]
+    fn relocal_metadata_dir_always_excluded() {
+        for direction in [Direction::Push, Direction::Pull] {
+            for options in [SyncOptions::default(), verbose()] {
+                let params =
+                    build_rsync_args(&minimal_config(), direction, "s1", &root(), &options);
+                assert!(params.args().contains(&"--exclude=.relocal/".to_string()));
+            }
+        }
+    }
+
+    #[test]
+    fn always_include_precedes_gitignore_filter() {
+        let config = Config::parse(
+            "remote = \"user@host\"\nalways_include = [\".env\"]\nexclude = [\".env\"]",
+        )
+        .unwrap();
+        let params = build_rsync_args(
+            &config,
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
+        let args = params.args();
+        let pos = |flag: &str| args.iter().position(|a| a == flag).unwrap();
+        let include = pos("--include=.env");
+        assert!(include < pos("--filter=:- .gitignore"));
+        assert!(include < pos("--exclude=.env"));
+        // relocal.toml protection still takes precedence.
+        assert!(pos("--exclude=/relocal.toml") < include);
+    }
+
+    #[test]
+    fn exclude_from_follows_inline_excludes_and_gitignore() {
+        let config = Config::parse(
+            "remote = \"user@host\"\nexclude = [\".env\"]\nexclude_from = \".relocalignore\"",
+        )
+        .unwrap();
+        for direction in [Direction::Push, Direction::Pull] {
+            let params =
+                build_rsync_args(&config, direction, "s1", &root(), &SyncOptions::default());
+            let args = params.args();
+            let pos = |flag: &str| args.iter().position(|a| a == flag).unwrap();
+            let from = pos("--exclude-from=/home/user/my-project/.relocalignore");
+            assert!(pos("--filter=:- .gitignore") < from);
+            assert!(pos("--exclude=.env") < from);
+        }
+    }
+
+    #[test]
+    fn filter_file_flags_rank_around_config_rules() {
+        let config = Config::parse(
+            "remote = \"user@host\"\nalways_include = [\".env\"]\nexclude_from = \".relocalignore\"",
+        )
+        .unwrap();
+        let options = SyncOptions {
+            include_from: Some(PathBuf::from("keep.txt")),
+            exclude_from: Some(PathBuf::from("/tmp/skip.txt")),
+            ..Default::default()
+        };
+        for direction in [Direction::Push, Direction::Pull] {
+            let params = build_rsync_args(&config, direction, "s1", &root(), &options);
+            let args = params.args();
+            let pos = |flag: &str| args.iter().position(|a| a == flag).unwrap();
+            let include = pos("--include-from=keep.txt");
+            let exclude = pos("--exclude-from=/tmp/skip.txt");
+            assert!(pos("--include=.env") < include);
+            assert!(include < pos("--filter=:- .gitignore"));
+            assert!(pos("--exclude-from=/home/user/my-project/.relocalignore") < exclude);
+            assert!(pos("--exclude=.claude/") < pos("--include=.env"));
+        }
+    }
+
+    #[test]
+    fn path_filter_applies_every_filter_source() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".relocalignore"), "*.bin\n\n# comment\n").unwrap();
+        let keep = dir.path().join("keep.txt");
+        std::fs::write(&keep, "keep.bin\n- drop.txt\n").unwrap();
+        let config = Config::parse(
+            "remote = \"u@h\"\nexclude = [\"target/\"]\nexclude_from = \".relocalignore\"\nalways_include = [\".claude/\"]",
+        )
+        .unwrap();
+        let options = SyncOptions {
+            include_from: Some(keep),
+            ..Default::default()
+        };
+        let filter = FilterSet::new(&config, dir.path(), &options).path_filter();
+
+        for excluded in [
+            "relocal.toml",
+            ".claude/settings.json",
+            ".relocal/last-sync",
+            "target/debug/app",
+            "data/blob.bin",
+            "drop.txt",
+        ] {
+            assert!(filter.is_excluded(excluded), "{excluded}");
+        }
+        for synced in ["src/main.rs", "sub/relocal.toml", "keep.bin", "target"] {
+            assert!(!filter.is_excluded(synced), "{synced}");
+        }
+    }
+
+    #[test]
+    fn path_filter_honours_a_path_list() {
+        let options = SyncOptions {
+            only_paths: vec!["src/a.rs".into()],
+            ..Default::default()
+        };
+        let filter = FilterSet::new(&minimal_config(), &root(), &options).path_filter();
+        assert!(!filter.is_excluded("src/a.rs"));
+        assert!(filter.is_excluded("src/b.rs"));
+        assert!(filter.is_excluded("README.md"));
+    }
+
+    #[test]
+    fn path_filter_reports_includes_ahead_of_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        let keep = dir.path().join("keep.txt");
+        std::fs::write(&keep, "generated/\n").unwrap();
+        let config = Config::parse("remote = \"user@host\"\nalways_include = [\".env\"]").unwrap();
+        let options = SyncOptions {
+            include_from: Some(keep),
+            ..Default::default()
+        };
+        let filter = FilterSet::new(&config, &root(), &options).path_filter();
+        assert!(filter.is_included_before_gitignore(".env"));
+        assert!(filter.is_included_before_gitignore("generated/out.rs"));
+        assert!(!filter.is_included_before_gitignore("src/main.rs"));
+    }
+
+    #[test]
+    fn always_include_cannot_reinclude_builtin_excludes() {
+        let config = Config::parse(
+            "remote = \"user@host\"\nalways_include = [\".claude/\", \".relocal/**\"]",
+        )
+        .unwrap();
+        let options = SyncOptions {
+            include_from: Some(PathBuf::from("keep.txt")),
+            ..Default::default()
+        };
+        let args: Vec<String> = FilterSet::new(&config, &root(), &options).args().collect();
+        let pos = |arg: &str| args.iter().position(|a| a == arg).unwrap();
+        for builtin in ["--exclude=.claude/", "--exclude=.relocal/"] {
+            assert!(pos(builtin) < pos("--include=.claude/"), "{args:?}");
+            assert!(pos(builtin) < pos("--include=.relocal/**"), "{args:?}");
+            assert!(pos(builtin) < pos("--include-from=keep.txt"), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn rsync_rsh_sets_remote_shell() {
+        let config =
+            Config::parse("remote = \"user@host\"\nrsync_rsh = \"my-wrapper --proxy\"").unwrap();
+        for direction in [Direction::Push, Direction::Pull] {
+            let params =
+                build_rsync_args(&config, direction, "s1", &root(), &SyncOptions::default());
+            let args = params.args();
+            let e = args.iter().position(|a| a == "-e").unwrap();
+            assert_eq!(args[e + 1], "my-wrapper --proxy");
+            assert!(params.custom_rsh());
+        }
+    }
+
+    #[test]
+    fn no_remote_shell_by_default() {
+        let params = build_rsync_args(
+            &minimal_config(),
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
+        assert!(!params.args().iter().any(|a| a == "-e"));
+        assert!(!params.custom_rsh());
+    }
+
+    fn chmod_args(config: &Config) -> Vec<String> {
+        let params = build_rsync_args(
+            config,
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
+        params
+            .args()
+            .iter()
+            .filter(|a| a.starts_with("--chmod"))
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn permissions_preserved_without_chmod_by_default() {
+        assert!(chmod_args(&minimal_config()).is_empty());
+    }
+
+    #[test]
+    fn preserve_perms_false_applies_default_chmod() {
+        let config = Config::parse("remote = \"user@host\"\npreserve_perms = false").unwrap();
+        assert_eq!(chmod_args(&config), ["--chmod=ug=rwX,o=rX"]);
+    }
+
+    #[test]
+    fn explicit_chmod_wins_over_preserve_perms() {
+        for preserve in ["true", "false"] {
+            let config = Config::parse(&format!(
+                "remote = \"user@host\"\npreserve_perms = {preserve}\nchmod = \"Fu+x\""
+            ))
+            .unwrap();
+            assert_eq!(chmod_args(&config), ["--chmod=Fu+x"], "{preserve}");
+        }
+    }
+
+    #[test]
+    fn chmod_applied_in_both_directions_with_archive_mode() {
+        let config = Config::parse("remote = \"user@host\"\nchmod = \"go-w\"").unwrap();
+        for direction in [Direction::Push, Direction::Pull] {
+            let params =
+                build_rsync_args(&config, direction, "s1", &root(), &SyncOptions::default());
+            let args = params.args();
+            assert_eq!(args[0], "-az");
+            assert!(args.iter().any(|a| a == "--chmod=go-w"));
+        }
+    }
+
+    #[test]
+    fn no_exclude_from_by_default() {
+        let params = build_rsync_args(
+            &minimal_config(),
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
+        assert!(!params
+            .args()
+            .iter()
+            .any(|a| a.starts_with("--exclude-from") || a.starts_with("--include-from")));
+    }
+
+    #[test]
+    fn no_include_args_by_default() {
+        let params = build_rsync_args(
+            &minimal_config(),
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
+        assert!(!params.args().iter().any(|a| a.starts_with("--include=")));
+    }
+
+    #[test]
+    fn bwlimit_added_in_both_directions() {
+        let config = Config::parse("remote = \"user@host\"\nbwlimit = \"2m\"").unwrap();
+        for direction in [Direction::Push, Direction::Pull] {
+            let params =
+                build_rsync_args(&config, direction, "s1", &root(), &SyncOptions::default());
+            assert!(params.args().contains(&"--bwlimit=2m".to_string()));
+        }
+    }
+
+    #[test]
+    fn bwlimit_absent_when_unset() {
+        let params = build_rsync_args(
+            &minimal_config(),
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
+        assert!(!params.args().iter().any(|a| a.starts_with("--bwlimit")));
+    }
+
+    #[test]
+    fn one_file_system_from_option_or_config() {
+        let options = SyncOptions {
+            one_file_system: true,
+            ..Default::default()
+        };
+        let config = Config::parse("remote = \"user@host\"\none_file_system = true").unwrap();
+        for direction in [Direction::Push, Direction::Pull] {
+            let from_option =
+                build_rsync_args(&minimal_config(), direction, "s1", &root(), &options);
+            assert!(from_option
+                .args()
+                .contains(&"--one-file-system".to_string()));
+            let from_config =
+                build_rsync_args(&config, direction, "s1", &root(), &SyncOptions::default());
+            assert!(from_config
+                .args()
+                .contains(&"--one-file-system".to_string()));
+        }
+    }
+
+    #[test]
+    fn one_file_system_absent_by_default() {
+        let params = build_rsync_args(
+            &minimal_config(),
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
+        assert!(!params.args().contains(&"--one-file-system".to_string()));
+    }
+
+    #[test]
+    fn compare_dest_added_to_push_only() {
+        let options = SyncOptions {
+            compare_dest: Some("/srv/reference".to_string()),
+            ..Default::default()
+        };
+        let push = build_rsync_args(&minimal_config(), Direction::Push, "s1", &root(), &options);
+        assert!(push
+            .args()
+            .contains(&"--compare-dest=/srv/reference".to_string()));
+        let pull = build_rsync_args(&minimal_config(), Direction::Pull, "s1", &root(), &options);
+        assert!(!pull.args().iter().any(|a| a.starts_with("--compare-dest")));
+        let default = build_rsync_args(
+            &minimal_config(),
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
+        assert!(!default
+            .args()
+            .iter()
+            .any(|a| a.starts_with("--compare-dest")));
+    }
+
+    #[test]
+    fn checksum_from_option_or_config() {
+        let options = SyncOptions {
+            checksum: true,
+            ..Default::default()
+        };
+        let config = Config::parse("remote = \"user@host\"\nchecksum = true").unwrap();
+        for direction in [Direction::Push, Direction::Pull] {
+            let from_option =
+                build_rsync_args(&minimal_config(), direction, "s1", &root(), &options);
+            assert!(from_option.args().contains(&"-c".to_string()));
+            let from_config =
+                build_rsync_args(&config, direction, "s1", &root(), &SyncOptions::default());
+            assert!(from_config.args().contains(&"-c".to_string()));
+        }
+    }
+
+    #[test]
+    fn checksum_absent_by_default() {
+        for direction in [Direction::Push, Direction::Pull] {
+            let params = build_rsync_args(
+                &minimal_config(),
+                direction,
+                "s1",
+                &root(),
+                &SyncOptions::default(),
+            );
+            assert!(!params.args().contains(&"-c".to_string()));
+        }
+    }
+
+    #[test]
+    fn resume_adds_partial_dir_and_excludes_it() {
+        let options = SyncOptions {
+            resume: true,
+            ..Default::default()
+        };
+        for direction in [Direction::Push, Direction::Pull] {
+            let params = build_rsync_args(&minimal_config(), direction, "s1", &root(), &options);
+            let args = params.args();
+            assert!(args.contains(&"--partial".to_string()));
+            assert!(args.contains(&"--partial-dir=.rsync-partial".to_string()));
+            assert!(args.contains(&"--exclude=.rsync-partial/".to_string()));
+        }
+    }
+
+    #[test]
+    fn no_partial_flags_without_resume() {
+        let params = build_rsync_args(
+            &minimal_config(),
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
+        assert!(!params.args().iter().any(|a| a.starts_with("--partial")));
+        assert!(!params
+            .args()
+            .contains(&"--exclude=.rsync-partial/".to_string()));
+    }
+
     #[test]
     fn push_source_dest_paths() {
-        let params = build_rsync_args(&minimal_config(), Direction::Push, "s1", &root(), false);
+        let params = build_rsync_args(
+            &minimal_config(),
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
         let last_two: Vec<&String> = params.args().iter().rev().take(2).collect();
         assert_eq!(last_two[1], "/home/user/my-project/");
         assert_eq!(last_two[0], "user@host:~/relocal/s1/");
     }
 
+    #[test]
+    fn bare_host_alias_paths() {
+        let config = Config::parse("remote = \"devbox\"").unwrap();
+        for direction in [Direction::Push, Direction::Pull] {
+            let params =
+                build_rsync_args(&config, direction, "s1", &root(), &SyncOptions::default());
+            assert!(params.args().contains(&"devbox:~/relocal/s1/".to_string()));
+        }
+    }
+
     #[test]
     fn pull_source_dest_paths() {
-        let params = build_rsync_args(&minimal_config(), Direction::Pull, "s1", &root(), false);
+        let params = build_rsync_args(
+            &minimal_config(),
+            Direction::Pull,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
         let last_two: Vec<&String> = params.args().iter().rev().take(2).collect();
         assert_eq!(last_two[1], "user@host:~/relocal/s1/");
         assert_eq!(last_two[0], "/home/user/my-project/");
@@ -200,24 +1380,490 @@ exclude = [".env", "secrets/"]
 
     #[test]
     fn verbose_adds_progress() {
-        let params = build_rsync_args(&minimal_config(), Direction::Push, "s1", &root(), true);
+        let params = build_rsync_args(
+            &minimal_config(),
+            Direction::Push,
+            "s1",
+            &root(),
+            &verbose(),
+        );
         assert!(params.args().contains(&"--progress".to_string()));
     }
 
+    #[test]
+    fn verbose_uses_configured_flags() {
+        let config =
+            Config::parse("remote = \"user@host\"\nverbose_rsync_flags = [\"--itemize-changes\"]")
+                .unwrap();
+        let params = build_rsync_args(&config, Direction::Push, "s1", &root(), &verbose());
+        let args = params.args();
+        assert!(args.contains(&"--itemize-changes".to_string()));
+        assert!(!args.contains(&"--progress".to_string()));
+    }
+
     #[test]
     fn non_verbose_no_progress() {
-        let params = build_rsync_args(&minimal_config(), Direction::Push, "s1", &root(), false);
+        let params = build_rsync_args(
+            &minimal_config(),
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
         assert!(!params.args().contains(&"--progress".to_string()));
     }
 
     #[test]
     fn params_carry_direction_and_local_path() {
-        let push = build_rsync_args(&minimal_config(), Direction::Push, "s1", &root(), false);
+        let push = build_rsync_args(
+            &minimal_config(),
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
         assert_eq!(push.direction(), Direction::Push);
         assert_eq!(push.local_path(), root());
 
-        let pull = build_rsync_args(&minimal_config(), Direction::Pull, "s1", &root(), false);
+        let pull = build_rsync_args(
+            &minimal_config(),
+            Direction::Pull,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
         assert_eq!(pull.direction(), Direction::Pull);
         assert_eq!(pull.local_path(), root());
     }
+
+    #[test]
+    fn dry_run_adds_dry_run_and_itemize_once() {
+        for options in [
+            SyncOptions {
+                dry_run: true,
+                ..Default::default()
+            },
+            SyncOptions {
+                dry_run: true,
+                delete_dry_run: true,
+                ..Default::default()
+            },
+        ] {
+            let params =
+                build_rsync_args(&minimal_config(), Direction::Pull, "s1", &root(), &options);
+            let count = |flag: &str| params.args().iter().filter(|a| *a == flag).count();
+            assert_eq!(count("--dry-run"), 1);
+            assert_eq!(count("--itemize-changes"), 1);
+        }
+    }
+
+    #[test]
+    fn delete_dry_run_adds_dry_run_and_itemize() {
+        let options = SyncOptions {
+            delete_dry_run: true,
+            ..Default::default()
+        };
+        let params = build_rsync_args(&minimal_config(), Direction::Push, "s1", &root(), &options);
+        assert!(params.args().contains(&"--dry-run".to_string()));
+        assert!(params.args().contains(&"--itemize-changes".to_string()));
+        // --delete must stay so rsync reports what it would remove.
+        assert!(params.args().contains(&"--delete".to_string()));
+    }
+
+    #[test]
+    fn default_options_do_not_dry_run() {
+        let params = build_rsync_args(
+            &minimal_config(),
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
+        assert!(!params.args().contains(&"--dry-run".to_string()));
+        assert!(!params.args().contains(&"--itemize-changes".to_string()));
+    }
+
+    #[test]
+    fn itemize_option_adds_itemize_changes_once() {
+        let options = SyncOptions {
+            itemize: true,
+            verbose: true,
+            ..Default::default()
+        };
+        let config =
+            Config::parse("remote = \"user@host\"\nverbose_rsync_flags = [\"--itemize-changes\"]")
+                .unwrap();
+        let params = build_rsync_args(&config, Direction::Pull, "s1", &root(), &options);
+        let count = params
+            .args()
+            .iter()
+            .filter(|a| *a == "--itemize-changes")
+            .count();
+        assert_eq!(count, 1);
+        assert!(!params.args().contains(&"--dry-run".to_string()));
+    }
+
+    #[test]
+    fn parse_change_summary_counts_each_kind() {
+        let output = "\
+receiving incremental file list
+*deleting   old/notes.txt
+cd+++++++++ new-dir/
+>f+++++++++ new-dir/a.rs
+>f+++++++++ b.rs
+>f.st...... src/main.rs
+>f..t...... src/lib.rs
+.d..t...... src/
+.f...p..... script.sh
+cL+++++++++ link -> b.rs
+
+Number of files: 10
+Total transferred file size: 1,234 bytes
+";
+        assert_eq!(
+            parse_change_summary(output),
+            ChangeSummary {
+                updated: 2,
+                added: 4,
+                deleted: 1,
+            }
+        );
+        assert_eq!(
+            parse_change_summary(output).summary(),
+            "2 files updated, 4 added, 1 deleted"
+        );
+    }
+
+    #[test]
+    fn parse_change_summary_reads_rsync_2_6_9_codes() {
+        let output = "\
+*deleting   old/notes.txt
+cd+++++++ new-dir/
+>f+++++++ new-dir/a.rs
+>f.st.... src/main.rs
+.f...p... script.sh
+";
+        assert_eq!(
+            parse_change_summary(output),
+            ChangeSummary {
+                updated: 1,
+                added: 2,
+                deleted: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_change_summary_empty_output() {
+        assert_eq!(parse_change_summary(""), ChangeSummary::default());
+    }
+
+    #[test]
+    fn parse_deletions_extracts_only_deleting_lines() {
+        let output = "\
+sending incremental file list
+*deleting   old/notes.txt
+*deleting   old/
+>f+++++++++ new-file.rs
+.d..t...... src/
+*deleting   src/removed file.rs
+cd+++++++++ added-dir/
+";
+        assert_eq!(
+            parse_deletions(output),
+            vec!["old/notes.txt", "old/", "src/removed file.rs"]
+        );
+    }
+
+    #[test]
+    fn parse_deletions_accepts_plain_verbose_format() {
+        let output = "deleting stale.log\nkept.txt\n";
+        assert_eq!(parse_deletions(output), vec!["stale.log"]);
+    }
+
+    #[test]
+    fn parse_deletions_empty_when_nothing_deleted() {
+        let output = ">f+++++++++ a.txt\n\nsent 100 bytes  received 20 bytes\n";
+        assert!(parse_deletions(output).is_empty());
+    }
+
+    const STATS_3X: &str = "\
+>f+++++++++ src/new.rs
+
+Number of files: 1,234 (reg: 1,000, dir: 234)
+Number of created files: 1 (reg: 1)
+Number of deleted files: 0
+Number of regular files transferred: 12
+Total file size: 12,345,678 bytes
+Total transferred file size: 3,412,345 bytes
+Literal data: 3,412,345 bytes
+Matched data: 0 bytes
+File list size: 0
+Total bytes sent: 1,234,567
+Total bytes received: 345
+
+sent 1,234,567 bytes  received 345 bytes  823,274.67 bytes/sec
+total size is 12,345,678  speedup is 10.00
+";
+
+    const STATS_2X: &str = "\
+Number of files: 80
+Number of files transferred: 1
+Total file size: 52390 bytes
+Total transferred file size: 512 bytes
+Literal data: 512 bytes
+";
+
+    #[test]
+    fn stats_always_requested() {
+        for direction in [Direction::Push, Direction::Pull] {
+            let params = build_rsync_args(
+                &minimal_config(),
+                direction,
+                "s1",
+                &root(),
+                &SyncOptions::default(),
+            );
+            assert!(params.args().contains(&"--stats".to_string()));
+        }
+    }
+
+    #[test]
+    fn parse_stats_rsync_3_format() {
+        assert_eq!(
+            parse_rsync_stats(STATS_3X),
+            RsyncStats {
+                files_transferred: 12,
+                transferred_bytes: 3_412_345,
+                bytes_sent: 1_234_567,
+                bytes_received: 345,
+                speedup: 10.0,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_stats_rsync_2_format() {
+        assert_eq!(
+            parse_rsync_stats(STATS_2X),
+            RsyncStats {
+                files_transferred: 1,
+                transferred_bytes: 512,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn parse_stats_missing_block_is_zero() {
+        assert_eq!(parse_rsync_stats(""), RsyncStats::default());
+        assert_eq!(
+            parse_rsync_stats("garbage\nmore: x\n"),
+            RsyncStats::default()
+        );
+    }
+
+    #[test]
+    fn parse_stats_dry_run_speedup() {
+        let stats = parse_rsync_stats("total size is 52,390  speedup is 1,234.56 (DRY RUN)\n");
+        assert_eq!(stats.speedup, 1_234.56);
+    }
+
+    #[test]
+    fn strip_stats_keeps_only_itemized_lines() {
+        assert_eq!(strip_stats(STATS_3X).trim_end(), ">f+++++++++ src/new.rs");
+        assert_eq!(strip_stats(STATS_2X), "");
+        assert_eq!(strip_stats("*deleting a\n"), "*deleting a\n");
+    }
+
+    #[test]
+    fn summary_formats_count_and_size() {
+        assert_eq!(
+            parse_rsync_stats(STATS_3X).summary(),
+            "Synced 12 files, 3.4 MB"
+        );
+        assert_eq!(
+            parse_rsync_stats(STATS_2X).summary(),
+            "Synced 1 file, 512 B"
+        );
+    }
+
+    #[test]
+    fn format_size_units() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(999), "999 B");
+        assert_eq!(format_size(1_000), "1.0 KB");
+        assert_eq!(format_size(2_500_000_000), "2.5 GB");
+        assert_eq!(format_size(7_000_000_000_000_000), "7000.0 TB");
+    }
+
+    fn scratch_pull() -> SyncOptions {
+        SyncOptions {
+            local_path: Some(PathBuf::from("/tmp/scratch")),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn local_path_overrides_pull_destination() {
+        let params = build_rsync_args(
+            &minimal_config(),
+            Direction::Pull,
+            "s1",
+            &root(),
+            &scratch_pull(),
+        );
+        assert_eq!(params.args().last().unwrap(), "/tmp/scratch/");
+        assert_eq!(params.local_path(), Path::new("/tmp/scratch"));
+        assert!(!params.force());
+    }
+
+    #[test]
+    fn local_path_ignored_on_push() {
+        let params = build_rsync_args(
+            &minimal_config(),
+            Direction::Push,
+            "s1",
+            &root(),
+            &scratch_pull(),
+        );
+        assert_eq!(
+            params.args()[params.args().len() - 2],
+            "/home/user/my-project/"
+        );
+        assert_eq!(params.local_path(), root());
+    }
+
+    #[test]
+    fn exclude_from_stays_relative_to_repo_root_with_local_path() {
+        let config =
+            Config::parse("remote = \"user@host\"\nexclude_from = \"ignore.txt\"").unwrap();
+        let params = build_rsync_args(&config, Direction::Pull, "s1", &root(), &scratch_pull());
+        assert!(params
+            .args()
+            .contains(&"--exclude-from=/home/user/my-project/ignore.txt".to_string()));
+    }
+
+    #[test]
+    fn force_is_carried_on_params() {
+        let options = SyncOptions {
+            force: true,
+            ..scratch_pull()
+        };
+        let params = build_rsync_args(&minimal_config(), Direction::Pull, "s1", &root(), &options);
+        assert!(params.force());
+    }
+
+    #[test]
+    fn push_no_delete_flag_drops_delete() {
+        let options = SyncOptions {
+            no_delete: true,
+            ..Default::default()
+        };
+        let push = build_rsync_args(&minimal_config(), Direction::Push, "s1", &root(), &options);
+        assert!(!push.args().contains(&"--delete".to_string()));
+        assert_eq!(push.args()[0], "-az");
+
+        // Pull still mirrors.
+        let pull = build_rsync_args(&minimal_config(), Direction::Pull, "s1", &root(), &options);
+        assert!(pull.args().contains(&"--delete".to_string()));
+    }
+
+    #[test]
+    fn push_no_delete_config_drops_delete() {
+        let config = Config::parse("remote = \"user@host\"\npush_no_delete = true").unwrap();
+        let push = build_rsync_args(
+            &config,
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
+        assert!(!push.args().contains(&"--delete".to_string()));
+        let pull = build_rsync_args(
+            &config,
+            Direction::Pull,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
+        assert!(pull.args().contains(&"--delete".to_string()));
+    }
+
+    #[test]
+    fn update_adds_update_and_drops_delete_in_both_directions() {
+        let options = SyncOptions {
+            update: true,
+            ..Default::default()
+        };
+        for direction in [Direction::Push, Direction::Pull] {
+            let params = build_rsync_args(&minimal_config(), direction, "s1", &root(), &options);
+            assert!(params.args().contains(&"--update".to_string()));
+            assert!(!params.args().contains(&"--delete".to_string()));
+        }
+    }
+
+    #[test]
+    fn no_update_by_default() {
+        let params = build_rsync_args(
+            &minimal_config(),
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
+        assert!(!params.args().contains(&"--update".to_string()));
+    }
+
+    #[test]
+    fn only_paths_rules_include_parents_then_exclude_rest() {
+        let rules = only_paths_rules(&["src/cli/mod.rs".into(), "README.md".into()]);
+        assert_eq!(
+            rules,
+            vec![
+                "--include=/README.md",
+                "--include=/src/",
+                "--include=/src/cli/",
+                "--include=/src/cli/mod.rs",
+                "--exclude=*",
+            ]
+        );
+    }
+
+    #[test]
+    fn only_paths_rules_escape_wildcards() {
+        let rules = only_paths_rules(&["a[1]/b*.txt".into(), "back\\slash".into()]);
+        assert!(
+            rules.contains(&"--include=/a\\[1]/".to_string()),
+            "{rules:?}"
+        );
+        assert!(rules.contains(&"--include=/a\\[1]/b\\*.txt".to_string()));
+        // No wildcard: rsync matches the backslash literally.
+        assert!(rules.contains(&"--include=/back\\slash".to_string()));
+    }
+
+    #[test]
+    fn only_paths_precede_always_include() {
+        let config = Config::parse("remote = \"user@host\"\nalways_include = [\".env\"]").unwrap();
+        let options = SyncOptions {
+            only_paths: vec!["a.txt".into()],
+            ..Default::default()
+        };
+        let params = build_rsync_args(&config, Direction::Push, "s1", &root(), &options);
+        let pos = |arg: &str| params.args().iter().position(|a| a == arg).unwrap();
+        assert!(pos("--exclude=*") < pos("--include=.env"));
+        assert!(pos("--delete") < pos("--include=/a.txt"));
+    }
+
+    #[test]
+    fn no_path_rules_by_default() {
+        let params = build_rsync_args(
+            &minimal_config(),
+            Direction::Push,
+            "s1",
+            &root(),
+            &SyncOptions::default(),
+        );
+        assert!(!params.args().contains(&"--exclude=*".to_string()));
+    }
 }
@@ -7,15 +7,20 @@
 //! real SSH or rsync.
 
 use std::ffi::OsString;
+use std::io::{IsTerminal, Read, Write};
 use std::path::Path;
 use std::process::{Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
 
 use shell_quote::{Bash, QuoteRefExt};
+use tracing::warn;
 
+use crate::config::Config;
 use crate::error::{Error, Result};
 use crate::rsync::{Direction, RsyncParams};
+use crate::ssh::{self, SshControlMaster};
 
-/// Builds an rsync `-e` value for SSH ControlMaster args.
+/// Builds an rsync `-e` value for the runner's extra SSH args.
 ///
 /// The args are `-o Key=Value` pairs with no shell metacharacters, so they
 /// are joined directly. (Using shell_quote here would produce `$'...'`
@@ -56,33 +61,149 @@ impl CommandOutput {
 ///
 /// Each method corresponds to a distinct invocation pattern:
 /// - `run_ssh`: non-interactive `ssh user@host "command"`, captures output.
-/// - `run_ssh_interactive`: `ssh -t user@host "command"`, inherits the terminal.
+/// - `run_ssh_operation`: like `run_ssh`, tagged with the [`Operation`] so the
+///   runner can apply that kind's timeout. Defaults to `run_ssh`.
+/// - `run_ssh_interactive`: `ssh -t user@host "command"`, inherits the terminal
+///   (`-T` instead when stdin is not a terminal, see [`tty_flag`]).
 /// - `run_rsync`: runs rsync with the given argument list, captures output.
+/// - `run_rsync_streaming`: like `run_rsync`, but also forwards rsync's stdout
+///   to ours as it arrives (for `--progress`). Defaults to `run_rsync`.
 /// - `run_local`: runs an arbitrary local program, captures output.
 pub trait CommandRunner {
     fn run_ssh(&self, remote: &str, command: &str) -> Result<CommandOutput>;
+    fn run_ssh_operation(
+        &self,
+        _operation: Operation,
+        remote: &str,
+        command: &str,
+    ) -> Result<CommandOutput> {
+        self.run_ssh(remote, command)
+    }
     fn run_ssh_interactive(&self, remote: &str, command: &str) -> Result<ExitStatus>;
     fn run_rsync(&self, params: &RsyncParams) -> Result<CommandOutput>;
+    fn run_rsync_streaming(&self, params: &RsyncParams) -> Result<CommandOutput> {
+        self.run_rsync(params)
+    }
     fn run_local(&self, program: &str, args: &[&str]) -> Result<CommandOutput>;
 }
 
+/// Kind of non-interactive remote command, for picking its timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// A quick probe, e.g. an existence check.
+    Check,
+    /// A package or tool installation.
+    Install,
+    /// Anything else; never timed out.
+    Other,
+}
+
+/// Default for [`TimeoutPolicy::check`], in seconds.
+pub const DEFAULT_CHECK_TIMEOUT: u64 = 10;
+/// Default for [`TimeoutPolicy::install`], in seconds.
+pub const DEFAULT_INSTALL_TIMEOUT: u64 = 600;
+
+/// How long each kind of command may run before the runner kills it. `None`
+/// waits forever.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeoutPolicy {
+    pub check: Option<Duration>,
+    pub install: Option<Duration>,
+    pub rsync: Option<Duration>,
+}
+
+impl Default for TimeoutPolicy {
+    fn default() -> Self {
+        Self {
+            check: Some(Duration::from_secs(DEFAULT_CHECK_TIMEOUT)),
+            install: Some(Duration::from_secs(DEFAULT_INSTALL_TIMEOUT)),
+            rsync: None,
+        }
+    }
+}
+
+impl TimeoutPolicy {
+    /// No timeouts at all.
+    pub fn none() -> Self {
+        Self {
+            check: None,
+            install: None,
+            rsync: None,
+        }
+    }
+
+    /// The timeout for an ssh `operation`.
+    pub fn for_operation(&self, operation: Operation) -> Option<Duration> {
+        match operation {
+            Operation::Check => self.check,
+            Operation::Install => self.install,
+            Operation::Other => None,
+        }
+    }
+}
+
 /// Production implementation that shells out via `std::process::Command`.
 ///
 /// When a ControlMaster socket is configured, all SSH and rsync commands
-/// reuse that persistent connection.
+/// reuse that persistent connection. The socket is either borrowed from the
+/// session daemon ([`with_control_path`](Self::with_control_path)) or owned by
+/// the runner itself ([`with_control_master`](Self::with_control_master)), in
+/// which case dropping the runner tears the connection down.
 pub struct ProcessRunner {
     ssh: OsString,
-    /// Extra args injected into all SSH invocations (e.g., ControlPath options).
+    /// Extra args injected into all SSH invocations (ControlPath and
+    /// strict host key options).
     ssh_extra_args: Vec<String>,
+    control_master: Option<SshControlMaster>,
+    /// How many times `run_ssh` retries after a connection-level failure.
+    ssh_retries: u32,
+    /// Delay before the first retry; doubles on each further attempt.
+    retry_base_delay: Duration,
+    /// Overrides terminal detection for `run_ssh_interactive` (see [`tty_flag`]).
+    force_tty: Option<bool>,
+    timeouts: TimeoutPolicy,
+}
+
+/// Default for [`ProcessRunner::with_ssh_retries`].
+pub const DEFAULT_SSH_RETRIES: u32 = 3;
+
+const DEFAULT_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// ssh's own exit code for "could not run the remote command at all".
+const SSH_ERROR_EXIT: i32 = 255;
+
+/// Picks ssh's pseudo-terminal flag for an interactive session: `-t` when
+/// stdin is a terminal, `-T` otherwise (CI, pipes), so ssh does not fail with
+/// "Pseudo-terminal will not be allocated". `force_tty` overrides detection.
+pub fn tty_flag(force_tty: Option<bool>, stdin_is_terminal: bool) -> &'static str {
+    if force_tty.unwrap_or(stdin_is_terminal) {
+        "-t"
+    } else {
+        "-T"
+    }
 }
 
+/// stderr fragments ssh prints when it failed before the connection was up,
+/// so the remote command never started and running it again is safe. Errors
+/// that can also hit an established connection (`Broken pipe`,
+/// `Connection closed`, a reset after login) are left out: the command may
+/// already have run.
+const CONNECTION_ERRORS: &[&str] = &[
+    // `ssh: connect to host h port 22: Connection timed out` and friends.
+    "connect to host",
+    "Connection refused",
+    "Network is unreachable",
+    "No route to host",
+    "Could not resolve hostname",
+    "kex_exchange_identification",
+    "ssh_exchange_identification",
+    "banner exchange",
+];
+
 impl ProcessRunner {
     /// Creates a runner that uses the system `ssh` binary.
     pub fn new() -> Self {
-        Self {
-            ssh: OsString::from("ssh"),
-            ssh_extra_args: Vec::new(),
-        }
+        Self::with_ssh_program("ssh")
     }
 
     /// Creates a runner that shells out through the given SSH client program.
@@ -94,23 +215,240 @@ impl ProcessRunner {
         Self {
             ssh: ssh.into(),
             ssh_extra_args: Vec::new(),
+            control_master: None,
+            ssh_retries: DEFAULT_SSH_RETRIES,
+            retry_base_delay: DEFAULT_RETRY_BASE_DELAY,
+            force_tty: None,
+            timeouts: TimeoutPolicy::default(),
         }
     }
 
     /// Creates a runner that routes all SSH/rsync through a ControlMaster socket.
     pub fn with_control_path(socket_path: &Path) -> Self {
-        Self {
-            ssh: OsString::from("ssh"),
-            ssh_extra_args: vec![
-                "-o".to_string(),
-                format!("ControlPath={}", socket_path.display()),
-                "-o".to_string(),
-                "ControlMaster=auto".to_string(),
-            ],
+        let mut runner = Self::new();
+        runner.ssh_extra_args = vec![
+            "-o".to_string(),
+            format!("ControlPath={}", socket_path.display()),
+            "-o".to_string(),
+            "ControlMaster=auto".to_string(),
+        ];
+        runner
+    }
+
+    /// Creates a runner that owns a private ControlMaster for its lifetime.
+    ///
+    /// Used by standalone commands (`sync`, `status`, `destroy`, `remote
+    /// install`) that issue several SSH/rsync calls outside a daemon-managed
+    /// session. The first handshake is paid once when the master starts; every
+    /// later call multiplexes over it. The socket path is PID-keyed so it never
+    /// collides with a running session daemon's shared socket.
+    pub fn with_control_master(
+        remote: &str,
+        label: &str,
+        strict_host_key: bool,
+        control_persist: u64,
+    ) -> Result<Self> {
+        let control_master =
+            SshControlMaster::start(remote, label, strict_host_key, control_persist)?;
+        let mut runner = Self::with_control_path(control_master.socket_path())
+            .with_strict_host_key(strict_host_key);
+        runner.control_master = Some(control_master);
+        Ok(runner)
+    }
+
+    /// Makes ssh refuse unknown or changed host keys instead of prompting
+    /// (see [`ssh::STRICT_HOST_KEY_ARGS`]).
+    pub fn with_strict_host_key(mut self, strict: bool) -> Self {
+        if strict {
+            self.ssh_extra_args
+                .extend(ssh::STRICT_HOST_KEY_ARGS.map(String::from));
         }
+        self
+    }
+
+    /// Sets how many times `run_ssh` retries, with exponential backoff, when
+    /// ssh itself fails to reach the remote. Commands that ran and exited
+    /// non-zero are never retried, and neither are interactive sessions.
+    pub fn with_ssh_retries(mut self, retries: u32) -> Self {
+        self.ssh_retries = retries;
+        self
+    }
+
+    /// Forces (`Some(true)`) or suppresses (`Some(false)`) pseudo-terminal
+    /// allocation for interactive sessions; `None` detects it from stdin.
+    pub fn with_force_tty(mut self, force_tty: Option<bool>) -> Self {
+        self.force_tty = force_tty;
+        self
+    }
+
+    /// Sets how long checks, installs and rsync may run (see
+    /// [`TimeoutPolicy`]). Interactive sessions are never timed out.
+    pub fn with_timeouts(mut self, timeouts: TimeoutPolicy) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Applies the config's `ssh_retries`, timeouts and `force_tty`. Every
+    /// runner built from a config goes through this, so none misses a
+    /// setting; host key checking stays separate because
+    /// [`with_control_master`](Self::with_control_master) needs it up front.
+    pub fn with_config(self, config: &Config) -> Self {
+        self.with_ssh_retries(config.ssh_retries)
+            .with_timeouts(config.timeout_policy())
+            .with_force_tty(config.force_tty)
+    }
+
+    /// The `-e` value injecting this runner's SSH options into rsync, if any.
+    ///
+    /// Safe to join without shell quoting because ssh_extra_args are only
+    /// set by with_control_path() and with_strict_host_key(), which produce
+    /// `-o Key=Value` pairs with no spaces or shell metacharacters in the
+    /// values (the socket path is a short hash-based name we control). A
+    /// configured `rsync_rsh` replaces the ssh transport entirely, so these
+    /// options are not injected then.
+    fn rsync_ssh_override(&self, params: &RsyncParams) -> Option<String> {
+        if self.ssh_extra_args.is_empty() || params.custom_rsh() {
+            return None;
+        }
+        Some(build_rsync_ssh_command(&self.ssh_extra_args))
+    }
+
+    /// The rsync command for `params`, after refusing an unsafe pull target.
+    fn rsync_command(&self, params: &RsyncParams) -> Result<Command> {
+        if params.direction() == Direction::Pull && !params.force() {
+            validate_local_pull_target(params.local_path())?;
+        }
+        let mut cmd = Command::new("rsync");
+        if let Some(ssh_cmd) = self.rsync_ssh_override(params) {
+            cmd.args(["-e", &ssh_cmd]);
+        }
+        cmd.args(params.args());
+        Ok(cmd)
+    }
+
+    fn run_ssh_once(
+        &self,
+        remote: &str,
+        wrapped: &str,
+        timeout: Option<Duration>,
+    ) -> Result<CommandOutput> {
+        let mut cmd = Command::new(&self.ssh);
+        cmd.args(&self.ssh_extra_args).args([remote, wrapped]);
+        output_with_timeout(&mut cmd, "ssh", timeout)
     }
 }
 
+/// Runs `cmd` to completion and captures its output, killing it if it is
+/// still running after `timeout`.
+fn output_with_timeout(
+    cmd: &mut Command,
+    name: &str,
+    timeout: Option<Duration>,
+) -> Result<CommandOutput> {
+    if timeout.is_none() {
+        let output = cmd.output()?;
+        return Ok(CommandOutput {
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            status: output.status,
+        });
+    }
+    cmd.stdin(Stdio::null());
+    run_piped(cmd, name, timeout, false)
+}
+
+/// Spawns `cmd` with piped output and waits for it, killing it once
+/// `timeout` has passed. With `forward_stdout`, stdout is also copied to
+/// ours as it arrives (see [`forward_output`]).
+fn run_piped(
+    cmd: &mut Command,
+    name: &str,
+    timeout: Option<Duration>,
+    forward_stdout: bool,
+) -> Result<CommandOutput> {
+    let mut child = cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    // Drain both pipes on their own threads so a full pipe cannot stall the
+    // child while we wait for it.
+    let mut stdout_pipe = child.stdout.take().expect("stdout is piped");
+    let stdout = std::thread::spawn(move || {
+        if forward_stdout {
+            forward_output(stdout_pipe, &mut std::io::stdout())
+        } else {
+            let mut buf = Vec::new();
+            stdout_pipe.read_to_end(&mut buf).map(|_| buf)
+        }
+    });
+    let mut stderr_pipe = child.stderr.take().expect("stderr is piped");
+    let stderr = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        stderr_pipe.read_to_end(&mut buf).map(|_| buf)
+    });
+    let deadline = timeout.map(|timeout| (Instant::now() + timeout, timeout));
+    let status = loop {
+        let Some((deadline, timeout)) = deadline else {
+            break child.wait()?;
+        };
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            // The child may exit between try_wait and kill; either way reap it.
+            let _ = child.kill();
+            child.wait()?;
+            return Err(Error::CommandFailed {
+                command: name.to_string(),
+                message: format!("timed out after {timeout:?}"),
+            });
+        }
+        std::thread::sleep(TIMEOUT_POLL_INTERVAL);
+    };
+    let collect = |reader: std::thread::JoinHandle<std::io::Result<Vec<u8>>>| {
+        reader.join().expect("output reader thread panicked")
+    };
+    Ok(CommandOutput {
+        stdout: String::from_utf8_lossy(&collect(stdout)?).into_owned(),
+        stderr: String::from_utf8_lossy(&collect(stderr)?).into_owned(),
+        status,
+    })
+}
+
+/// How often [`run_piped`] checks whether the child has exited.
+const TIMEOUT_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Copies `reader` to `out` chunk by chunk as it arrives, flushing after
+/// each so `\r`-terminated progress lines show up immediately, and returns
+/// everything copied.
+fn forward_output(mut reader: impl Read, out: &mut impl Write) -> std::io::Result<Vec<u8>> {
+    let mut captured = Vec::new();
+    let mut buf = [0u8; 8192];
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        out.write_all(&buf[..n])?;
+        out.flush()?;
+        captured.extend_from_slice(&buf[..n]);
+    }
+    Ok(captured)
+}
+
+/// Whether `output` is ssh failing to connect, as opposed to the remote
+/// command (or authentication) failing.
+fn is_connection_failure(output: &CommandOutput) -> bool {
+    output.status.code() == Some(SSH_ERROR_EXIT)
+        && CONNECTION_ERRORS
+            .iter()
+            .any(|fragment| output.stderr.contains(fragment))
+}
+
+/// Backoff before retry number `attempt` (1-based): `base`, `2 * base`, ...
+fn retry_delay(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(1 << (attempt - 1).min(16))
+}
+
 impl Default for ProcessRunner {
     fn default() -> Self {
         Self::new()
@@ -154,23 +492,46 @@ fn validate_local_pull_target(local_path: &Path) -> Result<()> {
 
 impl CommandRunner for ProcessRunner {
     fn run_ssh(&self, remote: &str, command: &str) -> Result<CommandOutput> {
+        self.run_ssh_operation(Operation::Other, remote, command)
+    }
+
+    fn run_ssh_operation(
+        &self,
+        operation: Operation,
+        remote: &str,
+        command: &str,
+    ) -> Result<CommandOutput> {
         let wrapped = login_shell_wrap(command);
-        let output = Command::new(&self.ssh)
-            .args(&self.ssh_extra_args)
-            .args([remote, &wrapped])
-            .output()?;
-        Ok(CommandOutput {
-            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
-            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
-            status: output.status,
-        })
+        let timeout = self.timeouts.for_operation(operation);
+        let mut output = self.run_ssh_once(remote, &wrapped, timeout)?;
+        for attempt in 1..=self.ssh_retries {
+            if !is_connection_failure(&output) {
+                break;
+            }
+            let delay = retry_delay(self.retry_base_delay, attempt);
+            warn!(
+                "ssh to {remote} failed ({}), retrying in {delay:?} ({attempt}/{})",
+                output.stderr.trim(),
+                self.ssh_retries
+            );
+            std::thread::sleep(delay);
+            output = self.run_ssh_once(remote, &wrapped, timeout)?;
+        }
+        if output.status.code() == Some(SSH_ERROR_EXIT) && ssh::is_host_key_failure(&output.stderr)
+        {
+            return Err(Error::HostKeyMismatch {
+                remote: remote.to_string(),
+            });
+        }
+        Ok(output)
     }
 
     fn run_ssh_interactive(&self, remote: &str, command: &str) -> Result<ExitStatus> {
         let wrapped = login_shell_wrap(command);
+        let tty = tty_flag(self.force_tty, std::io::stdin().is_terminal());
         let status = Command::new(&self.ssh)
             .args(&self.ssh_extra_args)
-            .args(["-t", remote, &wrapped])
+            .args([tty, remote, &wrapped])
             .stdin(Stdio::inherit())
             .stdout(Stdio::inherit())
             .stderr(Stdio::inherit())
@@ -179,25 +540,20 @@ impl CommandRunner for ProcessRunner {
     }
 
     fn run_rsync(&self, params: &RsyncParams) -> Result<CommandOutput> {
-        if params.direction() == Direction::Pull {
-            validate_local_pull_target(params.local_path())?;
-        }
-        let mut cmd = Command::new("rsync");
-        // Inject SSH options for ControlMaster when configured.
-        // Safe to join without shell quoting because ssh_extra_args are only
-        // set by with_control_path(), which produces `-o Key=Value` pairs
-        // with no spaces or shell metacharacters in the values (the socket
-        // path is a short hash-based name we control).
-        if !self.ssh_extra_args.is_empty() {
-            let ssh_cmd = build_rsync_ssh_command(&self.ssh_extra_args);
-            cmd.args(["-e", &ssh_cmd]);
-        }
-        let output = cmd.args(params.args()).output()?;
-        Ok(CommandOutput {
-            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
-            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
-            status: output.status,
-        })
+        output_with_timeout(
+            &mut self.rsync_command(params)?,
+            "rsync",
+            self.timeouts.rsync,
+        )
+    }
+
+    fn run_rsync_streaming(&self, params: &RsyncParams) -> Result<CommandOutput> {
+        run_piped(
+            &mut self.rsync_command(params)?,
+            "rsync",
+            self.timeouts.rsync,
+            true,
+        )
     }
 
     fn run_local(&self, program: &str, args: &[&str]) -> Result<CommandOutput> {
@@ -319,6 +675,19 @@ mod tests {
         assert!(err.contains("refusing to pull"));
     }
 
+    #[test]
+    fn forced_pull_skips_validation() {
+        let runner = ProcessRunner::default();
+        let dir = tempfile::tempdir().unwrap();
+        let params = make_params(Direction::Pull, dir.path().to_path_buf()).forced();
+        if let Err(e) = runner.run_rsync(&params) {
+            assert!(
+                !e.to_string().contains("refusing to pull"),
+                "forced pull should not validate local path"
+            );
+        }
+    }
+
     #[test]
     fn pull_refused_nonexistent_path() {
         let result = validate_local_pull_target(&PathBuf::from("/nonexistent/path/xyz"));
@@ -327,6 +696,35 @@ mod tests {
         assert!(err.contains("cannot be resolved"));
     }
 
+    #[test]
+    fn forward_output_copies_and_captures() {
+        let mut out = Vec::new();
+        let captured = forward_output(&b"a.txt\n  1%\r100%\n"[..], &mut out).unwrap();
+        assert_eq!(out, b"a.txt\n  1%\r100%\n");
+        assert_eq!(captured, out);
+    }
+
+    #[test]
+    fn run_rsync_streaming_pull_rejects_invalid_destination() {
+        let runner = ProcessRunner::default();
+        let dir = tempfile::tempdir().unwrap();
+        let params = make_params(Direction::Pull, dir.path().to_path_buf());
+        let err = runner.run_rsync_streaming(&params).unwrap_err().to_string();
+        assert!(err.contains("refusing to pull"));
+    }
+
+    #[test]
+    fn tty_flag_follows_detected_terminal() {
+        assert_eq!(tty_flag(None, true), "-t");
+        assert_eq!(tty_flag(None, false), "-T");
+    }
+
+    #[test]
+    fn tty_flag_override_wins() {
+        assert_eq!(tty_flag(Some(true), false), "-t");
+        assert_eq!(tty_flag(Some(false), true), "-T");
+    }
+
     #[test]
     fn run_local_captures_stdout() {
         let runner = ProcessRunner::default();
@@ -378,6 +776,165 @@ mod tests {
         assert!(wrapped.starts_with("bash -lc "));
     }
 
+    /// Writes an executable fake ssh that fails with `stderr` and exit 255
+    /// for the first `failures` calls, then succeeds. Returns the script path
+    /// and the file counting calls.
+    fn flaky_ssh(dir: &Path, failures: u32, stderr: &str) -> (PathBuf, PathBuf) {
+        let script = dir.join("fake-ssh");
+        let counter = dir.join("calls");
+        std::fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\necho x >> '{counter}'\nif [ $(wc -l < '{counter}') -le {failures} ]; then echo '{stderr}' >&2; exit 255; fi\necho ok\n",
+                counter = counter.display()
+            ),
+        )
+        .unwrap();
+        let mut permissions = std::fs::metadata(&script).unwrap().permissions();
+        permissions.set_mode(0o755);
+        std::fs::set_permissions(&script, permissions).unwrap();
+        (script, counter)
+    }
+
+    fn call_count(counter: &Path) -> usize {
+        std::fs::read_to_string(counter).unwrap().lines().count()
+    }
+
+    fn fast_retries(script: &Path) -> ProcessRunner {
+        let mut runner = ProcessRunner::with_ssh_program(script);
+        runner.retry_base_delay = Duration::from_millis(1);
+        runner
+    }
+
+    #[test]
+    fn run_ssh_retries_connection_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        let (script, counter) = flaky_ssh(
+            dir.path(),
+            2,
+            "kex_exchange_identification: read: Connection reset by peer",
+        );
+
+        let out = fast_retries(&script).run_ssh("user@host", "true").unwrap();
+        assert!(out.status.success());
+        assert_eq!(out.stdout.trim(), "ok");
+        assert_eq!(call_count(&counter), 3);
+    }
+
+    #[test]
+    fn run_ssh_gives_up_after_configured_retries() {
+        let dir = tempfile::tempdir().unwrap();
+        let (script, counter) = flaky_ssh(dir.path(), 10, "Connection refused");
+
+        let out = fast_retries(&script)
+            .with_ssh_retries(2)
+            .run_ssh("user@host", "true")
+            .unwrap();
+        assert!(!out.status.success());
+        assert_eq!(call_count(&counter), 3);
+    }
+
+    #[test]
+    fn run_ssh_does_not_retry_errors_after_connecting() {
+        for stderr in [
+            "client_loop: send disconnect: Broken pipe",
+            "Connection to host closed by remote host.",
+        ] {
+            let dir = tempfile::tempdir().unwrap();
+            let (script, counter) = flaky_ssh(dir.path(), 10, stderr);
+
+            let out = fast_retries(&script).run_ssh("user@host", "true").unwrap();
+            assert!(!out.status.success());
+            assert_eq!(call_count(&counter), 1, "{stderr}");
+        }
+    }
+
+    #[test]
+    fn run_ssh_does_not_retry_non_connection_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let (script, counter) = flaky_ssh(dir.path(), 10, "Permission denied (publickey).");
+
+        let out = fast_retries(&script).run_ssh("user@host", "true").unwrap();
+        assert!(!out.status.success());
+        assert_eq!(call_count(&counter), 1);
+    }
+
+    #[test]
+    fn connection_failure_requires_ssh_exit_code() {
+        let output = |code: i32, stderr: &str| CommandOutput {
+            stdout: String::new(),
+            stderr: stderr.to_string(),
+            status: ExitStatus::from_raw(code << 8),
+        };
+        let timed_out = "ssh: connect to host h port 22: Connection timed out";
+        assert!(is_connection_failure(&output(255, timed_out)));
+        // The remote command itself printed the text and exited non-zero.
+        assert!(!is_connection_failure(&output(1, timed_out)));
+        assert!(!is_connection_failure(&output(
+            255,
+            "Host key verification failed."
+        )));
+    }
+
+    #[test]
+    fn run_ssh_host_key_failure_is_distinct_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let (script, counter) = flaky_ssh(
+            dir.path(),
+            10,
+            "@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@\n\
+             @    WARNING: REMOTE HOST IDENTIFICATION HAS CHANGED!     @\n\
+             Host key verification failed.",
+        );
+
+        let err = fast_retries(&script)
+            .run_ssh("user@host", "true")
+            .unwrap_err();
+        assert!(matches!(err, Error::HostKeyMismatch { ref remote } if remote == "user@host"));
+        assert!(err.to_string().contains("known_hosts"));
+        assert_eq!(call_count(&counter), 1);
+    }
+
+    #[test]
+    fn with_strict_host_key_adds_options() {
+        let runner = ProcessRunner::default().with_strict_host_key(true);
+        assert_eq!(
+            runner.ssh_extra_args,
+            ["-o", "StrictHostKeyChecking=yes", "-o", "BatchMode=yes"]
+        );
+        assert_eq!(
+            build_rsync_ssh_command(&runner.ssh_extra_args),
+            "ssh -o StrictHostKeyChecking=yes -o BatchMode=yes"
+        );
+    }
+
+    #[test]
+    fn without_strict_host_key_adds_nothing() {
+        let runner = ProcessRunner::default().with_strict_host_key(false);
+        assert!(runner.ssh_extra_args.is_empty());
+    }
+
+    #[test]
+    fn with_config_applies_connection_settings() {
+        let config = Config::parse(
+            "remote = \"u@h\"\nssh_retries = 5\nrsync_timeout = 60\nforce_tty = false",
+        )
+        .unwrap();
+        let runner = ProcessRunner::default().with_config(&config);
+        assert_eq!(runner.ssh_retries, 5);
+        assert_eq!(runner.timeouts, config.timeout_policy());
+        assert_eq!(runner.timeouts.rsync, Some(Duration::from_secs(60)));
+        assert_eq!(runner.force_tty, Some(false));
+    }
+
+    #[test]
+    fn retry_delay_doubles() {
+        let base = Duration::from_millis(500);
+        assert_eq!(retry_delay(base, 1), Duration::from_millis(500));
+        assert_eq!(retry_delay(base, 2), Duration::from_millis(1000));
+        assert_eq!(retry_delay(base, 3), Duration::from_millis(2000));
+    }
+
     #[test]
     fn injected_ssh_program_is_used() {
         let dir = tempfile::tempdir().unwrap();
@@ -397,6 +954,141 @@ mod tests {
         assert!(out.stderr.contains("injected failure from runner test"));
     }
 
+    /// A runner whose fake ssh sleeps for a second before succeeding.
+    fn slow_ssh(dir: &Path, timeouts: TimeoutPolicy) -> ProcessRunner {
+        let script = dir.join("fake-ssh");
+        std::fs::write(&script, "#!/bin/sh\nsleep 1\necho done\n").unwrap();
+        let mut permissions = std::fs::metadata(&script).unwrap().permissions();
+        permissions.set_mode(0o755);
+        std::fs::set_permissions(&script, permissions).unwrap();
+        ProcessRunner::with_ssh_program(&script)
+            .with_ssh_retries(0)
+            .with_timeouts(timeouts)
+    }
+
+    #[test]
+    fn check_operation_uses_check_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = slow_ssh(
+            dir.path(),
+            TimeoutPolicy {
+                check: Some(Duration::from_millis(100)),
+                ..TimeoutPolicy::none()
+            },
+        );
+
+        let err = runner
+            .run_ssh_operation(Operation::Check, "user@host", "test -d x")
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+        let out = runner
+            .run_ssh_operation(Operation::Install, "user@host", "install")
+            .unwrap();
+        assert_eq!(out.stdout.trim(), "done");
+    }
+
+    #[test]
+    fn install_operation_uses_install_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = slow_ssh(
+            dir.path(),
+            TimeoutPolicy {
+                install: Some(Duration::from_millis(100)),
+                ..TimeoutPolicy::none()
+            },
+        );
+
+        let err = runner
+            .run_ssh_operation(Operation::Install, "user@host", "install")
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+        let out = runner
+            .run_ssh_operation(Operation::Check, "user@host", "test -d x")
+            .unwrap();
+        assert_eq!(out.stdout.trim(), "done");
+    }
+
+    #[test]
+    fn other_operations_have_no_timeout() {
+        let policy = TimeoutPolicy {
+            check: Some(Duration::from_millis(1)),
+            install: Some(Duration::from_millis(1)),
+            rsync: Some(Duration::from_millis(1)),
+        };
+        assert_eq!(policy.for_operation(Operation::Other), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let runner = slow_ssh(dir.path(), policy);
+        let out = runner.run_ssh("user@host", "echo hi").unwrap();
+        assert_eq!(out.stdout.trim(), "done");
+    }
+
+    #[test]
+    fn default_timeout_policy() {
+        let policy = TimeoutPolicy::default();
+        assert_eq!(policy.check, Some(Duration::from_secs(10)));
+        assert_eq!(policy.install, Some(Duration::from_secs(600)));
+        assert_eq!(policy.rsync, None);
+    }
+
+    #[test]
+    fn output_with_timeout_kills_slow_command() {
+        let err = output_with_timeout(
+            Command::new("sleep").arg("5"),
+            "rsync",
+            Some(Duration::from_millis(100)),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "command failed: rsync: timed out after 100ms"
+        );
+    }
+
+    #[test]
+    fn streamed_run_is_killed_after_timeout() {
+        let started = Instant::now();
+        let err = run_piped(
+            Command::new("sh").args(["-c", "echo started; sleep 5"]),
+            "rsync",
+            Some(Duration::from_millis(100)),
+            true,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "command failed: rsync: timed out after 100ms"
+        );
+        assert!(started.elapsed() < Duration::from_secs(4));
+    }
+
+    #[test]
+    fn streamed_run_captures_forwarded_output() {
+        let out = run_piped(
+            Command::new("sh").args(["-c", "echo out; echo err >&2"]),
+            "rsync",
+            Some(Duration::from_secs(5)),
+            true,
+        )
+        .unwrap();
+        assert!(out.status.success());
+        assert_eq!(out.stdout.trim(), "out");
+        assert_eq!(out.stderr.trim(), "err");
+    }
+
+    #[test]
+    fn output_with_timeout_captures_fast_command() {
+        let out = output_with_timeout(
+            Command::new("sh").args(["-c", "echo out; echo err >&2"]),
+            "rsync",
+            Some(Duration::from_secs(5)),
+        )
+        .unwrap();
+        assert!(out.status.success());
+        assert_eq!(out.stdout.trim(), "out");
+        assert_eq!(out.stderr.trim(), "err");
+    }
+
     #[test]
     fn with_control_path_sets_extra_args() {
         let runner = ProcessRunner::with_control_path(Path::new("/tmp/test.sock"));
@@ -409,6 +1101,42 @@ mod tests {
             .contains(&"ControlMaster=auto".to_string()));
     }
 
+    #[test]
+    fn with_control_master_fails_for_unreachable_remote() {
+        let result = ProcessRunner::with_control_master("relocal-test.invalid", "s1", false, 300);
+        let err = result
+            .err()
+            .expect("ControlMaster to an invalid host must fail");
+        assert!(err.to_string().contains("ControlMaster"));
+    }
+
+    #[test]
+    fn rsync_gets_control_path_without_custom_rsh() {
+        let runner = ProcessRunner::with_control_path(Path::new("/tmp/test.sock"));
+        let params = make_params(Direction::Push, PathBuf::from("/tmp"));
+        let ssh_cmd = runner.rsync_ssh_override(&params).unwrap();
+        assert!(ssh_cmd.contains("ControlPath=/tmp/test.sock"));
+        assert!(ProcessRunner::default()
+            .rsync_ssh_override(&params)
+            .is_none());
+    }
+
+    #[test]
+    fn custom_rsh_suppresses_injected_ssh_options() {
+        let config =
+            crate::config::Config::parse("remote = \"u@h\"\nrsync_rsh = \"wrapper\"").unwrap();
+        let params = crate::rsync::build_rsync_args(
+            &config,
+            Direction::Push,
+            "s1",
+            Path::new("/tmp"),
+            &Default::default(),
+        );
+        let runner = ProcessRunner::with_control_path(Path::new("/tmp/test.sock"))
+            .with_strict_host_key(true);
+        assert!(runner.rsync_ssh_override(&params).is_none());
+    }
+
     #[test]
     fn build_rsync_ssh_command_no_args() {
         let cmd = build_rsync_ssh_command(&[]);
@@ -1,15 +1,25 @@
-//! Session name validation and default derivation.
+//! Session name validation and default derivation, and the local sync state.
 //!
 //! Each session maps to a remote working directory at `~/relocal/<session-name>/`.
 //! The name is embedded in filesystem paths, so it must be restricted to safe
 //! characters.
+//!
+//! After each successful full-tree sync, the size and mtime of every synced
+//! local file is recorded in `<repo-root>/.relocal/state.json`, keyed by
+//! session name (see [`record_sync_state`]). Pulls compare against it to warn
+//! about local changes they may overwrite (see [`changed_since_sync`]).
 
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
 
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
+use crate::case_check::parse_file_list;
 use crate::error::{Error, Result};
+use crate::rsync::PathFilter;
 
 /// Validates that a session name contains only alphanumeric characters, hyphens,
 /// and underscores. This prevents path traversal and shell injection issues since
@@ -44,6 +54,12 @@ pub fn validate_session_name(name: &str) -> Result<()> {
 /// the hash prevents collisions. Returns an error if the directory name
 /// contains characters invalid for session names.
 pub fn hashed_session_name(repo_root: &Path) -> Result<String> {
+    hashed_session_name_with_origin(repo_root, &git_origin_url(repo_root))
+}
+
+/// [`hashed_session_name`] for a repo whose git origin URL is `origin`, for
+/// a directory whose `.git` has not arrived yet.
+pub fn hashed_session_name_with_origin(repo_root: &Path, origin: &str) -> Result<String> {
     let dirname = repo_root
         .file_name()
         .and_then(|n| n.to_str())
@@ -57,7 +73,6 @@ pub fn hashed_session_name(repo_root: &Path) -> Result<String> {
     let canonical = repo_root
         .canonicalize()
         .unwrap_or_else(|_| repo_root.to_path_buf());
-    let origin = git_origin_url(repo_root);
 
     let hash = compute_hash(canonical.as_os_str().as_encoded_bytes(), origin.as_bytes());
     Ok(format!("{dirname}-{hash}"))
@@ -89,9 +104,162 @@ fn compute_hash(path_bytes: &[u8], origin_bytes: &[u8]) -> String {
     )
 }
 
+/// Size and mtime of one file as of the last successful sync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileState {
+    pub size: u64,
+    /// Seconds since the epoch.
+    pub mtime: u64,
+}
+
+/// One session's entry in `.relocal/state.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionSyncState {
+    /// Unix timestamp (seconds) of the sync that recorded this state.
+    pub synced_at: u64,
+    /// Synced files by path relative to the repo root.
+    pub files: BTreeMap<String, FileState>,
+}
+
+/// The contents of `.relocal/state.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct SyncStateFile {
+    #[serde(default)]
+    sessions: BTreeMap<String, SessionSyncState>,
+}
+
+/// Path to the local sync state file.
+pub fn sync_state_path(repo_root: &Path) -> PathBuf {
+    repo_root.join(".relocal").join("state.json")
+}
+
+fn load_sync_state_file(path: &Path) -> Result<SyncStateFile> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(SyncStateFile::default()),
+        Err(e) => return Err(e.into()),
+    };
+    serde_json::from_str(&contents).map_err(|e| Error::ConfigParse {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Reads the state recorded by the last successful sync of `session`, or
+/// `None` if there is none.
+pub fn read_sync_state(repo_root: &Path, session: &str) -> Result<Option<SessionSyncState>> {
+    let mut file = load_sync_state_file(&sync_state_path(repo_root))?;
+    Ok(file.sessions.remove(session))
+}
+
+/// Stores `state` as `session`'s entry, keeping other sessions' entries.
+///
+/// Creates `.relocal/` with a `.gitignore` that ignores it, so the state
+/// never shows up in `git status`. The file is replaced atomically (write
+/// then rename), since the session daemon and a manual sync may record
+/// concurrently.
+pub fn write_sync_state(repo_root: &Path, session: &str, state: SessionSyncState) -> Result<()> {
+    let path = sync_state_path(repo_root);
+    let dir = repo_root.join(".relocal");
+    std::fs::create_dir_all(&dir)?;
+    let gitignore = dir.join(".gitignore");
+    if !gitignore.exists() {
+        std::fs::write(&gitignore, "*\n")?;
+    }
+
+    let mut file = load_sync_state_file(&path)?;
+    file.sessions.insert(session.to_string(), state);
+    let contents =
+        serde_json::to_string_pretty(&file).expect("sync state serialization cannot fail");
+    let tmp = dir.join(format!("state.json.{}.tmp", std::process::id()));
+    std::fs::write(&tmp, contents)?;
+    std::fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// Size and mtime of each of `paths` under `repo_root`. Paths that can't be
+/// read (e.g. deleted but still in the git index) are skipped.
+pub fn snapshot_files(repo_root: &Path, paths: &[String]) -> BTreeMap<String, FileState> {
+    paths
+        .iter()
+        .filter_map(|path| {
+            let meta = std::fs::symlink_metadata(repo_root.join(path)).ok()?;
+            let mtime = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+            Some((
+                path.clone(),
+                FileState {
+                    size: meta.len(),
+                    mtime: mtime.as_secs(),
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Enumerates the synced local files like `--check-case` does: `git
+/// ls-files` honouring `.gitignore`, minus the paths `filter` excludes from
+/// the sync. Fails outside a git work tree.
+fn synced_files(repo_root: &Path, filter: &PathFilter) -> Result<Vec<String>> {
+    let output = Command::new("git")
+        .current_dir(repo_root)
+        .args([
+            "ls-files",
+            "-z",
+            "--cached",
+            "--others",
+            "--exclude-standard",
+        ])
+        .output()?;
+    if !output.status.success() {
+        return Err(Error::CommandFailed {
+            command: "git ls-files".to_string(),
+            message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(parse_file_list(
+        &String::from_utf8_lossy(&output.stdout),
+        filter,
+    ))
+}
+
+/// Records the current local state of the synced tree for `session` (see
+/// [`synced_files`]).
+pub fn record_sync_state(repo_root: &Path, session: &str, filter: &PathFilter) -> Result<()> {
+    let paths = synced_files(repo_root, filter)?;
+    let state = SessionSyncState {
+        synced_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs()),
+        files: snapshot_files(repo_root, &paths),
+    };
+    write_sync_state(repo_root, session, state)
+}
+
+/// Local files added or modified (by size or mtime) since the last recorded
+/// sync of `session`, or `None` if none was recorded. Files deleted since are
+/// not listed.
+pub fn changed_since_sync(
+    repo_root: &Path,
+    session: &str,
+    filter: &PathFilter,
+) -> Result<Option<Vec<String>>> {
+    let Some(state) = read_sync_state(repo_root, session)? else {
+        return Ok(None);
+    };
+    let paths = synced_files(repo_root, filter)?;
+    let changed = snapshot_files(repo_root, &paths)
+        .into_iter()
+        .filter(|(path, current)| state.files.get(path) != Some(current))
+        .map(|(path, _)| path)
+        .collect();
+    Ok(Some(changed))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::Config;
+    use crate::rsync::{FilterSet, SyncOptions};
     use std::fs;
     use tempfile::TempDir;
 
@@ -248,4 +416,91 @@ mod tests {
 
         assert_eq!(git_origin_url(tmp.path()), "https://example.com/repo.git");
     }
+
+    fn state(synced_at: u64) -> SessionSyncState {
+        SessionSyncState {
+            synced_at,
+            files: BTreeMap::from([("a.txt".to_string(), FileState { size: 1, mtime: 2 })]),
+        }
+    }
+
+    #[test]
+    fn sync_state_missing_is_none() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(read_sync_state(dir.path(), "s1").unwrap(), None);
+    }
+
+    #[test]
+    fn sync_state_is_keyed_by_session() {
+        let dir = TempDir::new().unwrap();
+        write_sync_state(dir.path(), "s1", state(10)).unwrap();
+        write_sync_state(dir.path(), "s2", state(20)).unwrap();
+        write_sync_state(dir.path(), "s1", state(30)).unwrap();
+
+        assert_eq!(read_sync_state(dir.path(), "s1").unwrap(), Some(state(30)));
+        assert_eq!(read_sync_state(dir.path(), "s2").unwrap(), Some(state(20)));
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".relocal/.gitignore")).unwrap(),
+            "*\n"
+        );
+    }
+
+    #[test]
+    fn corrupt_sync_state_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join(".relocal")).unwrap();
+        fs::write(sync_state_path(dir.path()), "{").unwrap();
+        assert!(read_sync_state(dir.path(), "s1").is_err());
+    }
+
+    #[test]
+    fn record_sync_state_snapshots_synced_files() {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        fs::write(dir.path().join(".gitignore"), "build/\n").unwrap();
+        fs::write(dir.path().join("main.rs"), "fn main() {}").unwrap();
+        fs::write(dir.path().join("secret.env"), "x").unwrap();
+        fs::create_dir(dir.path().join("build")).unwrap();
+        fs::write(dir.path().join("build/out"), "x").unwrap();
+        fs::write(dir.path().join("relocal.toml"), "").unwrap();
+
+        fs::write(dir.path().join(".relocalignore"), "*.env\n").unwrap();
+        let config = Config::parse("remote = \"u@h\"\nexclude_from = \".relocalignore\"").unwrap();
+        let filter = FilterSet::new(&config, dir.path(), &SyncOptions::default()).path_filter();
+        record_sync_state(dir.path(), "s1", &filter).unwrap();
+
+        let recorded = read_sync_state(dir.path(), "s1").unwrap().unwrap();
+        let paths: Vec<&str> = recorded.files.keys().map(String::as_str).collect();
+        assert_eq!(paths, vec![".gitignore", ".relocalignore", "main.rs"]);
+        assert_eq!(recorded.files["main.rs"].size, 12);
+        assert!(recorded.synced_at > 0);
+    }
+
+    #[test]
+    fn changed_since_sync_lists_added_and_modified_files() {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        fs::write(dir.path().join("kept.rs"), "a").unwrap();
+        fs::write(dir.path().join("edited.rs"), "a").unwrap();
+        fs::write(dir.path().join("deleted.rs"), "a").unwrap();
+        let filter = PathFilter::default();
+        assert_eq!(changed_since_sync(dir.path(), "s1", &filter).unwrap(), None);
+
+        record_sync_state(dir.path(), "s1", &filter).unwrap();
+        fs::write(dir.path().join("edited.rs"), "longer").unwrap();
+        fs::write(dir.path().join("added.rs"), "a").unwrap();
+        fs::remove_file(dir.path().join("deleted.rs")).unwrap();
+
+        assert_eq!(
+            changed_since_sync(dir.path(), "s1", &filter).unwrap(),
+            Some(vec!["added.rs".to_string(), "edited.rs".to_string()])
+        );
+        assert_eq!(changed_since_sync(dir.path(), "s2", &filter).unwrap(), None);
+    }
+
+    #[test]
+    fn record_sync_state_outside_git_fails() {
+        let dir = TempDir::new().unwrap();
+        assert!(record_sync_state(dir.path(), "s1", &PathFilter::default()).is_err());
+    }
 }
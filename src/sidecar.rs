@@ -2,19 +2,21 @@
 //!
 //! A background thread runs `sync_pull` on a fixed interval while the
 //! interactive session is active. Uses `mpsc::recv_timeout` for clean,
-//! fast shutdown.
+//! fast shutdown. Every pull is counted in [`SyncStats`], readable via
+//! [`Sidecar::stats`].
 
 use std::path::PathBuf;
 use std::sync::mpsc;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
 use std::time::Duration;
 
 use tracing::warn;
 
-use crate::commands::sync::sync_pull;
+use crate::commands::sync::{sync_pull, SyncStats};
 use crate::config::Config;
 use crate::error::Result;
+use crate::rsync::{Direction, SyncOptions};
 use crate::runner::CommandRunner;
 
 /// How often the background loop runs sync_pull.
@@ -24,6 +26,7 @@ const SYNC_INTERVAL: Duration = Duration::from_secs(3);
 pub struct Sidecar {
     thread: Option<JoinHandle<()>>,
     shutdown_sender: Option<mpsc::Sender<()>>,
+    stats: Arc<Mutex<SyncStats>>,
 }
 
 impl Sidecar {
@@ -40,22 +43,38 @@ impl Sidecar {
     ) -> Result<Self> {
         let (tx, rx) = mpsc::channel();
 
+        let options = SyncOptions {
+            verbose,
+            background: true,
+            ..Default::default()
+        };
+        let stats = Arc::new(Mutex::new(SyncStats::default()));
+        let thread_stats = Arc::clone(&stats);
         let thread = thread::spawn(move || {
             while let Err(mpsc::RecvTimeoutError::Timeout) = rx.recv_timeout(SYNC_INTERVAL) {
-                if let Err(e) =
-                    sync_pull(runner.as_ref(), &config, &session_name, &repo_root, verbose)
-                {
-                    warn!("background sync failed: {e}");
-                }
+                pull_once(
+                    runner.as_ref(),
+                    &config,
+                    &session_name,
+                    &repo_root,
+                    &options,
+                    &thread_stats,
+                );
             }
         });
 
         Ok(Self {
             thread: Some(thread),
             shutdown_sender: Some(tx),
+            stats,
         })
     }
 
+    /// Snapshot of the syncs performed so far.
+    pub fn stats(&self) -> SyncStats {
+        self.stats.lock().expect("sync stats lock poisoned").clone()
+    }
+
     /// Signals the background loop to stop and waits for it to exit.
     ///
     /// Dropping the channel sender unblocks `recv_timeout` immediately,
@@ -68,6 +87,25 @@ impl Sidecar {
     }
 }
 
+/// One iteration of the background loop: pull and record the outcome.
+fn pull_once(
+    runner: &dyn CommandRunner,
+    config: &Config,
+    session_name: &str,
+    repo_root: &std::path::Path,
+    options: &SyncOptions,
+    stats: &Mutex<SyncStats>,
+) {
+    let result = sync_pull(runner, config, session_name, repo_root, options);
+    if let Err(e) = &result {
+        warn!("background sync failed: {e}");
+    }
+    stats
+        .lock()
+        .expect("sync stats lock poisoned")
+        .record(Direction::Pull, &result);
+}
+
 impl Drop for Sidecar {
     fn drop(&mut self) {
         self.shutdown();
@@ -146,4 +184,36 @@ mod tests {
         // Dropping should not panic or hang
         drop(sidecar);
     }
+
+    #[test]
+    fn pull_once_records_success_and_failure() {
+        use crate::test_support::{MockResponse, MockRunner};
+
+        let stats = Mutex::new(SyncStats::default());
+        let mock = MockRunner::new();
+        mock.add_response(MockResponse::Ok(String::new())); // fsck
+        mock.add_response(MockResponse::Ok(String::new())); // rsync
+        mock.add_response(MockResponse::Fail("not a git repository".into())); // fsck
+
+        let options = SyncOptions::default();
+        pull_once(&mock, &test_config(), "s1", &repo_root(), &options, &stats);
+        pull_once(&mock, &test_config(), "s1", &repo_root(), &options, &stats);
+
+        let stats = stats.into_inner().unwrap();
+        assert_eq!((stats.pushes, stats.pulls, stats.errors), (0, 2, 1));
+        assert!(stats.last_sync.is_some());
+    }
+
+    #[test]
+    fn stats_start_empty() {
+        let sidecar = Sidecar::start(
+            Arc::new(ThreadSafeRunner),
+            test_config(),
+            "s1".into(),
+            repo_root(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(sidecar.stats(), SyncStats::default());
+    }
 }
@@ -7,19 +7,38 @@
 //! exit-code-to-meaning translation.
 
 use std::hash::{Hash, Hasher};
+use std::io::{Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use shell_quote::{Bash, QuoteRefExt};
 
 use crate::error::{Error, Result};
-use crate::runner::CommandRunner;
+use crate::runner::{CommandRunner, Operation};
 
 /// Remote base directory for all relocal state.
 const RELOCAL_DIR: &str = "~/relocal";
 pub const STATUS_CHECK_TRUE: &str = "__RELOCAL_STATUS_TRUE__";
 pub const STATUS_CHECK_FALSE: &str = "__RELOCAL_STATUS_FALSE__";
 
+/// ssh options for `strict_host_key`: refuse unknown or changed host keys
+/// instead of prompting, and never prompt for anything else either.
+pub const STRICT_HOST_KEY_ARGS: [&str; 4] =
+    ["-o", "StrictHostKeyChecking=yes", "-o", "BatchMode=yes"];
+
+/// stderr fragments ssh prints when it refuses the remote's host key.
+const HOST_KEY_ERRORS: &[&str] = &[
+    "Host key verification failed",
+    "REMOTE HOST IDENTIFICATION HAS CHANGED",
+];
+
+/// Whether ssh's stderr says it refused the remote's host key.
+pub fn is_host_key_failure(stderr: &str) -> bool {
+    HOST_KEY_ERRORS
+        .iter()
+        .any(|fragment| stderr.contains(fragment))
+}
+
 /// Returns the remote working directory path for a session.
 pub fn remote_work_dir(session: &str) -> String {
     format!("{RELOCAL_DIR}/{session}")
@@ -35,6 +54,21 @@ pub fn rm_work_dir(session: &str) -> String {
     format!("rm -rf {}", remote_work_dir(session))
 }
 
+/// Command to rename a session's working directory. Fails rather than moving
+/// `old` inside `new` if `new` already exists.
+pub fn mv_work_dir(old: &str, new: &str) -> String {
+    let new_dir = remote_work_dir(new);
+    format!(
+        "test ! -e {new_dir} && mv {} {new_dir}",
+        remote_work_dir(old)
+    )
+}
+
+/// Command to create the relocal base directory.
+pub fn mkdir_relocal_dir() -> String {
+    format!("mkdir -p {RELOCAL_DIR}")
+}
+
 /// Command to remove the entire relocal directory (nuke).
 pub fn rm_relocal_dir() -> String {
     format!("rm -rf {RELOCAL_DIR}")
@@ -48,30 +82,229 @@ fn lock_file_path(session: &str) -> String {
 /// Command to create a lock file for a session (fails if it already exists).
 ///
 /// Uses `set -o noclobber` so the redirect fails if the file exists, providing
-/// atomic stale-session detection without requiring external tools.
-pub fn create_lock_file(session: &str) -> String {
+/// atomic stale-session detection without requiring external tools. The lock
+/// holds the shell's pid and, on its second line, `heartbeat_secs`: how often
+/// the owner refreshes it (read back by [`lock_file_age`]).
+pub fn create_lock_file(session: &str, heartbeat_secs: u64) -> String {
     format!(
-        "mkdir -p {RELOCAL_DIR}/.locks && ( set -o noclobber; echo $$ > {} )",
+        "mkdir -p {RELOCAL_DIR}/.locks && ( set -o noclobber; printf '%s\\n%s\\n' $$ {heartbeat_secs} > {} )",
         lock_file_path(session)
     )
 }
 
+/// Command that prepares a session in one round trip: creates the working
+/// directory, writes its origin record (`origin_json`) and takes the lock
+/// file (see [`create_lock_file`]), stopping at the first step that fails.
+pub fn setup_session(session: &str, origin_json: &str, heartbeat_secs: u64) -> String {
+    format!(
+        "{} && {} && {}",
+        mkdir_work_dir(session),
+        write_origin_file(session, origin_json),
+        create_lock_file(session, heartbeat_secs)
+    )
+}
+
 /// Command to check whether a lock file exists for a session.
 pub fn check_lock_file_exists(session: &str) -> String {
     format!("test -e {}", lock_file_path(session))
 }
 
+/// Command to refresh a session's lock file mtime, the daemon's heartbeat.
+/// Does not recreate a lock that was removed (e.g. by `relocal destroy`).
+pub fn touch_lock_file(session: &str) -> String {
+    format!("touch -c {}", lock_file_path(session))
+}
+
+/// Command printing the seconds since a session's lock file was last
+/// touched, measured against the remote's clock, then the owner's heartbeat
+/// period on a second line. Locks from older versions record no period, so
+/// the second line is missing. Fails if there is no lock.
+pub fn lock_file_age(session: &str) -> String {
+    let path = lock_file_path(session);
+    format!("mtime=$(stat -c %Y {path}) && echo $(( $(date +%s) - mtime )) && sed -n 2p {path}")
+}
+
 /// Command to remove a session's lock file.
 pub fn remove_lock_file(session: &str) -> String {
     format!("rm -f {}", lock_file_path(session))
 }
 
+/// Command to remove lock files whose session working directory is gone.
+///
+/// Such a lock can never be released by a daemon (destroy and nuke remove the
+/// directory first), so it only blocks the next session. Locks for sessions
+/// that still have a directory are left alone: a daemon on another machine may
+/// hold them. Prints each removed session name on its own line.
+pub fn remove_orphan_lock_files() -> String {
+    format!(
+        "cd {RELOCAL_DIR}/.locks 2>/dev/null || exit 0; for f in *.lock; do [ -e \"$f\" ] || continue; s=${{f%.lock}}; if [ ! -d \"../$s\" ]; then rm -f \"$f\" && printf '%s\\n' \"$s\"; fi; done"
+    )
+}
+
+/// Path to the remote compatibility metadata file (see [`crate::remote_meta`]).
+fn meta_file_path() -> String {
+    format!("{RELOCAL_DIR}/.relocal-meta.json")
+}
+
+/// Command to print the remote metadata file, or nothing if it is absent.
+///
+/// Absence exits 0 with empty output so callers can tell "never written"
+/// apart from a failing `cat`.
+pub fn read_meta_file() -> String {
+    let path = meta_file_path();
+    format!("if [ -e {path} ]; then cat {path}; fi")
+}
+
+/// Command to (over)write the remote metadata file with `contents`.
+pub fn write_meta_file(contents: &str) -> String {
+    let quoted: String = contents.quoted(Bash);
+    format!(
+        "mkdir -p {RELOCAL_DIR} && printf '%s\\n' {quoted} > {}",
+        meta_file_path()
+    )
+}
+
 /// Command to list session directories with sizes.
 ///
 /// Output format: `<name>\t<size>` per line, e.g. `my-session\t4.0K`.
+/// Prints nothing and succeeds when `~/relocal/` does not exist yet, so a
+/// failing exit status always means the listing itself failed.
 pub fn list_sessions() -> String {
     format!(
-        "cd {RELOCAL_DIR} 2>/dev/null && for d in $(ls -1); do size=$(du -sh \"$d\" 2>/dev/null | cut -f1); printf '%s\\t%s\\n' \"$d\" \"$size\"; done"
+        "[ -d {RELOCAL_DIR} ] || exit 0; cd {RELOCAL_DIR} && for d in $(ls -1); do size=$(du -sh \"$d\" 2>/dev/null | cut -f1); printf '%s\\t%s\\n' \"$d\" \"$size\"; done"
+    )
+}
+
+/// Command to list session directories with their age and lock state.
+///
+/// Output format: `<name>\t<age-secs>\t<locked>` per line. The age counts
+/// from the newest timestamp relocal itself wrote for the session: the
+/// origin record (rewritten by every [`setup_session`]), the reconciling
+/// sync baseline and the lock file (the daemon's heartbeat). Only a session
+/// with none of them falls back to its directory's mtime, which pushes copy
+/// from the local repo root and so says little about activity. Ages use the
+/// remote's own clock, so local clock skew doesn't matter; `locked` is `1`
+/// when the session has a lock file. Like [`list_sessions`], prints nothing
+/// and succeeds when `~/relocal/` does not exist yet.
+pub fn list_sessions_with_age() -> String {
+    format!(
+        "[ -d {RELOCAL_DIR} ] || exit 0; cd {RELOCAL_DIR} && now=$(date +%s) && for d in $(ls -1); do [ -d \"$d\" ] || continue; mtime=$(stat -c %Y \"$d/.relocal/origin.json\" \"$d/.relocal/last-sync\" \".locks/$d.lock\" 2>/dev/null | sort -n | tail -n 1); [ -n \"$mtime\" ] || mtime=$(stat -c %Y \"$d\") || continue; locked=0; [ -e \".locks/$d.lock\" ] && locked=1; printf '%s\\t%s\\t%s\\n' \"$d\" \"$((now - mtime))\" \"$locked\"; done"
+    )
+}
+
+/// Path to a session's origin record, inside the always-excluded `.relocal/`.
+fn origin_file_path(session: &str) -> String {
+    format!("{}/.relocal/origin.json", remote_work_dir(session))
+}
+
+/// Command to (over)write a session's origin record with `contents`.
+pub fn write_origin_file(session: &str, contents: &str) -> String {
+    let quoted: String = contents.quoted(Bash);
+    format!(
+        "mkdir -p {}/.relocal && printf '%s\\n' {quoted} > {}",
+        remote_work_dir(session),
+        origin_file_path(session)
+    )
+}
+
+/// Command that starts a reconciling `relocal sync` (see
+/// [`crate::reconcile`]).
+///
+/// First records `local_now` (the local clock) in a pending baseline inside
+/// `.relocal/`, whose mtime marks this sync's start on the remote clock.
+/// Then, if a previous sync left a baseline, prints its recorded local time
+/// on the first line followed by every file and symlink modified since it,
+/// one `./`-prefixed path per line. Comparing against the baseline's mtime
+/// with `find -newer` keeps the remote side on the remote clock. Last come
+/// the paths that were on both sides after that sync, each prefixed with
+/// `=` (e.g. `=./src/lib.rs`).
+pub fn begin_reconcile(session: &str, local_now: u64) -> String {
+    format!(
+        "cd {} && mkdir -p .relocal && echo {local_now} > .relocal/sync-pending && if [ -f .relocal/last-sync ]; then cat .relocal/last-sync && find . -path ./.relocal -prune -o \\( -type f -o -type l \\) -newer .relocal/last-sync -print && {{ [ ! -f .relocal/last-sync-files ] || sed 's/^/=/' .relocal/last-sync-files; }}; fi",
+        remote_work_dir(session)
+    )
+}
+
+/// Command that promotes the pending baseline written by [`begin_reconcile`]
+/// once the reconciling sync has been applied.
+///
+/// Also records every remote file and symlink as present on both sides,
+/// except `remote_only`: paths the sync left on the remote alone.
+pub fn commit_reconcile(session: &str, remote_only: &[String]) -> String {
+    let mut list =
+        "find . -path ./.relocal -prune -o \\( -type f -o -type l \\) -print".to_string();
+    if !remote_only.is_empty() {
+        list.push_str(" | { grep -vxF");
+        for path in remote_only {
+            let quoted: String = format!("./{path}").as_str().quoted(Bash);
+            list.push_str(" -e ");
+            list.push_str(&quoted);
+        }
+        list.push_str(" || true; }");
+    }
+    format!(
+        "cd {} && {list} > .relocal/sync-files-pending && mv .relocal/sync-files-pending .relocal/last-sync-files && mv .relocal/sync-pending .relocal/last-sync",
+        remote_work_dir(session)
+    )
+}
+
+/// Like [`list_sessions`], with each session's origin record appended.
+///
+/// Output format: `<name>\t<size>\t<origin-json>` per line; the third field
+/// is empty when no origin was recorded. The JSON is single-line, so it
+/// contains no raw tabs or newlines.
+pub fn list_sessions_detail() -> String {
+    format!(
+        "[ -d {RELOCAL_DIR} ] || exit 0; cd {RELOCAL_DIR} && for d in $(ls -1); do size=$(du -sh \"$d\" 2>/dev/null | cut -f1); origin=$(cat \"$d/.relocal/origin.json\" 2>/dev/null | tr -d '\\n'); printf '%s\\t%s\\t%s\\n' \"$d\" \"$size\" \"$origin\"; done"
+    )
+}
+
+/// Command to mirror one session's working directory into another on the
+/// same remote, without a round trip through the local machine.
+///
+/// Each session keeps its own `.relocal/` metadata: it is neither copied nor
+/// deleted on the destination.
+pub fn copy_work_dir(from: &str, to: &str) -> String {
+    format!(
+        "rsync -a --delete --exclude=/.relocal/ {}/ {}/",
+        remote_work_dir(from),
+        remote_work_dir(to)
+    )
+}
+
+/// Command to check whether `path` is a directory on the remote.
+pub fn check_dir_exists(path: &str) -> String {
+    format!("test -d {}", shell_path(path))
+}
+
+/// Command to adopt the existing remote directory `path` as a session's
+/// working directory by symlinking it there, so nothing is copied. A relative
+/// `path` is taken from the remote home directory. Fails if the session
+/// already exists.
+pub fn link_work_dir(session: &str, path: &str) -> String {
+    let work_dir = remote_work_dir(session);
+    format!(
+        "mkdir -p {RELOCAL_DIR} && test ! -e {work_dir} && ln -s \"$(cd {} && pwd)\" {work_dir}",
+        shell_path(path)
+    )
+}
+
+/// Command that undoes [`link_work_dir`] and the origin record written
+/// through the link, leaving the adopted directory otherwise as it was. Does
+/// nothing unless the session's working directory is a symlink.
+pub fn unlink_work_dir(session: &str) -> String {
+    let work_dir = remote_work_dir(session);
+    format!(
+        "test -L {work_dir} && rm -f {work_dir}/.relocal/origin.json && {{ rmdir {work_dir}/.relocal 2>/dev/null || true; }} && rm {work_dir}"
+    )
+}
+
+/// Command to print the git origin URL of the remote directory `path`, or
+/// nothing if it has none.
+pub fn git_origin_url(path: &str) -> String {
+    format!(
+        "git -C {} config --get remote.origin.url || true",
+        shell_path(path)
     )
 }
 
@@ -91,6 +324,22 @@ fn wrap_status_check(command: &str) -> String {
     )
 }
 
+/// Command running every `relocal status` check in one round trip. Prints
+/// `dir=<0|1> claude=<0|1> codex=<0|1>`, `1` where the check passed.
+pub fn status_probe(session: &str, claude_bin: &str) -> String {
+    [
+        ("dir", check_work_dir_exists(session)),
+        ("claude", check_claude_installed(claude_bin)),
+        ("codex", check_codex_installed()),
+    ]
+    .iter()
+    .map(|(name, check)| {
+        format!("if {{ {check}; }} >/dev/null 2>&1; then printf '{name}=1 '; else printf '{name}=0 '; fi")
+    })
+    .collect::<Vec<_>>()
+    .join("; ")
+}
+
 /// Runs a wrapped SSH probe and returns whether the remote check succeeded.
 ///
 /// Many remote shell probes use exit code `1` to mean "absent" rather than
@@ -98,7 +347,7 @@ fn wrap_status_check(command: &str) -> String {
 /// into explicit markers and surfacing transport or shell-level failures as
 /// [`Error::Remote`].
 pub fn run_status_check(runner: &dyn CommandRunner, remote: &str, command: &str) -> Result<bool> {
-    let output = runner.run_ssh(remote, &wrap_status_check(command))?;
+    let output = runner.run_ssh_operation(Operation::Check, remote, &wrap_status_check(command))?;
 
     if !output.status.success() {
         let code = output.status.code().map_or_else(
@@ -140,9 +389,22 @@ pub fn git_fsck(session: &str) -> String {
     )
 }
 
-/// Command to check whether `claude` is on PATH.
-pub fn check_claude_installed() -> String {
-    "command -v claude".to_string()
+/// Shell word for a remote path such as `claude_bin`: quoted, except that a
+/// leading `~/` is left bare so the remote shell expands it.
+pub fn shell_path(program: &str) -> String {
+    match program.strip_prefix("~/") {
+        Some(rest) => {
+            let quoted: String = rest.quoted(Bash);
+            format!("~/{quoted}")
+        }
+        None => program.quoted(Bash),
+    }
+}
+
+/// Command to check whether `claude_bin` is on PATH (or, for a path, an
+/// executable).
+pub fn check_claude_installed(claude_bin: &str) -> String {
+    format!("command -v {}", shell_path(claude_bin))
 }
 
 /// Command to launch an interactive login shell in the working directory.
@@ -157,13 +419,23 @@ pub fn start_ssh_session(session: &str) -> String {
 
 /// Command to launch an interactive Claude session in the working directory.
 ///
-/// Any extra arguments are appended after `--dangerously-skip-permissions`,
-/// allowing the caller to pass flags like `--debug` through to `claude`.
-pub fn start_claude_session(session: &str, extra_args: &[String]) -> String {
+/// Runs `claude_bin`. With `skip_permissions`, it runs with
+/// `--dangerously-skip-permissions`. Any extra arguments are appended after
+/// it, allowing the caller to pass flags like `--debug` through to `claude`.
+pub fn start_claude_session(
+    session: &str,
+    claude_bin: &str,
+    skip_permissions: bool,
+    extra_args: &[String],
+) -> String {
     let mut cmd = format!(
-        "cd {} && claude --dangerously-skip-permissions",
-        remote_work_dir(session)
+        "cd {} && {}",
+        remote_work_dir(session),
+        shell_path(claude_bin)
     );
+    if skip_permissions {
+        cmd.push_str(" --dangerously-skip-permissions");
+    }
     for arg in extra_args {
         cmd.push(' ');
         let quoted: String = arg.as_str().quoted(Bash);
@@ -172,17 +444,93 @@ pub fn start_claude_session(session: &str, extra_args: &[String]) -> String {
     cmd
 }
 
+/// Name of the tmux session a detached session runs in on the remote.
+pub fn detached_tmux_name(session: &str) -> String {
+    format!("relocal-{session}")
+}
+
+/// Command to check whether `tmux` is on PATH.
+pub fn check_tmux_installed() -> String {
+    "command -v tmux".to_string()
+}
+
+/// Command to run `start_command` (e.g. from [`start_claude_session`]) in a
+/// new detached tmux session, which ends when the command exits. Fails if the
+/// session already has one running.
+pub fn start_detached_session(session: &str, start_command: &str) -> String {
+    let name: String = detached_tmux_name(session).quoted(Bash);
+    let command: String = start_command.quoted(Bash);
+    format!("tmux new-session -d -s {name} {command}")
+}
+
+/// Command to check whether a session's detached tmux session is still
+/// running. A remote without tmux has none.
+pub fn check_detached_session(session: &str) -> String {
+    let name: String = detached_tmux_name(session).quoted(Bash);
+    format!("command -v tmux >/dev/null && tmux has-session -t ={name}")
+}
+
+/// Command to attach the terminal to a session's detached tmux session.
+pub fn attach_detached_session(session: &str) -> String {
+    let name: String = detached_tmux_name(session).quoted(Bash);
+    format!("exec tmux attach-session -t ={name}")
+}
+
+/// Command to run `command` (argv form) in the session's working directory.
+///
+/// Every argument is shell-quoted, so `["ls", "my dir"]` lists one directory
+/// rather than two. Shell syntax (pipes, `&&`) is therefore not interpreted;
+/// wrap it explicitly as `["sh", "-c", "..."]` when needed.
+pub fn exec_in_work_dir(session: &str, command: &[String]) -> String {
+    let mut cmd = format!("cd {} &&", remote_work_dir(session));
+    for arg in command {
+        cmd.push(' ');
+        let quoted: String = arg.as_str().quoted(Bash);
+        cmd.push_str(&quoted);
+    }
+    cmd
+}
+
 /// Command to check whether `codex` is on PATH.
 pub fn check_codex_installed() -> String {
     "command -v codex".to_string()
 }
 
+/// Command to check whether rustup is on PATH.
+pub fn check_rustup_installed() -> String {
+    "command -v rustup".to_string()
+}
+
+/// Command that succeeds when `claude_bin` is logged in.
+pub fn check_claude_authenticated(claude_bin: &str) -> String {
+    format!("{} auth status", shell_path(claude_bin))
+}
+
+/// Command that succeeds when `codex` has stored credentials.
+pub fn check_codex_authenticated() -> String {
+    "test -e ~/.codex/auth.json".to_string()
+}
+
+/// Command to check whether the relocal base directory exists.
+pub fn check_relocal_dir_exists() -> String {
+    format!("test -d {RELOCAL_DIR}")
+}
+
+/// Command that prints the remote login user and hostname, one per line.
+pub fn whoami() -> String {
+    "whoami && hostname".to_string()
+}
+
 /// Command to launch an interactive Codex session in the working directory.
 ///
-/// Any extra arguments are appended after `--yolo`,
-/// allowing the caller to pass flags through to `codex`.
-pub fn start_codex_session(session: &str, extra_args: &[String]) -> String {
-    let mut cmd = format!("cd {} && codex --yolo", remote_work_dir(session));
+/// With `skip_permissions`, `codex` runs with `--yolo` (no approvals or
+/// sandbox). Any extra arguments are appended after it, allowing the caller
+/// to pass flags through to `codex`.
+pub fn start_codex_session(session: &str, skip_permissions: bool, extra_args: &[String]) -> String {
+    let mut cmd = format!("cd {} && codex", remote_work_dir(session));
+    if skip_permissions {
+        cmd.push_str(" --yolo");
+    }
     for arg in extra_args {
         cmd.push(' ');
         let quoted: String = arg.as_str().quoted(Bash);
@@ -276,6 +624,46 @@ pub fn acquire_flock(file: &std::fs::File) -> Result<()> {
     Ok(())
 }
 
+/// Opens a read/write file next to `near` and unlinks it, so it disappears
+/// once every handle (including a child's) is closed.
+fn unlinked_temp_file(near: &Path) -> Result<std::fs::File> {
+    let path = PathBuf::from(format!("{}.stderr", near.display()));
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path)?;
+    std::fs::remove_file(&path)?;
+    Ok(file)
+}
+
+/// Arguments for `ssh` to start a backgrounded ControlMaster on `socket_path`
+/// that lingers `control_persist` seconds after its last client.
+fn master_args(
+    remote: &str,
+    socket_path: &Path,
+    strict_host_key: bool,
+    control_persist: u64,
+) -> Vec<String> {
+    let mut args: Vec<String> = Vec::new();
+    if strict_host_key {
+        args.extend(STRICT_HOST_KEY_ARGS.map(String::from));
+    }
+    args.extend([
+        "-o".to_string(),
+        "ControlMaster=yes".to_string(),
+        "-o".to_string(),
+        format!("ControlPath={}", socket_path.display()),
+        "-o".to_string(),
+        format!("ControlPersist={control_persist}"),
+        "-N".to_string(),
+        "-f".to_string(),
+        remote.to_string(),
+    ]);
+    args
+}
+
 /// Manages a persistent SSH ControlMaster connection.
 ///
 /// All SSH and rsync commands during a session can share this connection,
@@ -289,11 +677,17 @@ impl SshControlMaster {
     /// Establishes a ControlMaster with a deterministic socket path (no PID).
     ///
     /// Used by the session daemon so that all clients resolve to the same
-    /// ControlMaster. Standalone commands should use [`start`] instead to
-    /// avoid colliding with a running daemon.
-    pub fn start_shared(remote: &str, session: &str) -> Result<Self> {
+    /// ControlMaster. Standalone commands should use [`start`](Self::start)
+    /// (via [`ProcessRunner::with_control_master`](crate::runner::ProcessRunner::with_control_master))
+    /// instead to avoid colliding with a running daemon.
+    pub fn start_shared(
+        remote: &str,
+        session: &str,
+        strict_host_key: bool,
+        control_persist: u64,
+    ) -> Result<Self> {
         let socket_path = shared_control_socket_path(session, remote);
-        Self::start_with_path(remote, socket_path)
+        Self::start_with_path(remote, socket_path, strict_host_key, control_persist)
     }
 
     /// Establishes a ControlMaster connection to the remote.
@@ -302,27 +696,50 @@ impl SshControlMaster {
     /// open. The socket path is kept short to stay under the 104-byte Unix
     /// socket limit on macOS: `rlc-<prefix>-<hash>` where prefix is up to 20
     /// chars of the session name and hash encodes session+PID.
-    pub fn start(remote: &str, session: &str) -> Result<Self> {
+    pub fn start(
+        remote: &str,
+        session: &str,
+        strict_host_key: bool,
+        control_persist: u64,
+    ) -> Result<Self> {
         let socket_path = Self::socket_path_for(session);
-        Self::start_with_path(remote, socket_path)
+        Self::start_with_path(remote, socket_path, strict_host_key, control_persist)
     }
 
-    fn start_with_path(remote: &str, socket_path: PathBuf) -> Result<Self> {
+    /// Every later connection multiplexes over the master, so this is where
+    /// `strict_host_key` takes effect.
+    ///
+    /// ssh's stderr goes to an unlinked temp file rather than a pipe: the
+    /// backgrounded master keeps it open, so reading a pipe to EOF would
+    /// block for the master's lifetime.
+    fn start_with_path(
+        remote: &str,
+        socket_path: PathBuf,
+        strict_host_key: bool,
+        control_persist: u64,
+    ) -> Result<Self> {
+        let mut stderr_file = unlinked_temp_file(&socket_path)?;
         let status = Command::new("ssh")
-            .args([
-                "-o",
-                "ControlMaster=yes",
-                "-o",
-                &format!("ControlPath={}", socket_path.display()),
-                "-o",
-                "ControlPersist=300",
-                "-N",
-                "-f",
+            .args(master_args(
                 remote,
-            ])
+                &socket_path,
+                strict_host_key,
+                control_persist,
+            ))
+            .stderr(stderr_file.try_clone()?)
             .status()?;
 
+        let mut stderr = String::new();
+        stderr_file.seek(SeekFrom::Start(0))?;
+        stderr_file.read_to_string(&mut stderr)?;
+        eprint!("{stderr}");
+
         if !status.success() {
+            if is_host_key_failure(&stderr) {
+                return Err(Error::HostKeyMismatch {
+                    remote: remote.to_string(),
+                });
+            }
             return Err(Error::CommandFailed {
                 command: "ssh ControlMaster".to_string(),
                 message: format!("failed to establish ControlMaster to {remote}"),
@@ -395,6 +812,23 @@ mod tests {
         path
     }
 
+    #[test]
+    fn master_args_carry_control_persist() {
+        let args = master_args("u@h", Path::new("/tmp/rlc-s1"), false, 45);
+        let persist = args.iter().position(|a| a == "ControlPersist=45").unwrap();
+        assert_eq!(args[persist - 1], "-o");
+        assert!(args.contains(&"ControlPath=/tmp/rlc-s1".to_string()));
+        assert_eq!(args.last().map(String::as_str), Some("u@h"));
+        assert!(!args.iter().any(|a| a.starts_with("StrictHostKeyChecking")));
+    }
+
+    #[test]
+    fn master_args_with_strict_host_key() {
+        let args = master_args("u@h", Path::new("/tmp/rlc-s1"), true, 300);
+        assert_eq!(&args[..4], STRICT_HOST_KEY_ARGS);
+        assert!(args.contains(&"ControlPersist=300".to_string()));
+    }
+
     #[test]
     fn control_socket_path_fits_unix_limit() {
         // Unix socket paths max out at 104 bytes on macOS.
@@ -432,9 +866,39 @@ mod tests {
         assert_eq!(lock_file_path("s1"), "~/relocal/.locks/s1.lock");
     }
 
+    #[test]
+    fn write_origin_file_targets_relocal_dir() {
+        let cmd = write_origin_file("s1", r#"{"remote":"h"}"#);
+        assert!(cmd.starts_with("mkdir -p ~/relocal/s1/.relocal && printf"));
+        assert!(cmd.ends_with("> ~/relocal/s1/.relocal/origin.json"));
+    }
+
+    #[test]
+    fn list_sessions_detail_reads_origin() {
+        let cmd = list_sessions_detail();
+        assert!(cmd.contains("du -sh"));
+        assert!(cmd.contains(".relocal/origin.json"));
+    }
+
+    #[test]
+    fn read_meta_file_format() {
+        assert_eq!(
+            read_meta_file(),
+            "if [ -e ~/relocal/.relocal-meta.json ]; then cat ~/relocal/.relocal-meta.json; fi"
+        );
+    }
+
+    #[test]
+    fn write_meta_file_quotes_contents() {
+        let cmd = write_meta_file(r#"{"protocol_version":1}"#);
+        assert!(cmd.starts_with("mkdir -p ~/relocal && printf"));
+        assert!(cmd.ends_with("> ~/relocal/.relocal-meta.json"));
+        assert!(!cmd.contains(r#" {"protocol_version":1} "#));
+    }
+
     #[test]
     fn create_lock_file_format() {
-        let cmd = create_lock_file("s1");
+        let cmd = create_lock_file("s1", 30);
         assert!(cmd.contains("mkdir -p"));
         assert!(cmd.contains(".locks"));
         assert!(cmd.contains("noclobber"));
@@ -448,6 +912,48 @@ mod tests {
         assert!(cmd.contains("s1.lock"));
     }
 
+    #[test]
+    fn remove_orphan_lock_files_format() {
+        let cmd = remove_orphan_lock_files();
+        assert!(cmd.starts_with("cd ~/relocal/.locks 2>/dev/null || exit 0;"));
+        assert!(cmd.contains("[ ! -d \"../$s\" ]"));
+        assert!(cmd.contains("rm -f \"$f\""));
+    }
+
+    #[test]
+    fn remove_orphan_lock_files_only_removes_locks_without_a_dir() {
+        let home = tempfile::tempdir().unwrap();
+        let locks = home.path().join("relocal/.locks");
+        std::fs::create_dir_all(&locks).unwrap();
+        std::fs::create_dir(home.path().join("relocal/live")).unwrap();
+        std::fs::write(locks.join("live.lock"), "1").unwrap();
+        std::fs::write(locks.join("gone.lock"), "1").unwrap();
+
+        let output = Command::new("bash")
+            .args(["-c", &remove_orphan_lock_files()])
+            .env("HOME", home.path())
+            .output()
+            .unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "gone\n");
+        assert!(locks.join("live.lock").exists());
+        assert!(!locks.join("gone.lock").exists());
+    }
+
+    #[test]
+    fn remove_orphan_lock_files_without_locks_dir_is_a_noop() {
+        let home = tempfile::tempdir().unwrap();
+        let output = Command::new("bash")
+            .args(["-c", &remove_orphan_lock_files()])
+            .env("HOME", home.path())
+            .output()
+            .unwrap();
+
+        assert!(output.status.success());
+        assert!(output.stdout.is_empty());
+    }
+
     #[test]
     fn remote_work_dir_format() {
         assert_eq!(remote_work_dir("my-proj"), "~/relocal/my-proj");
@@ -463,6 +969,71 @@ mod tests {
         assert_eq!(rm_work_dir("s1"), "rm -rf ~/relocal/s1");
     }
 
+    #[test]
+    fn link_work_dir_format() {
+        assert_eq!(
+            link_work_dir("s1", "~/src/my app"),
+            "mkdir -p ~/relocal && test ! -e ~/relocal/s1 && ln -s \"$(cd ~/$'src/my app' && pwd)\" ~/relocal/s1"
+        );
+    }
+
+    #[test]
+    fn unlink_work_dir_removes_only_the_link_and_origin() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(home.path().join("work/app")).unwrap();
+        std::fs::write(home.path().join("work/app/main.rs"), "").unwrap();
+        let bash = |cmd: &str| {
+            Command::new("bash")
+                .args(["-c", cmd])
+                .env("HOME", home.path())
+                .output()
+                .unwrap()
+        };
+        assert!(bash(&link_work_dir("s1", "~/work/app")).status.success());
+        assert!(bash(&write_origin_file("s1", "{}")).status.success());
+
+        assert!(bash(&unlink_work_dir("s1")).status.success());
+        assert!(!home.path().join("relocal/s1").exists());
+        assert!(home.path().join("work/app/main.rs").exists());
+        assert!(!home.path().join("work/app/.relocal").exists());
+        // A real session directory is never touched.
+        std::fs::create_dir_all(home.path().join("relocal/s2")).unwrap();
+        assert!(!bash(&unlink_work_dir("s2")).status.success());
+        assert!(home.path().join("relocal/s2").exists());
+    }
+
+    #[test]
+    fn mv_work_dir_format() {
+        assert_eq!(
+            mv_work_dir("a", "b"),
+            "test ! -e ~/relocal/b && mv ~/relocal/a ~/relocal/b"
+        );
+    }
+
+    #[test]
+    fn mv_work_dir_refuses_existing_destination() {
+        let home = tempfile::tempdir().unwrap();
+        let relocal = home.path().join("relocal");
+        std::fs::create_dir_all(relocal.join("a")).unwrap();
+        std::fs::create_dir(relocal.join("b")).unwrap();
+
+        let run = || {
+            Command::new("bash")
+                .args(["-c", &mv_work_dir("a", "b")])
+                .env("HOME", home.path())
+                .status()
+                .unwrap()
+        };
+        assert!(!run().success());
+        assert!(relocal.join("a").is_dir());
+        assert!(!relocal.join("b/a").exists());
+
+        std::fs::remove_dir(relocal.join("b")).unwrap();
+        assert!(run().success());
+        assert!(!relocal.join("a").exists());
+        assert!(relocal.join("b").is_dir());
+    }
+
     #[test]
     fn list_sessions_format() {
         let cmd = list_sessions();
@@ -471,6 +1042,211 @@ mod tests {
         assert!(!cmd.contains("grep -v"));
     }
 
+    #[test]
+    fn list_sessions_succeed_without_relocal_dir() {
+        let home = tempfile::tempdir().unwrap();
+        for cmd in [
+            list_sessions(),
+            list_sessions_detail(),
+            list_sessions_with_age(),
+        ] {
+            let output = Command::new("bash")
+                .args(["-c", &cmd])
+                .env("HOME", home.path())
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{cmd}");
+            assert!(output.stdout.is_empty(), "{cmd}");
+        }
+    }
+
+    #[test]
+    fn list_sessions_with_age_reports_age_and_lock() {
+        let home = tempfile::tempdir().unwrap();
+        let relocal = home.path().join("relocal");
+        std::fs::create_dir_all(relocal.join(".locks")).unwrap();
+        std::fs::create_dir(relocal.join("busy")).unwrap();
+        std::fs::create_dir(relocal.join("idle")).unwrap();
+        std::fs::write(relocal.join(".locks/busy.lock"), "1").unwrap();
+        std::fs::write(relocal.join("stray-file"), "").unwrap();
+
+        let output = Command::new("bash")
+            .args(["-c", &list_sessions_with_age()])
+            .env("HOME", home.path())
+            .output()
+            .unwrap();
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let lines: Vec<Vec<&str>> = stdout.lines().map(|l| l.split('\t').collect()).collect();
+        assert_eq!(lines.len(), 2, "{stdout}");
+        assert_eq!((lines[0][0], lines[0][2]), ("busy", "1"));
+        assert_eq!((lines[1][0], lines[1][2]), ("idle", "0"));
+        for line in &lines {
+            assert!(line[1].parse::<u64>().unwrap() < 60, "{stdout}");
+        }
+    }
+
+    #[test]
+    fn list_sessions_with_age_ignores_directory_mtime_copied_by_rsync() {
+        let home = tempfile::tempdir().unwrap();
+        let relocal = home.path().join("relocal");
+        std::fs::create_dir_all(relocal.join("pushed/.relocal")).unwrap();
+        std::fs::create_dir_all(relocal.join("stale/.relocal")).unwrap();
+        std::fs::write(relocal.join("pushed/.relocal/last-sync"), "1\n").unwrap();
+        std::fs::write(relocal.join("stale/.relocal/origin.json"), "{}").unwrap();
+        let bash = |cmd: &str| {
+            Command::new("bash")
+                .args(["-c", cmd])
+                .env("HOME", home.path())
+                .output()
+                .unwrap()
+        };
+        // rsync -t gave `pushed` the old mtime of the local repo root, but it
+        // synced just now; `stale` was set up long ago with a fresh mtime.
+        assert!(bash(
+            "touch -d '30 days ago' ~/relocal/pushed ~/relocal/stale/.relocal/origin.json"
+        )
+        .status
+        .success());
+
+        let output = bash(&list_sessions_with_age());
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let ages: Vec<(&str, u64)> = stdout
+            .lines()
+            .map(|l| {
+                let fields: Vec<&str> = l.split('\t').collect();
+                (fields[0], fields[1].parse().unwrap())
+            })
+            .collect();
+        assert_eq!(ages[0].0, "pushed");
+        assert!(ages[0].1 < 60, "{stdout}");
+        assert_eq!(ages[1].0, "stale");
+        assert!(ages[1].1 > 29 * 24 * 3600, "{stdout}");
+    }
+
+    #[test]
+    fn reconcile_baseline_lists_files_changed_since_last_sync() {
+        let home = tempfile::tempdir().unwrap();
+        let work = home.path().join("relocal/s1");
+        std::fs::create_dir_all(work.join("src")).unwrap();
+        std::fs::write(work.join("old.txt"), "").unwrap();
+        std::fs::write(work.join("conflict.txt"), "").unwrap();
+        let bash = |cmd: &str| {
+            Command::new("bash")
+                .args(["-c", cmd])
+                .env("HOME", home.path())
+                .output()
+                .unwrap()
+        };
+
+        // No baseline yet: nothing printed, pending baseline recorded.
+        let output = bash(&begin_reconcile("s1", 100));
+        assert!(output.status.success());
+        assert!(output.stdout.is_empty());
+        let commit = bash(&commit_reconcile("s1", &["conflict.txt".to_string()]));
+        assert!(commit.status.success());
+        assert_eq!(
+            std::fs::read_to_string(work.join(".relocal/last-sync")).unwrap(),
+            "100\n"
+        );
+        // The conflict left on the remote alone is not recorded as synced.
+        assert_eq!(
+            std::fs::read_to_string(work.join(".relocal/last-sync-files")).unwrap(),
+            "./old.txt\n"
+        );
+
+        // Backdate the baseline so the new file is strictly newer.
+        assert!(
+            bash("touch -d '1 hour ago' ~/relocal/s1/.relocal/last-sync ~/relocal/s1/*.txt")
+                .status
+                .success()
+        );
+        std::fs::write(work.join("src/new.txt"), "").unwrap();
+        let output = bash(&begin_reconcile("s1", 200));
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "100\n./src/new.txt\n=./old.txt\n"
+        );
+        assert!(work.join(".relocal/sync-pending").exists());
+    }
+
+    #[test]
+    fn start_detached_session_quotes_the_launch_command() {
+        let launch =
+            start_claude_session("s1", "claude", true, &["--model".into(), "opus x".into()]);
+        let cmd = start_detached_session("s1", &launch);
+        assert!(
+            cmd.starts_with("tmux new-session -d -s relocal-s1 "),
+            "{cmd}"
+        );
+
+        // The launch command survives as a single argument.
+        let output = Command::new("bash")
+            .args([
+                "-c",
+                &cmd.replace("tmux new-session -d -s", "printf '%s\\n'"),
+            ])
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            format!("relocal-s1\n{launch}\n")
+        );
+    }
+
+    #[test]
+    fn detached_session_probe_and_attach_use_exact_name() {
+        assert_eq!(
+            check_detached_session("s1"),
+            "command -v tmux >/dev/null && tmux has-session -t =relocal-s1"
+        );
+        assert_eq!(
+            attach_detached_session("s1"),
+            "exec tmux attach-session -t =relocal-s1"
+        );
+    }
+
+    #[test]
+    fn lock_file_age_and_touch() {
+        let home = tempfile::tempdir().unwrap();
+        let locks = home.path().join("relocal/.locks");
+        std::fs::create_dir_all(&locks).unwrap();
+        let bash = |cmd: &str| {
+            Command::new("bash")
+                .args(["-c", cmd])
+                .env("HOME", home.path())
+                .output()
+                .unwrap()
+        };
+
+        assert!(!bash(&lock_file_age("s1")).status.success());
+        // touch -c does not create a missing lock.
+        assert!(bash(&touch_lock_file("s1")).status.success());
+        assert!(!locks.join("s1.lock").exists());
+
+        std::fs::write(locks.join("s1.lock"), "1").unwrap();
+        let output = bash(&lock_file_age("s1"));
+        assert!(output.status.success());
+        let age: u64 = String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse()
+            .unwrap();
+        assert!(age < 60);
+
+        // A lock written by create_lock_file reports its heartbeat period.
+        std::fs::remove_file(locks.join("s1.lock")).unwrap();
+        assert!(bash(&create_lock_file("s1", 600)).status.success());
+        let output = bash(&lock_file_age("s1"));
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines.len(), 2, "{stdout}");
+        assert_eq!(lines[1], "600");
+    }
+
     #[test]
     fn start_ssh_session_format() {
         let cmd = start_ssh_session("s1");
@@ -479,7 +1255,7 @@ mod tests {
 
     #[test]
     fn start_claude_session_format() {
-        let cmd = start_claude_session("s1", &[]);
+        let cmd = start_claude_session("s1", "claude", true, &[]);
         assert!(cmd.contains("cd ~/relocal/s1"));
         assert!(cmd.contains("claude --dangerously-skip-permissions"));
     }
@@ -487,11 +1263,86 @@ mod tests {
     #[test]
     fn start_claude_session_with_extra_args() {
         let args = vec!["--debug".to_string(), "--resume".to_string()];
-        let cmd = start_claude_session("s1", &args);
+        let cmd = start_claude_session("s1", "claude", true, &args);
         assert!(cmd.contains("claude --dangerously-skip-permissions"));
         assert!(cmd.ends_with(" --debug --resume"));
     }
 
+    #[test]
+    fn start_claude_session_without_skip_permissions() {
+        let args = vec!["--debug".to_string()];
+        let cmd = start_claude_session("s1", "claude", false, &args);
+        assert_eq!(cmd, "cd ~/relocal/s1 && claude --debug");
+        assert!(!cmd.contains("--dangerously-skip-permissions"));
+    }
+
+    #[test]
+    fn copy_work_dir_format() {
+        assert_eq!(
+            copy_work_dir("a", "b"),
+            "rsync -a --delete --exclude=/.relocal/ ~/relocal/a/ ~/relocal/b/"
+        );
+    }
+
+    #[test]
+    fn exec_in_work_dir_format() {
+        let cmd = exec_in_work_dir("s1", &["ls".to_string(), "-la".to_string()]);
+        assert_eq!(cmd, "cd ~/relocal/s1 && ls -la");
+    }
+
+    #[test]
+    fn exec_in_work_dir_quotes_each_argument() {
+        let cmd = exec_in_work_dir("s1", &["echo".to_string(), "a b; rm -rf /".to_string()]);
+        assert!(cmd.starts_with("cd ~/relocal/s1 && echo "));
+        assert!(!cmd.contains(" a b; rm -rf /"));
+    }
+
+    #[test]
+    fn check_rustup_installed_format() {
+        assert_eq!(check_rustup_installed(), "command -v rustup");
+    }
+
+    #[test]
+    fn check_relocal_dir_exists_format() {
+        assert_eq!(check_relocal_dir_exists(), "test -d ~/relocal");
+    }
+
+    #[test]
+    fn status_probe_runs_every_check() {
+        let probe = status_probe("s1", "claude");
+        assert!(probe.contains("if { test -d ~/relocal/s1; }"), "{probe}");
+        assert!(probe.contains("if { command -v claude; }"), "{probe}");
+        assert!(probe.contains("if { command -v codex; }"), "{probe}");
+        for name in ["dir", "claude", "codex"] {
+            assert!(probe.contains(&format!("printf '{name}=1 '")), "{probe}");
+            assert!(probe.contains(&format!("printf '{name}=0 '")), "{probe}");
+        }
+    }
+
+    #[test]
+    fn status_probe_output_format() {
+        let output = std::process::Command::new("sh")
+            .args(["-c", &status_probe("no-such-session-xyz", "sh")])
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.starts_with("dir=0 claude=1 codex="), "{stdout}");
+    }
+
+    #[test]
+    fn setup_session_chains_steps() {
+        let command = setup_session("s1", "{}", 30);
+        assert_eq!(
+            command,
+            format!(
+                "{} && {} && {}",
+                mkdir_work_dir("s1"),
+                write_origin_file("s1", "{}"),
+                create_lock_file("s1", 30)
+            )
+        );
+    }
+
     #[test]
     fn check_codex_installed_format() {
         assert_eq!(check_codex_installed(), "command -v codex");
@@ -499,7 +1350,7 @@ mod tests {
 
     #[test]
     fn start_codex_session_format() {
-        let cmd = start_codex_session("s1", &[]);
+        let cmd = start_codex_session("s1", true, &[]);
         assert!(cmd.contains("cd ~/relocal/s1"));
         assert!(cmd.contains("codex --yolo"));
     }
@@ -507,11 +1358,17 @@ mod tests {
     #[test]
     fn start_codex_session_with_extra_args() {
         let args = vec!["--model".to_string(), "o3".to_string()];
-        let cmd = start_codex_session("s1", &args);
+        let cmd = start_codex_session("s1", true, &args);
         assert!(cmd.contains("codex --yolo"));
         assert!(cmd.ends_with(" --model o3"));
     }
 
+    #[test]
+    fn start_codex_session_without_skip_permissions() {
+        let cmd = start_codex_session("s1", false, &[]);
+        assert_eq!(cmd, "cd ~/relocal/s1 && codex");
+    }
+
     #[test]
     fn git_fsck_format() {
         let cmd = git_fsck("s1");
@@ -523,7 +1380,23 @@ mod tests {
 
     #[test]
     fn check_claude_installed_format() {
-        assert_eq!(check_claude_installed(), "command -v claude");
+        assert_eq!(check_claude_installed("claude"), "command -v claude");
+    }
+
+    #[test]
+    fn claude_bin_paths_keep_tilde_expansion() {
+        assert_eq!(
+            check_claude_installed("~/.local/bin/claude"),
+            "command -v ~/.local/bin/claude"
+        );
+        assert_eq!(
+            check_claude_authenticated("/opt/my tools/claude"),
+            "$'/opt/my tools/claude' auth status"
+        );
+        assert_eq!(
+            start_claude_session("s1", "~/bin/my claude", false, &[]),
+            "cd ~/relocal/s1 && ~/$'bin/my claude'"
+        );
     }
 
     #[test]
@@ -554,6 +1427,7 @@ mod tests {
 
         let result = run_status_check(&mock, "user@host", "test -d ~/relocal/s1").unwrap();
         assert!(!result);
+        assert_eq!(mock.ssh_operations(), vec![Operation::Check]);
     }
 
     #[test]
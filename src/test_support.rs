@@ -10,7 +10,7 @@ use std::process::ExitStatus;
 
 use crate::error::{Error, Result};
 use crate::rsync::{Direction, RsyncParams};
-use crate::runner::{CommandOutput, CommandRunner};
+use crate::runner::{CommandOutput, CommandRunner, Operation};
 
 /// What kind of command was invoked.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -27,6 +27,8 @@ pub enum Invocation {
         args: Vec<String>,
         direction: Direction,
         local_path: PathBuf,
+        /// Run through `run_rsync_streaming`.
+        streamed: bool,
     },
     Local {
         program: String,
@@ -42,6 +44,8 @@ pub enum MockResponse {
     OkWithStderr(String, String),
     /// Return a `CommandOutput` with a non-zero exit status.
     Fail(String),
+    /// Return a `CommandOutput` with the given exit code and stderr.
+    Exit(i32, String),
     /// Return an `Err(Error::CommandFailed { .. })`.
     Err(String),
 }
@@ -60,6 +64,14 @@ fn failure_status() -> ExitStatus {
         .expect("failed to run `false`")
 }
 
+/// Creates an `ExitStatus` with the given exit code.
+fn exit_status(code: i32) -> ExitStatus {
+    std::process::Command::new("sh")
+        .args(["-c", &format!("exit {code}")])
+        .status()
+        .expect("failed to run `sh`")
+}
+
 /// A fake [`CommandRunner`] for unit tests.
 ///
 /// Enqueue expected responses with [`MockRunner::add_response`]. Each call to
@@ -70,6 +82,8 @@ fn failure_status() -> ExitStatus {
 /// Panics if a method is called with no responses remaining.
 pub struct MockRunner {
     invocations: RefCell<Vec<Invocation>>,
+    /// The [`Operation`] of each non-interactive ssh call, in order.
+    operations: RefCell<Vec<Operation>>,
     responses: RefCell<Vec<MockResponse>>,
 }
 
@@ -83,6 +97,7 @@ impl MockRunner {
     pub fn new() -> Self {
         Self {
             invocations: RefCell::new(Vec::new()),
+            operations: RefCell::new(Vec::new()),
             responses: RefCell::new(Vec::new()),
         }
     }
@@ -95,6 +110,12 @@ impl MockRunner {
         self.invocations.borrow().clone()
     }
 
+    /// The [`Operation`] each `run_ssh`/`run_ssh_operation` call was tagged
+    /// with (`Other` for plain `run_ssh`).
+    pub fn ssh_operations(&self) -> Vec<Operation> {
+        self.operations.borrow().clone()
+    }
+
     fn next_response(&self) -> MockResponse {
         let mut responses = self.responses.borrow_mut();
         assert!(
@@ -121,6 +142,11 @@ impl MockRunner {
                 stderr,
                 status: failure_status(),
             }),
+            MockResponse::Exit(code, stderr) => Ok(CommandOutput {
+                stdout: String::new(),
+                stderr,
+                status: exit_status(code),
+            }),
             MockResponse::Err(message) => Err(Error::CommandFailed {
                 command: "mock".to_string(),
                 message,
@@ -129,12 +155,35 @@ impl MockRunner {
     }
 }
 
+impl MockRunner {
+    fn record_rsync(&self, params: &RsyncParams, streamed: bool) -> Result<CommandOutput> {
+        self.invocations.borrow_mut().push(Invocation::Rsync {
+            args: params.args().to_vec(),
+            direction: params.direction(),
+            local_path: params.local_path().to_path_buf(),
+            streamed,
+        });
+        let response = self.next_response();
+        self.respond(response)
+    }
+}
+
 impl CommandRunner for MockRunner {
     fn run_ssh(&self, remote: &str, command: &str) -> Result<CommandOutput> {
+        self.run_ssh_operation(Operation::Other, remote, command)
+    }
+
+    fn run_ssh_operation(
+        &self,
+        operation: Operation,
+        remote: &str,
+        command: &str,
+    ) -> Result<CommandOutput> {
         self.invocations.borrow_mut().push(Invocation::Ssh {
             remote: remote.to_string(),
             command: command.to_string(),
         });
+        self.operations.borrow_mut().push(operation);
         let response = self.next_response();
         self.respond(response)
     }
@@ -150,6 +199,7 @@ impl CommandRunner for MockRunner {
         match response {
             MockResponse::Ok(_) | MockResponse::OkWithStderr(_, _) => Ok(success_status()),
             MockResponse::Fail(_) => Ok(failure_status()),
+            MockResponse::Exit(code, _) => Ok(exit_status(code)),
             MockResponse::Err(message) => Err(Error::CommandFailed {
                 command: "mock".to_string(),
                 message,
@@ -158,13 +208,12 @@ impl CommandRunner for MockRunner {
     }
 
     fn run_rsync(&self, params: &RsyncParams) -> Result<CommandOutput> {
-        self.invocations.borrow_mut().push(Invocation::Rsync {
-            args: params.args().to_vec(),
-            direction: params.direction(),
-            local_path: params.local_path().to_path_buf(),
-        });
-        let response = self.next_response();
-        self.respond(response)
+        self.record_rsync(params, false)
+    }
+
+    /// Buffered like `run_rsync`; only the recorded invocation differs.
+    fn run_rsync_streaming(&self, params: &RsyncParams) -> Result<CommandOutput> {
+        self.record_rsync(params, true)
     }
 
     fn run_local(&self, program: &str, args: &[&str]) -> Result<CommandOutput> {
@@ -0,0 +1,78 @@
+//! Tests that run the `relocal` binary against stand-in `ssh` and `rsync`
+//! scripts, for behavior that only shows end to end (output, exit codes).
+//! Unlike `integration.rs`, these need no remote.
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::Command;
+
+/// Writes an executable `#!/bin/sh` script named `name` into `dir`.
+fn write_script(dir: &Path, name: &str, body: &str) {
+    let path = dir.join(name);
+    std::fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+/// A repo with a relocal.toml, plus a `bin/` of fake `ssh` (always
+/// succeeds) and `rsync` (reports one new file) to put first on `PATH`.
+fn fake_env() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let bin = dir.path().join("bin");
+    let repo = dir.path().join("repo");
+    std::fs::create_dir_all(&bin).unwrap();
+    std::fs::create_dir_all(&repo).unwrap();
+    write_script(&bin, "ssh", "exit 0");
+    write_script(&bin, "rsync", "echo '>f+++++++++ main.rs'");
+    std::fs::write(repo.join("relocal.toml"), "remote = \"user@host\"\n").unwrap();
+    std::fs::write(repo.join("main.rs"), "fn main() {}\n").unwrap();
+    dir
+}
+
+/// Runs `relocal` with `args` in the fake environment's repo.
+fn relocal(env: &Path, args: &[&str]) -> std::process::Output {
+    let path = format!(
+        "{}:{}",
+        env.join("bin").display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    Command::new(env!("CARGO_BIN_EXE_relocal"))
+        .args(args)
+        .current_dir(env.join("repo"))
+        .env("HOME", env)
+        .env("PATH", path)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn usage_errors_and_a_missing_repo_root_exit_differently() {
+    let env = fake_env();
+    let elsewhere = env.path().join("elsewhere");
+    std::fs::create_dir(&elsewhere).unwrap();
+
+    let usage = relocal(env.path(), &["sync", "push", "--no-such-flag"]);
+    assert_eq!(usage.status.code(), Some(2), "{usage:?}");
+
+    let no_root = Command::new(env!("CARGO_BIN_EXE_relocal"))
+        .arg("status")
+        .current_dir(&elsewhere)
+        .env("HOME", env.path())
+        .output()
+        .unwrap();
+    assert_eq!(no_root.status.code(), Some(17), "{no_root:?}");
+}
+
+#[test]
+fn quiet_dry_run_still_prints_its_report() {
+    let env = fake_env();
+
+    let output = relocal(env.path(), &["--quiet", "sync", "push", "--dry-run"]);
+
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("Dry run: 1 change(s) would be made:"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("main.rs"), "{stdout}");
+}
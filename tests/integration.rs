@@ -13,10 +13,12 @@
 
 use std::sync::Arc;
 
-use relocal::commands::{destroy, nuke, sync};
+use relocal::commands::destroy::{self, DestroyOptions};
+use relocal::commands::{nuke, sync};
 use relocal::config::Config;
-use relocal::daemon;
+use relocal::daemon::{self, DaemonOptions};
 use relocal::daemon_client;
+use relocal::rsync::SyncOptions;
 use relocal::runner::{CommandRunner, ProcessRunner};
 use relocal::sidecar::Sidecar;
 use relocal::ssh;
@@ -166,7 +168,14 @@ fn push_files_appear_on_remote() {
     // Create a local file
     std::fs::write(dir.path().join("hello.txt"), "world").unwrap();
 
-    sync::sync_push(&runner, &config, &session, dir.path(), false).unwrap();
+    sync::sync_push(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
 
     let content = read_remote_file(&remote, &format!("{}/hello.txt", remote_dir(&session)));
     assert_eq!(content.as_deref(), Some("world"));
@@ -187,7 +196,14 @@ fn push_deletes_propagate() {
 
     // Push a file
     std::fs::write(dir.path().join("delete-me.txt"), "temp").unwrap();
-    sync::sync_push(&runner, &config, &session, dir.path(), false).unwrap();
+    sync::sync_push(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
     assert!(remote_file_exists(
         &remote,
         &format!("{}/delete-me.txt", remote_dir(&session))
@@ -195,7 +211,14 @@ fn push_deletes_propagate() {
 
     // Delete locally and push again
     std::fs::remove_file(dir.path().join("delete-me.txt")).unwrap();
-    sync::sync_push(&runner, &config, &session, dir.path(), false).unwrap();
+    sync::sync_push(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
     assert!(!remote_file_exists(
         &remote,
         &format!("{}/delete-me.txt", remote_dir(&session))
@@ -219,7 +242,14 @@ fn push_respects_gitignore() {
     std::fs::write(dir.path().join("app.log"), "log data").unwrap();
     std::fs::write(dir.path().join("keep.txt"), "keep").unwrap();
 
-    sync::sync_push(&runner, &config, &session, dir.path(), false).unwrap();
+    sync::sync_push(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
 
     assert!(!remote_file_exists(
         &remote,
@@ -249,7 +279,14 @@ fn push_respects_config_excludes() {
     std::fs::write(dir.path().join("secrets/key.pem"), "key").unwrap();
     std::fs::write(dir.path().join("normal.txt"), "ok").unwrap();
 
-    sync::sync_push(&runner, &config, &session, dir.path(), false).unwrap();
+    sync::sync_push(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
 
     assert!(!remote_file_exists(
         &remote,
@@ -284,7 +321,14 @@ fn push_excludes_claude_dir() {
     std::fs::create_dir_all(dir.path().join(".claude")).unwrap();
     std::fs::write(dir.path().join(".claude/settings.json"), "{}").unwrap();
 
-    sync::sync_push(&runner, &config, &session, dir.path(), false).unwrap();
+    sync::sync_push(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
 
     // Nothing under .claude/ should be synced
     assert!(!remote_file_exists(
@@ -351,7 +395,14 @@ fn git_only_push_pull_round_trip() {
     ensure_remote_session_dir(&remote, &session);
 
     std::fs::write(dir.path().join("hello.txt"), "from git-only").unwrap();
-    sync::sync_push(&runner, &config, &session, dir.path(), false).unwrap();
+    sync::sync_push(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
 
     let content = read_remote_file(&remote, &format!("{}/hello.txt", remote_dir(&session)));
     assert_eq!(content.as_deref(), Some("from git-only"));
@@ -361,7 +412,14 @@ fn git_only_push_pull_round_trip() {
         &format!("{}/hello.txt", remote_dir(&session)),
         "modified-remote",
     );
-    sync::sync_pull(&runner, &config, &session, dir.path(), false).unwrap();
+    sync::sync_pull(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
     let local = std::fs::read_to_string(dir.path().join("hello.txt")).unwrap();
     assert_eq!(local, "modified-remote");
 }
@@ -384,7 +442,14 @@ fn pull_files_appear_locally() {
     ensure_remote_session_dir(&remote, &session);
 
     // Push first to create remote dir
-    sync::sync_push(&runner, &config, &session, dir.path(), false).unwrap();
+    sync::sync_push(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
 
     // Create a file on the remote
     write_remote_file(
@@ -393,7 +458,14 @@ fn pull_files_appear_locally() {
         "from remote",
     );
 
-    sync::sync_pull(&runner, &config, &session, dir.path(), false).unwrap();
+    sync::sync_pull(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
 
     let content = std::fs::read_to_string(dir.path().join("remote-file.txt")).unwrap();
     assert_eq!(content, "from remote");
@@ -415,14 +487,28 @@ fn pull_deletes_propagate() {
     // Push two files
     std::fs::write(dir.path().join("keep.txt"), "keep").unwrap();
     std::fs::write(dir.path().join("remove.txt"), "remove").unwrap();
-    sync::sync_push(&runner, &config, &session, dir.path(), false).unwrap();
+    sync::sync_push(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
 
     // Delete one on remote
     runner
         .run_ssh(&remote, &format!("rm {}/remove.txt", remote_dir(&session)))
         .unwrap();
 
-    sync::sync_pull(&runner, &config, &session, dir.path(), false).unwrap();
+    sync::sync_pull(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
 
     assert!(dir.path().join("keep.txt").exists());
     assert!(!dir.path().join("remove.txt").exists());
@@ -444,19 +530,40 @@ fn pull_keeps_gitignored_relocal_toml_across_repeated_pulls() {
     std::fs::write(dir.path().join(".gitignore"), "relocal.toml\n").unwrap();
     assert!(dir.path().join("relocal.toml").exists());
 
-    sync::sync_push(&runner, &config, &session, dir.path(), false).unwrap();
+    sync::sync_push(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
     assert!(!remote_file_exists(
         &remote,
         &format!("{}/relocal.toml", remote_dir(&session))
     ));
 
-    sync::sync_pull(&runner, &config, &session, dir.path(), false).unwrap();
+    sync::sync_pull(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
     assert!(
         dir.path().join("relocal.toml").exists(),
         "first pull must not delete local relocal.toml"
     );
 
-    sync::sync_pull(&runner, &config, &session, dir.path(), false).unwrap();
+    sync::sync_pull(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
     assert!(
         dir.path().join("relocal.toml").exists(),
         "second pull must also preserve local relocal.toml"
@@ -477,7 +584,14 @@ fn pull_excludes_claude_dir() {
     ensure_remote_session_dir(&remote, &session);
 
     // Push to create remote dir
-    sync::sync_push(&runner, &config, &session, dir.path(), false).unwrap();
+    sync::sync_push(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
 
     // Create .claude/ content on remote
     write_remote_file(
@@ -486,7 +600,14 @@ fn pull_excludes_claude_dir() {
         "{\"hooks\":{}}",
     );
 
-    sync::sync_pull(&runner, &config, &session, dir.path(), false).unwrap();
+    sync::sync_pull(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
 
     // .claude/ content should NOT be pulled
     assert!(!dir.path().join(".claude/settings.json").exists());
@@ -510,7 +631,15 @@ fn setup_creates_dir_and_pushes() {
 
     std::fs::write(dir.path().join("data.txt"), "hello").unwrap();
 
-    daemon::daemon_setup(&runner, &config, &session, dir.path(), false).unwrap();
+    daemon::daemon_setup(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        false,
+        DaemonOptions::default(),
+    )
+    .unwrap();
 
     // Remote dir exists with pushed data
     assert!(remote_file_exists(
@@ -529,10 +658,18 @@ fn destroy_removes_dir() {
     let runner = ProcessRunner::default();
 
     // Setup first
-    daemon::daemon_setup(&runner, &config, &session, dir.path(), false).unwrap();
+    daemon::daemon_setup(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        false,
+        DaemonOptions::default(),
+    )
+    .unwrap();
 
     // Destroy (no confirm in test)
-    destroy::run(&runner, &config, &session, false, false).unwrap();
+    destroy::run(&runner, &config, &session, DestroyOptions::default()).unwrap();
 
     assert!(!remote_file_exists(&remote, &remote_dir(&session)));
 }
@@ -554,7 +691,15 @@ fn background_sync_pulls_remote_changes() {
     let runner = ProcessRunner::default();
 
     // Setup: push initial state
-    daemon::daemon_setup(&runner, &config, &session, dir.path(), false).unwrap();
+    daemon::daemon_setup(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        false,
+        DaemonOptions::default(),
+    )
+    .unwrap();
 
     // Start background sync loop
     let sidecar_runner: Arc<dyn CommandRunner + Send + Sync> = Arc::new(ProcessRunner::default());
@@ -600,7 +745,15 @@ fn background_sync_shutdown_is_prompt() {
     };
     let runner = ProcessRunner::default();
 
-    daemon::daemon_setup(&runner, &config, &session, dir.path(), false).unwrap();
+    daemon::daemon_setup(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        false,
+        DaemonOptions::default(),
+    )
+    .unwrap();
 
     let sidecar_runner: Arc<dyn CommandRunner + Send + Sync> = Arc::new(ProcessRunner::default());
     let mut sidecar = Sidecar::start(
@@ -644,7 +797,9 @@ fn daemon_spawns_and_client_connects() {
         &session,
         &remote,
         dir.path(),
+        None,
         0,
+        DaemonOptions::default(),
         Some(relocal_bin().as_ref()),
     )
     .unwrap();
@@ -693,7 +848,9 @@ fn daemon_second_client_reuses_existing() {
         &session,
         &remote,
         dir.path(),
+        None,
         0,
+        DaemonOptions::default(),
         Some(relocal_bin().as_ref()),
     )
     .unwrap();
@@ -704,7 +861,9 @@ fn daemon_second_client_reuses_existing() {
         &session,
         &remote,
         dir.path(),
+        None,
         0,
+        DaemonOptions::default(),
         Some(relocal_bin().as_ref()),
     )
     .unwrap();
@@ -747,7 +906,9 @@ fn daemon_does_final_pull_on_last_disconnect() {
         &session,
         &remote,
         dir.path(),
+        None,
         0,
+        DaemonOptions::default(),
         Some(relocal_bin().as_ref()),
     )
     .unwrap();
@@ -844,7 +1005,15 @@ fn status_reports_correct_info() {
     assert!(!check.status.success());
 
     // After setup: dir should exist
-    daemon::daemon_setup(&runner, &config, &session, dir.path(), false).unwrap();
+    daemon::daemon_setup(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        false,
+        DaemonOptions::default(),
+    )
+    .unwrap();
 
     let check = runner
         .run_ssh(&remote, &ssh::check_work_dir_exists(&session))
@@ -912,7 +1081,7 @@ fn nuke_removes_everything() {
         .unwrap();
 
     // Nuke (no confirm)
-    nuke::run(&runner, &config, false).unwrap();
+    nuke::run(&runner, &config, false, false).unwrap();
 
     assert!(!remote_file_exists(&remote, "~/relocal"));
 }
@@ -941,7 +1110,14 @@ fn localhost_push_pull_roundtrip() {
     ensure_remote_session_dir(&remote, &session);
 
     // Push
-    sync::sync_push(&runner, &config, &session, dir.path(), false).unwrap();
+    sync::sync_push(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
 
     // Verify on remote
     let content =
@@ -962,7 +1138,14 @@ fn localhost_push_pull_roundtrip() {
     );
 
     // Pull
-    sync::sync_pull(&runner, &config, &session, dir.path(), false).unwrap();
+    sync::sync_pull(
+        &runner,
+        &config,
+        &session,
+        dir.path(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
 
     // Verify locally
     assert_eq!(